@@ -1,12 +1,24 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
-use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind};
+use chrono::Timelike;
+use crossterm::event::{Event, EventStream};
 use log::debug;
-use ratatui::{DefaultTerminal, Frame};
+use ratatui::{
+    Frame, Terminal,
+    backend::Backend,
+    layout::{Constraint, Layout},
+    widgets::Widget,
+};
 use ratatui_image::picker::{Picker, ProtocolType};
-use tokio::sync::{
-    mpsc::{self, Receiver, Sender},
-    oneshot,
+use tokio::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        oneshot,
+    },
+    time::Instant,
 };
 use tokio_stream::StreamExt;
 
@@ -15,13 +27,26 @@ use crate::component::debug::DebugComponent;
 
 use crate::{
     component::{
-        Component, postdetail::PostDetailComponent, postlist::PostlistComponent,
-        sublist::SublistComponent,
+        Component,
+        confirm_popup::{ConfirmPopup, ConfirmPopupEvent},
+        help_popup::{HelpPopup, HelpPopupEvent},
+        livethread::{LiveThreadComponent, LiveThreadEvent},
+        modmail::{ModmailComponent, ModmailEvent},
+        modqueue::{ModQueueComponent, ModQueueEvent},
+        postdetail::{PostDetailComponent, PostDetailEvent},
+        postlist::{PostlistComponent, PostlistEvent},
+        sub_prompt::{SubPrompt, SubPromptEvent},
+        sublist::{SublistComponent, SublistEvent},
     },
-    config::Config,
-    model::post::Post,
+    config::{Config, ImageProtocol},
+    format::Clock,
+    keymap::{Action, Keymap},
+    model::{post::Post, subreddit},
     ngored_error::NgoredError,
-    reddit_api::RedditApi,
+    reddit_api::{FixtureMode, HttpRedditApi, RedditApi},
+    state_store::StateStore,
+    theme::Theme,
+    widget::{diagnostics_widget::DiagnosticsWidget, status_line_widget::StatusLineWidget},
 };
 
 pub enum AppEvent {
@@ -30,16 +55,40 @@ pub enum AppEvent {
     DrawWithCallback(oneshot::Sender<()>),
     #[cfg(debug_assertions)]
     ToggleShowDebug,
+    ToggleDiagnostics,
     OpenPostList(String),
-    ClosePostList,
+    /// Whether the sub typed in the jump prompt exists, with its name as reddit spells it
+    SubJumpChecked {
+        input: String,
+        result: Result<String, NgoredError>,
+    },
     OpenPostDetail(Post),
-    ClosePostDetail,
+    /// Open a post permalink, or a comment permalink with its parent comments, in the post detail
+    OpenPermalink(String),
+    OpenLiveThread(String),
+    OpenModQueue,
+    OpenModmail,
+    /// Close the current screen and go back to the one it was opened from
+    Back,
+    Sublist(SublistEvent),
+    Postlist(PostlistEvent),
+    PostDetail(PostDetailEvent),
+    LiveThread(LiveThreadEvent),
+    ModQueue(ModQueueEvent),
+    Modmail(ModmailEvent),
 }
 
+/// Minimum time between two redraws in reduce motion mode
+const MIN_DRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, PartialEq)]
 pub enum Screen {
     Sublist,
     Postlist,
     PostDetail,
+    LiveThread,
+    ModQueue,
+    Modmail,
 }
 
 pub struct App {
@@ -48,44 +97,134 @@ pub struct App {
     #[cfg(debug_assertions)]
     debug_component: DebugComponent,
     running: bool,
+    show_diagnostics: bool,
+    keymap: Keymap,
+    /// Render time of the last frame, shown by the diagnostics overlay
+    last_frame_time: Duration,
     app_event_sender: Sender<AppEvent>,
     app_event_receiver: Receiver<AppEvent>,
-    current_screen: Screen,
+    /// Batch `AppEvent::Draw` so the screen changes at most every `MIN_DRAW_INTERVAL`
+    reduce_motion: bool,
+    draw_pending: bool,
+    last_draw: Instant,
+    /// `status_line` format from the config, the line isn't shown without one
+    status_line: Option<String>,
+    /// Screens in the order they were opened, the last one is shown. Never empty
+    screens: Vec<Screen>,
     sublist: SublistComponent,
     postlist: PostlistComponent,
     postdetail: PostDetailComponent,
+    livethread: LiveThreadComponent,
+    modqueue: ModQueueComponent,
+    modmail: ModmailComponent,
+    /// Prompt opened by `JumpToSub` over the current screen
+    sub_prompt: Option<SubPrompt>,
+    /// Keys of the current screen, opened by `Help`
+    help_popup: Option<HelpPopup>,
+    /// Ask before `Quit` quits
+    confirm: bool,
+    /// Handed to the popups opened from here
+    theme: Theme,
+    quit_popup: Option<ConfirmPopup>,
+    /// Subs opened from the prompt this session, offered as completions with the sublist's
+    jumped_subs: Vec<String>,
+    /// Sub opened from the prompt, added to `jumped_subs` once its posts load
+    jumping_to: Option<String>,
+    /// Looks up the subs typed in the prompt
+    reddit_api: Arc<dyn RedditApi>,
+    /// Handed to the components, fixed by the snapshot tests
+    clock: Clock,
 }
 
 impl App {
-    pub fn new() -> Self {
-        let reddit_api = Arc::new(RedditApi::new());
-        let config = Config::load();
+    pub fn new(fixture_mode: Option<FixtureMode>) -> Result<Self, NgoredError> {
+        let config = Config::load()?;
+        let reddit_api: Arc<dyn RedditApi> =
+            Arc::new(HttpRedditApi::new(config.account.clone(), fixture_mode));
+        let picker = Self::image_picker(config.image_protocol);
+        Ok(Self::from_parts(
+            config,
+            reddit_api,
+            StateStore::load()?,
+            picker,
+            Clock::System,
+        ))
+    }
+
+    /// Build the app around the given dependencies instead of the user's files, terminal and
+    /// clock, used by the snapshot tests
+    pub fn from_parts(
+        config: Config,
+        reddit_api: Arc<dyn RedditApi>,
+        state_store: StateStore,
+        picker: Picker,
+        clock: Clock,
+    ) -> Self {
+        let state_store = Arc::new(RwLock::new(state_store));
         let (sender, receiver) = mpsc::channel(100);
-        let mut picker = Picker::from_query_stdio().unwrap();
-        if picker.protocol_type() != ProtocolType::Kitty {
-            picker = Picker::from_fontsize((8, 12));
-        }
         let picker = Arc::new(picker);
+        let mut sublist = SublistComponent::new(
+            reddit_api.clone(),
+            state_store.clone(),
+            &config,
+            sender.clone(),
+        );
+        // The first sub is selected from the start, not by a key
+        sublist.load_selected_about();
         Self {
             #[cfg(debug_assertions)]
             debug_component: DebugComponent::new(),
             #[cfg(debug_assertions)]
             show_debug: false,
             running: true,
-            current_screen: Screen::Sublist,
-            sublist: SublistComponent::new(config.subs, sender.clone()),
-            postlist: PostlistComponent::new(reddit_api.clone(), sender.clone()),
+            show_diagnostics: false,
+            keymap: Keymap::new(&config),
+            last_frame_time: Duration::default(),
+            reduce_motion: config.reduce_motion,
+            draw_pending: false,
+            last_draw: Instant::now(),
+            status_line: config.status_line.clone().filter(|v| !v.trim().is_empty()),
+            screens: vec![Screen::Sublist],
+            sublist,
+            postlist: PostlistComponent::new(
+                reddit_api.clone(),
+                picker.clone(),
+                state_store.clone(),
+                &config,
+                clock,
+                sender.clone(),
+            ),
             postdetail: PostDetailComponent::new(
                 reddit_api.clone(),
                 picker.clone(),
+                state_store.clone(),
+                &config,
+                clock,
                 sender.clone(),
             ),
+            livethread: LiveThreadComponent::new(
+                reddit_api.clone(),
+                &config,
+                clock,
+                sender.clone(),
+            ),
+            modqueue: ModQueueComponent::new(reddit_api.clone(), &config, clock, sender.clone()),
+            modmail: ModmailComponent::new(reddit_api.clone(), &config, clock, sender.clone()),
+            sub_prompt: None,
+            help_popup: None,
+            confirm: config.confirm.unwrap_or(true),
+            theme: Theme::new(&config),
+            quit_popup: None,
+            jumped_subs: Vec::default(),
+            jumping_to: None,
+            reddit_api,
+            clock,
             app_event_sender: sender,
             app_event_receiver: receiver,
         }
     }
 
-    pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), NgoredError> {
+    pub async fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), NgoredError> {
         let mut events = EventStream::new();
         terminal.draw(|f| self.draw(f))?;
 
@@ -100,9 +239,11 @@ impl App {
             tokio::select! {
                 Some(Ok(event)) = events.next() => self.handle_event(&event).await?,
                 Some(app_event) = self.app_event_receiver.recv() => self.handle_app_event(app_event, terminal).await?,
+                _ = tokio::time::sleep_until(self.last_draw + MIN_DRAW_INTERVAL), if self.draw_pending => self.draw_now(terminal)?,
+                _ = tokio::time::sleep(Self::until_next_minute(self.clock)), if self.shows_clock() => self.draw_now(terminal)?,
                 _ = interval.tick() => {
                     if self.show_debug {
-                        terminal.draw(|f| self.debug_component.draw(f))?;
+                        terminal.draw(|f| self.debug_component.draw(f, f.area()))?;
                     }
                 }
             }
@@ -113,23 +254,54 @@ impl App {
             tokio::select! {
                 Some(Ok(event)) = events.next() => self.handle_event(&event).await?,
                 Some(app_event) = self.app_event_receiver.recv() => self.handle_app_event(app_event, terminal).await?,
+                _ = tokio::time::sleep_until(self.last_draw + MIN_DRAW_INTERVAL), if self.draw_pending => self.draw_now(terminal)?,
+                _ = tokio::time::sleep(Self::until_next_minute(self.clock)), if self.shows_clock() => self.draw_now(terminal)?,
             }
         }
+        self.sublist.save_pending();
+        Ok(())
+    }
+
+    fn draw_now<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), NgoredError> {
+        self.draw_pending = false;
+        self.last_draw = Instant::now();
+        terminal.draw(|frame| self.draw(frame))?;
+        self.last_frame_time = self.last_draw.elapsed();
         Ok(())
     }
 
-    async fn handle_app_event(
+    /// Handle app events until none arrives for `idle`, then draw. Lets the snapshot tests wait
+    /// for the loader tasks started by a key
+    #[cfg(test)]
+    pub async fn settle<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        idle: Duration,
+    ) -> Result<(), NgoredError> {
+        while let Ok(Some(app_event)) =
+            tokio::time::timeout(idle, self.app_event_receiver.recv()).await
+        {
+            self.handle_app_event(app_event, terminal).await?;
+        }
+        self.draw_now(terminal)
+    }
+
+    async fn handle_app_event<B: Backend>(
         &mut self,
         app_event: AppEvent,
-        terminal: &mut DefaultTerminal,
+        terminal: &mut Terminal<B>,
     ) -> Result<(), NgoredError> {
         match app_event {
             AppEvent::Quit => self.running = false,
             AppEvent::Draw => {
-                terminal.draw(|frame| self.draw(frame))?;
+                if self.reduce_motion && self.last_draw.elapsed() < MIN_DRAW_INTERVAL {
+                    self.draw_pending = true;
+                } else {
+                    self.draw_now(terminal)?;
+                }
             }
             AppEvent::DrawWithCallback(sender) => {
-                terminal.draw(|frame| self.draw(frame))?;
+                self.draw_now(terminal)?;
                 sender.send(()).unwrap();
             }
             #[cfg(debug_assertions)]
@@ -137,72 +309,295 @@ impl App {
                 self.show_debug = !self.show_debug;
                 self.app_event_sender.send(AppEvent::Draw).await?;
             }
+            AppEvent::ToggleDiagnostics => {
+                self.show_diagnostics = !self.show_diagnostics;
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
             AppEvent::OpenPostList(sub) => {
                 self.postlist.load(sub);
-                self.current_screen = Screen::Postlist;
+                self.push_screen(Screen::Postlist);
                 self.app_event_sender.send(AppEvent::Draw).await?;
             }
-            AppEvent::ClosePostList => {
-                self.current_screen = Screen::Sublist;
+            AppEvent::SubJumpChecked { input, result } => {
+                if let Some(sub_prompt) = self.sub_prompt.as_mut()
+                    && let Some(sub) = sub_prompt.checked(&input, result)
+                {
+                    self.sub_prompt = None;
+                    self.jumping_to = Some(sub.clone());
+                    self.app_event_sender
+                        .send(AppEvent::OpenPostList(sub))
+                        .await?;
+                }
                 self.app_event_sender.send(AppEvent::Draw).await?;
             }
             AppEvent::OpenPostDetail(post) => {
                 self.postdetail.load(post);
-                self.current_screen = Screen::PostDetail;
+                self.push_screen(Screen::PostDetail);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::OpenPermalink(permalink) => {
+                self.postdetail.load_permalink(&permalink);
+                self.push_screen(Screen::PostDetail);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::OpenLiveThread(thread_id) => {
+                self.livethread.load(thread_id);
+                self.push_screen(Screen::LiveThread);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::OpenModQueue => {
+                self.modqueue.load();
+                self.push_screen(Screen::ModQueue);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::OpenModmail => {
+                self.modmail.load();
+                self.push_screen(Screen::Modmail);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::Back => {
+                self.pop_screen();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::Sublist(event) => {
+                self.sublist.handle_loaded(event);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::Postlist(event) => {
+                if let PostlistEvent::PostsLoaded { sub, items: Ok(_) } = &event
+                    && self.jumping_to.as_ref() == Some(sub)
+                {
+                    self.jumping_to = None;
+                    if !self.jumped_subs.contains(sub) {
+                        self.jumped_subs.push(sub.clone());
+                    }
+                }
+                self.postlist.handle_loaded(event);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::PostDetail(event) => {
+                self.postdetail.handle_loaded(event);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::LiveThread(event) => {
+                self.livethread.handle_loaded(event);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            AppEvent::ModQueue(event) => {
+                self.modqueue.handle_loaded(event);
                 self.app_event_sender.send(AppEvent::Draw).await?;
             }
-            AppEvent::ClosePostDetail => {
-                self.current_screen = Screen::Postlist;
+            AppEvent::Modmail(event) => {
+                self.modmail.handle_loaded(event);
                 self.app_event_sender.send(AppEvent::Draw).await?;
             }
         };
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
-        match self.current_screen {
-            Screen::Sublist => self.sublist.draw(frame),
-            Screen::Postlist => self.postlist.draw(frame),
-            Screen::PostDetail => self.postdetail.draw(frame),
+    /// Kitty when the terminal supports it, otherwise halfblocks (or iTerm2 when running in tmux
+    /// inside a terminal that hints at it). Inside tmux ratatui-image turns on passthrough and
+    /// wraps the escape sequences itself, but the terminal query is more likely to fail there.
+    /// `image_protocol` from the config overrides the detection
+    fn image_picker(image_protocol: Option<ImageProtocol>) -> Picker {
+        let query = Picker::from_query_stdio();
+        if let Err(err) = &query {
+            debug!("Terminal graphics query failed: {:?}", err);
         }
+        let protocol_type = match image_protocol {
+            Some(ImageProtocol::Kitty) => Some(ProtocolType::Kitty),
+            Some(ImageProtocol::Sixel) => Some(ProtocolType::Sixel),
+            Some(ImageProtocol::Iterm2) => Some(ProtocolType::Iterm2),
+            Some(ImageProtocol::Halfblocks) => Some(ProtocolType::Halfblocks),
+            Some(ImageProtocol::None) | None => None,
+        };
+        let picker = match (query, protocol_type) {
+            // Keep the queried font size, it's more accurate than the fallback
+            (Ok(mut picker), Some(protocol_type)) => {
+                picker.set_protocol_type(protocol_type);
+                picker
+            }
+            (Err(_), Some(protocol_type)) => {
+                let mut picker = Picker::from_fontsize((8, 12));
+                picker.set_protocol_type(protocol_type);
+                picker
+            }
+            (Ok(picker), None) if picker.protocol_type() == ProtocolType::Kitty => picker,
+            (_, None) => Picker::from_fontsize((8, 12)),
+        };
+        debug!("Image protocol: {:?}", picker.protocol_type());
+        picker
     }
 
-    async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
-        match event {
-            Event::Key(KeyEvent {
-                kind: KeyEventKind::Press,
-                code: KeyCode::Char('q'),
-                ..
-            }) => self.app_event_sender.send(AppEvent::Quit).await?,
-            #[cfg(debug_assertions)]
-            Event::Key(KeyEvent {
-                kind: KeyEventKind::Press,
-                code: KeyCode::Char('`'),
-                ..
-            }) => {
-                self.app_event_sender
-                    .send(AppEvent::ToggleShowDebug)
-                    .await?
-            }
-            _ => {
-                #[cfg(debug_assertions)]
-                if self.show_debug {
-                    self.debug_component.handle_event(event).await?;
-                } else {
-                    match self.current_screen {
-                        Screen::Sublist => self.sublist.handle_event(event).await?,
-                        Screen::Postlist => self.postlist.handle_event(event).await?,
-                        Screen::PostDetail => self.postdetail.handle_event(event).await?,
-                    };
+    /// Each screen has a single component, so opening a screen already in the stack goes back to
+    /// it instead of stacking a second copy whose content would replace the first
+    fn push_screen(&mut self, screen: Screen) {
+        match self.screens.iter().position(|v| *v == screen) {
+            Some(index) => {
+                // A post left this way isn't one to go back to from the next post opened
+                if self.screens[index + 1..].contains(&Screen::PostDetail) {
+                    self.postdetail.close();
                 }
+                self.screens.truncate(index + 1);
+            }
+            None => self.screens.push(screen),
+        }
+    }
+
+    fn pop_screen(&mut self) {
+        if self.screens.len() > 1 {
+            self.screens.pop();
+        }
+    }
+
+    fn current_component(&mut self) -> &mut dyn Component {
+        match self.screens.last().copied().unwrap_or(Screen::Sublist) {
+            Screen::Sublist => &mut self.sublist,
+            Screen::Postlist => &mut self.postlist,
+            Screen::PostDetail => &mut self.postdetail,
+            Screen::LiveThread => &mut self.livethread,
+            Screen::ModQueue => &mut self.modqueue,
+            Screen::Modmail => &mut self.modmail,
+        }
+    }
+
+    /// Every screen's component, shown or not
+    fn components(&mut self) -> [&mut dyn Component; 6] {
+        [
+            &mut self.sublist,
+            &mut self.postlist,
+            &mut self.postdetail,
+            &mut self.livethread,
+            &mut self.modqueue,
+            &mut self.modmail,
+        ]
+    }
+
+    /// The component keys go to, the debug view when it's shown
+    fn focused_component(&mut self) -> &mut dyn Component {
+        #[cfg(debug_assertions)]
+        if self.show_debug {
+            return &mut self.debug_component;
+        }
+        self.current_component()
+    }
+
+    /// Whether the status line has a clock to keep up to date
+    fn shows_clock(&self) -> bool {
+        self.status_line
+            .as_deref()
+            .is_some_and(StatusLineWidget::shows_clock)
+    }
+
+    fn until_next_minute(clock: Clock) -> Duration {
+        Duration::from_secs(60 - clock.local_now().second() as u64)
+    }
 
-                #[cfg(not(debug_assertions))]
-                match self.current_screen {
-                    Screen::Sublist => self.sublist.handle_event(event).await?,
-                    Screen::Postlist => self.postlist.handle_event(event).await?,
-                    Screen::PostDetail => self.postdetail.handle_event(event).await?,
-                };
+    fn draw(&mut self, frame: &mut Frame) {
+        let area = match self.status_line.clone() {
+            Some(status_line) => {
+                let [area, status_area] =
+                    Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
+                        .areas(frame.area());
+                let info = self.current_component().status_info();
+                StatusLineWidget::new(&status_line, info, self.clock)
+                    .render(status_area, frame.buffer_mut());
+                area
+            }
+            None => frame.area(),
+        };
+        self.current_component().draw(frame, area);
+        if let Some(sub_prompt) = self.sub_prompt.as_mut() {
+            sub_prompt.draw(frame, area);
+        }
+        if let Some(help_popup) = self.help_popup.as_mut() {
+            help_popup.draw(frame, area);
+        }
+        if let Some(quit_popup) = self.quit_popup.as_ref() {
+            quit_popup.draw(frame, area);
+        }
+        if self.show_diagnostics {
+            let area = frame.area();
+            DiagnosticsWidget::new(self.last_frame_time).render(area, frame.buffer_mut());
+        }
+    }
+
+    /// Keys go through the keymap of the focused component, raw events go to its text input
+    pub async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
+        if let Event::Resize(..) = event {
+            for component in self.components() {
+                component.resized();
+            }
+        }
+        let app_event_sender = self.app_event_sender.clone();
+        if let Some(quit_popup) = self.quit_popup.as_mut() {
+            match quit_popup.handle_event(event) {
+                ConfirmPopupEvent::None => {}
+                ConfirmPopupEvent::Cancel => self.quit_popup = None,
+                ConfirmPopupEvent::Confirm => app_event_sender.send(AppEvent::Quit).await?,
+            }
+            app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
+        if let Some(help_popup) = self.help_popup.as_mut() {
+            if let HelpPopupEvent::Close = help_popup.handle_event(event) {
+                self.help_popup = None;
+            }
+            app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
+        if let Some(sub_prompt) = self.sub_prompt.as_mut() {
+            match sub_prompt.handle_event(event) {
+                SubPromptEvent::None => {}
+                SubPromptEvent::Cancel => self.sub_prompt = None,
+                SubPromptEvent::Submit(name) => {
+                    tokio::spawn({
+                        let reddit_api = self.reddit_api.clone();
+                        let app_event_sender = app_event_sender.clone();
+                        async move {
+                            let result = subreddit::look_up(&*reddit_api, &name).await;
+                            app_event_sender
+                                .send(AppEvent::SubJumpChecked {
+                                    input: name,
+                                    result,
+                                })
+                                .await
+                                .unwrap();
+                        }
+                    });
+                }
+            }
+            app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
+        let Some(context) = self.focused_component().key_context() else {
+            return self.focused_component().handle_event(event).await;
+        };
+        let Event::Key(key) = event else {
+            return Ok(());
+        };
+        match self.keymap.action(context, key) {
+            Some(Action::Quit) if self.confirm => {
+                self.quit_popup = Some(ConfirmPopup::new("Quit ngored?"));
+                app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Some(Action::Quit) => app_event_sender.send(AppEvent::Quit).await?,
+            #[cfg(debug_assertions)]
+            Some(Action::ToggleDebug) => app_event_sender.send(AppEvent::ToggleShowDebug).await?,
+            Some(Action::ToggleDiagnostics) => {
+                app_event_sender.send(AppEvent::ToggleDiagnostics).await?
+            }
+            Some(Action::JumpToSub) => {
+                let subs = self.sublist.subs().iter().chain(&self.jumped_subs);
+                self.sub_prompt = Some(SubPrompt::new(subs.cloned().collect(), self.theme));
+                app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Some(Action::Help) => {
+                self.help_popup = Some(HelpPopup::new(&self.keymap, context, self.theme));
+                app_event_sender.send(AppEvent::Draw).await?;
             }
+            Some(action) => self.focused_component().handle_action(action).await?,
+            None => {}
         }
         Ok(())
     }
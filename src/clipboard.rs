@@ -0,0 +1,24 @@
+use std::io::stdout;
+
+use crossterm::{clipboard::CopyToClipboard, execute};
+use ratatui::{
+    style::{Color, Stylize},
+    text::Line,
+};
+
+use crate::ngored_error::NgoredError;
+
+/// Copy through the terminal with OSC 52, so it also works over ssh. Terminals that don't support
+/// it ignore the sequence
+pub fn copy(text: &str) -> Result<(), NgoredError> {
+    execute!(stdout(), CopyToClipboard::to_clipboard_from(text))?;
+    Ok(())
+}
+
+/// Copy `text` and describe the result for a status line, `what` names what was copied
+pub fn copy_with_status(text: &str, what: &str) -> Line<'static> {
+    match copy(text) {
+        Ok(()) => Line::from(vec!["copied".bold(), format!(" {}", what).into()]),
+        Err(err) => Line::from(err.to_string().fg(Color::Red)),
+    }
+}
@@ -1,22 +1,69 @@
+use async_trait::async_trait;
 use crossterm::event::Event;
-use ratatui::Frame;
+use ratatui::{Frame, layout::Rect};
 
-use crate::ngored_error::NgoredError;
+use crate::{
+    keymap::{Action, KeyContext},
+    ngored_error::NgoredError,
+};
 
 #[cfg(debug_assertions)]
 pub mod debug;
 
+pub mod batch_popup;
+pub mod confirm_popup;
+pub mod help_popup;
+pub mod link_hints;
+pub mod livethread;
+pub mod modmail;
+pub mod modqueue;
 pub mod postdetail;
 pub mod postlist;
+pub mod share_popup;
+pub mod sub_prompt;
 pub mod sublist;
+pub mod user_tag_popup;
 
-pub trait Component {
+/// What a screen knows of the placeholders of the `status_line` format, the ones it doesn't
+/// know are left empty
+#[derive(Default)]
+pub struct StatusInfo {
+    pub sub: Option<String>,
+    pub sort: Option<String>,
+    /// The selected item, from 1, and how many there are
+    pub position: Option<(usize, usize)>,
+    /// Posts never opened, or comments new since the last visit
+    pub unread: Option<usize>,
+}
+
+/// A screen of the app. `App` keeps one component per screen and hands the one in front out as
+/// `&mut dyn Component`, so keys and drawing go through here without matching on the screen
+#[async_trait]
+pub trait Component: Send {
+    /// Key bindings in effect, `None` while a text input or popup takes the raw events
+    fn key_context(&self) -> Option<KeyContext>;
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        let _ = action;
+        Ok(())
+    }
+
+    /// Raw terminal events, only sent while `key_context` is `None`
     async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
         let _ = event;
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
-        let _ = frame;
+    /// Draw into `area`, the terminal but the status line when it's shown
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let _ = (frame, area);
+    }
+
+    /// The terminal changed size, drop whatever was laid out for the old one
+    fn resized(&mut self) {}
+
+    /// What the status line shows about the screen
+    fn status_info(&self) -> StatusInfo {
+        StatusInfo::default()
     }
 }
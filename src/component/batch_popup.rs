@@ -0,0 +1,91 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, List, ListState, StatefulWidget, Widget},
+};
+
+use crate::{keymap::Action, theme::Theme};
+
+pub enum BatchPopupEvent {
+    None,
+    Close,
+    /// Apply the action to every marked post
+    Apply(Action),
+}
+
+/// Menu of what can be done to the marked posts at once, each entry also has a key of its own
+pub struct BatchPopup {
+    count: usize,
+    entries: Vec<(&'static str, &'static str, Action)>,
+    list_state: ListState,
+    theme: Theme,
+}
+
+impl BatchPopup {
+    pub fn new(count: usize, theme: Theme) -> Self {
+        Self {
+            count,
+            entries: vec![
+                ("o", "Open all in browser", Action::OpenInBrowser),
+                ("r", "Mark read", Action::MarkRead),
+                ("x", "Hide", Action::Hide),
+                ("a", "Read later", Action::ReadLater),
+            ],
+            list_state: ListState::default().with_selected(Some(0)),
+            theme,
+        }
+    }
+
+    pub fn handle_action(&mut self, action: Action) -> BatchPopupEvent {
+        match action {
+            Action::Back => BatchPopupEvent::Close,
+            Action::Down => {
+                self.list_state.select_next();
+                BatchPopupEvent::None
+            }
+            Action::Up => {
+                self.list_state.select_previous();
+                BatchPopupEvent::None
+            }
+            Action::Open => self
+                .list_state
+                .selected()
+                .and_then(|i| self.entries.get(i))
+                .map_or(BatchPopupEvent::None, |(_, _, action)| {
+                    BatchPopupEvent::Apply(*action)
+                }),
+            action if self.entries.iter().any(|(_, _, v)| *v == action) => {
+                BatchPopupEvent::Apply(action)
+            }
+            _ => BatchPopupEvent::None,
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let [center_vertical] =
+            Layout::vertical([Constraint::Length(self.entries.len() as u16 + 2)])
+                .flex(Flex::Center)
+                .areas(area);
+        let [center] = Layout::horizontal([Constraint::Length(30)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        let buf = frame.buffer_mut();
+        Clear.render(center, buf);
+
+        let items = self
+            .entries
+            .iter()
+            .map(|(key, label, _)| Line::from(vec![format!("[{}] ", key).bold(), (*label).into()]));
+        let list = List::new(items)
+            .highlight_style(self.theme.selected_style())
+            .highlight_symbol(self.theme.highlight_symbol())
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title(format!("{} marked posts", self.count)),
+            );
+        StatefulWidget::render(list, center, buf, &mut self.list_state);
+    }
+}
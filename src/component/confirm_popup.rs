@@ -0,0 +1,68 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Paragraph, Widget},
+};
+
+use crate::text::{self, WrapOptions};
+
+pub enum ConfirmPopupEvent {
+    None,
+    Cancel,
+    Confirm,
+}
+
+/// Yes or no question asked before an action that can't be taken back
+pub struct ConfirmPopup {
+    question: String,
+}
+
+impl ConfirmPopup {
+    pub fn new(question: impl Into<String>) -> Self {
+        Self {
+            question: question.into(),
+        }
+    }
+
+    /// Only `y` or Enter confirm, any other key is a no
+    pub fn handle_event(&mut self, event: &Event) -> ConfirmPopupEvent {
+        let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return ConfirmPopupEvent::None;
+        };
+        match code {
+            KeyCode::Char('y' | 'Y') | KeyCode::Enter => ConfirmPopupEvent::Confirm,
+            _ => ConfirmPopupEvent::Cancel,
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        // Room for the hint in the bottom border even with a short question
+        let width = (text::width(&self.question) + 4).clamp(30, area.width.max(30));
+        // Our own question, the wrapping preferences are for what people wrote
+        let lines = text::wrap(&self.question, width - 4, WrapOptions::default());
+        let [center_vertical] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [center] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        let buf = frame.buffer_mut();
+        Clear.render(center, buf);
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .padding(Padding::horizontal(1))
+                    .title_bottom(Line::from("[y] yes • [n] no").bold().right_aligned()),
+            )
+            .render(center, buf);
+    }
+}
@@ -1,10 +1,15 @@
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use async_trait::async_trait;
 use log::LevelFilter;
 use ratatui::Frame;
+use ratatui::layout::Rect;
 use ratatui::text::Line;
 use tui_logger::{LogFormatter, TuiLoggerLevelOutput, TuiWidgetEvent, TuiWidgetState};
 
-use crate::{component::Component, ngored_error::NgoredError};
+use crate::{
+    component::Component,
+    keymap::{Action, KeyContext},
+    ngored_error::NgoredError,
+};
 
 pub struct DebugFormatter;
 
@@ -37,29 +42,27 @@ impl DebugComponent {
     }
 }
 
+#[async_trait]
 impl Component for DebugComponent {
-    async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
-        match event {
-            Event::Key(KeyEvent {
-                code,
-                kind: KeyEventKind::Press,
-                ..
-            }) => match code {
-                KeyCode::Char('j') => self.state.transition(TuiWidgetEvent::NextPageKey),
-                KeyCode::Char('k') => self.state.transition(TuiWidgetEvent::PrevPageKey),
-                KeyCode::Esc => self.state.transition(TuiWidgetEvent::EscapeKey),
-                _ => {}
-            },
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::Debug)
+    }
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        match action {
+            Action::Down | Action::PageDown => self.state.transition(TuiWidgetEvent::NextPageKey),
+            Action::Up | Action::PageUp => self.state.transition(TuiWidgetEvent::PrevPageKey),
+            // Leaving page mode follows the newest lines again
+            Action::Cancel | Action::Bottom => self.state.transition(TuiWidgetEvent::EscapeKey),
             _ => {}
         }
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
         use ratatui::widgets::{Block, Widget};
         use tui_logger::TuiLoggerWidget;
 
-        let area = frame.area();
         let buf = frame.buffer_mut();
         TuiLoggerWidget::default()
             .block(Block::bordered())
@@ -0,0 +1,124 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    keymap::{KeyContext, Keymap},
+    theme::Theme,
+};
+
+pub enum HelpPopupEvent {
+    None,
+    Close,
+}
+
+/// Cheatsheet of the keys of every screen, the current one first, listed from the keymap itself
+/// so it never goes stale and shows the keys bound in the config
+pub struct HelpPopup {
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+    /// Rows in view at the last draw, the scroll stops once the last line is in view
+    height: u16,
+    theme: Theme,
+}
+
+impl HelpPopup {
+    pub fn new(keymap: &Keymap, context: KeyContext, theme: Theme) -> Self {
+        let [screen, global] = keymap.bindings(context);
+        let mut sections = vec![
+            (context.label(), screen),
+            ("Everywhere".to_string(), global),
+        ];
+        // The global keys are the same everywhere, only the screen's own are listed
+        sections.extend(
+            KeyContext::SCREENS
+                .into_iter()
+                .filter(|v| *v != context)
+                .map(|v| {
+                    let [screen, _] = keymap.bindings(v);
+                    (v.label(), screen)
+                }),
+        );
+        let keys_width = sections
+            .iter()
+            .flat_map(|(_, bindings)| bindings)
+            .map(|(_, keys)| keys.join(", ").chars().count())
+            .max()
+            .unwrap_or_default();
+        let mut lines = Vec::new();
+        for (title, bindings) in sections {
+            if bindings.is_empty() {
+                continue;
+            }
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::from(title).bold());
+            for (action, keys) in bindings {
+                lines.push(Line::from(vec![
+                    format!("  {:<width$}  ", keys.join(", "), width = keys_width).bold(),
+                    action.label().into(),
+                ]));
+            }
+        }
+        Self {
+            lines,
+            scroll: 0,
+            height: 1,
+            theme,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> HelpPopupEvent {
+        let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return HelpPopupEvent::None;
+        };
+        let max_scroll = (self.lines.len() as u16).saturating_sub(self.height);
+        match code {
+            KeyCode::Char('j') | KeyCode::Down => self.scroll = (self.scroll + 1).min(max_scroll),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown => self.scroll = (self.scroll + self.height).min(max_scroll),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.height),
+            _ => return HelpPopupEvent::Close,
+        }
+        HelpPopupEvent::None
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let width = self
+            .lines
+            .iter()
+            .map(|v| v.width() as u16)
+            .max()
+            .unwrap_or_default()
+            + 4;
+        let [center_vertical] = Layout::vertical([Constraint::Length(self.lines.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [center] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        let buf = frame.buffer_mut();
+        Clear.render(center, buf);
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title("Keys")
+            .title_bottom(Line::from("[j/k] scroll • any other key closes").fg(self.theme.muted()));
+        self.height = block.inner(center).height.max(1);
+        Paragraph::new(self.lines.clone())
+            .scroll((self.scroll, 0))
+            .block(block)
+            .render(center, buf);
+    }
+}
@@ -0,0 +1,157 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style, Stylize},
+    text::Span,
+};
+
+use crate::text;
+
+/// Keys labels are made of, the easiest to reach first
+const HINT_KEYS: &str = "asdfghjklqwertyuiopzxcvbnm";
+
+pub enum LinkHintsEvent {
+    None,
+    Cancel,
+    /// The url of the label typed
+    Select(String),
+}
+
+/// Labels over the links, mentions and images in view, typing a label picks its url
+pub struct LinkHints {
+    /// Copy the url picked instead of opening it
+    pub copy: bool,
+    typed: String,
+    /// Label, where it's drawn and its url, found by the last draw
+    hints: Vec<(String, Position, String)>,
+}
+
+impl LinkHints {
+    pub fn new(copy: bool) -> Self {
+        Self {
+            copy,
+            typed: String::default(),
+            hints: Vec::default(),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> LinkHintsEvent {
+        let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return LinkHintsEvent::None;
+        };
+        let KeyCode::Char(c) = code else {
+            return LinkHintsEvent::Cancel;
+        };
+        self.typed.push(c.to_ascii_lowercase());
+        if let Some((_, _, url)) = self.hints.iter().find(|(label, ..)| *label == self.typed) {
+            return LinkHintsEvent::Select(url.clone());
+        }
+        if self
+            .hints
+            .iter()
+            .any(|(label, ..)| label.starts_with(&self.typed))
+        {
+            LinkHintsEvent::None
+        } else {
+            LinkHintsEvent::Cancel
+        }
+    }
+
+    /// Label the starts of `links` found in `area` of the drawn `buf`, and `images` already placed
+    /// on screen, then draw the labels still matching what was typed
+    pub fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        links: &[String],
+        images: Vec<(Position, String)>,
+    ) {
+        let mut targets = images;
+        targets.extend(find_links(area, buf, links));
+        self.hints = hint_labels(targets.len())
+            .into_iter()
+            .zip(targets)
+            .map(|(label, (position, url))| (label, position, url))
+            .collect();
+
+        let style = Style::new().fg(Color::Black).bg(Color::Yellow).bold();
+        for (label, position, _) in &self.hints {
+            if let Some(rest) = label.strip_prefix(self.typed.as_str()) {
+                buf.set_span(
+                    position.x,
+                    position.y,
+                    &Span::styled(rest, style),
+                    area.right() - position.x,
+                );
+            }
+        }
+    }
+}
+
+/// One letter each while there are enough letters, else two letters for all so no label is the
+/// start of another
+fn hint_labels(count: usize) -> Vec<String> {
+    let keys = HINT_KEYS.chars();
+    if count <= HINT_KEYS.len() {
+        keys.take(count).map(String::from).collect()
+    } else {
+        keys.clone()
+            .flat_map(|first| {
+                HINT_KEYS
+                    .chars()
+                    .map(move |second| format!("{first}{second}"))
+            })
+            .take(count)
+            .collect()
+    }
+}
+
+/// Where the urls and mentions of `links` start in `area`. A url wrapped onto the next lines
+/// is only found at its start, the rest of it is matched against `links` by prefix
+fn find_links(area: Rect, buf: &Buffer, links: &[String]) -> Vec<(Position, String)> {
+    let mut found = Vec::new();
+    for y in area.top()..area.bottom() {
+        // The row as text, with the column each byte of it starts at
+        let mut row = String::new();
+        let mut columns = Vec::new();
+        let mut x = area.left();
+        while x < area.right() {
+            let symbol = buf[(x, y)].symbol();
+            columns.extend(std::iter::repeat_n(x, symbol.len()));
+            row.push_str(symbol);
+            // Cells under a wide glyph are left behind by it, skip them
+            x += Span::raw(symbol).width().max(1) as u16;
+        }
+        let mut start = 0;
+        while let Some(offset) = row[start..].find("http") {
+            let url_start = start + offset;
+            let fragment = row[url_start..]
+                .split(|c: char| {
+                    c.is_whitespace() || !c.is_ascii() || matches!(c, ')' | ']' | '<' | '>' | '"')
+                })
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '*']);
+            if fragment.len() > "https://".len()
+                && let Some(url) = links.iter().find(|v| v.starts_with(fragment))
+            {
+                found.push((Position::new(columns[url_start], y), url.clone()));
+            }
+            start = url_start + fragment.len().max("http".len());
+        }
+        // Mentions are short enough to never be wrapped
+        for (at, mention) in text::mentions(&row) {
+            if links.iter().any(|v| v == mention) {
+                found.push((Position::new(columns[at], y), mention.to_string()));
+            }
+        }
+    }
+    found.sort_by_key(|(position, _)| (position.y, position.x));
+    found
+}
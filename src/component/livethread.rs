@@ -0,0 +1,369 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use ratatui::{
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Paragraph, Widget},
+};
+use tokio::{sync::mpsc::Sender, task::JoinHandle};
+
+use crate::{
+    app::AppEvent,
+    component::Component,
+    config::Config,
+    format::Clock,
+    keymap::{Action, KeyContext},
+    model::live_thread::{LiveThread, LiveUpdate},
+    ngored_error::NgoredError,
+    opener::Opener,
+    reddit_api::RedditApi,
+    text::{self, WrapOptions},
+    widget::{
+        live_update_widget::LiveUpdateWidget,
+        virtual_scroll::{VirtualScroll, VirtualScrollState},
+    },
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct LiveThreadState {
+    thread_id: String,
+    loading: bool,
+    thread: LiveThread,
+    updates: Vec<LiveUpdate>,
+    /// Why the last poll failed, cleared by the next successful one
+    error: Option<String>,
+    scroll_state: VirtualScrollState,
+    poll_handle: Option<JoinHandle<()>>,
+}
+
+/// Results of the live thread's polling task, delivered through `AppEvent::LiveThread`
+pub enum LiveThreadEvent {
+    UpdatesLoaded {
+        thread_id: String,
+        thread: LiveThread,
+        updates: Vec<LiveUpdate>,
+    },
+    /// Polling goes on after a failure, the thread may be reachable again on the next poll
+    PollFailed {
+        thread_id: String,
+        error: NgoredError,
+    },
+}
+
+pub struct LiveThreadComponent {
+    reddit_api: Arc<dyn RedditApi>,
+    app_event_sender: Sender<AppEvent>,
+    state: LiveThreadState,
+    clock: Clock,
+    /// Lines the updates scroll by
+    scroll_step: u16,
+    wrap_options: WrapOptions,
+    opener: Opener,
+}
+
+impl LiveThreadComponent {
+    pub fn new(
+        reddit_api: Arc<dyn RedditApi>,
+        config: &Config,
+        clock: Clock,
+        app_event_sender: Sender<AppEvent>,
+    ) -> Self {
+        let scroll_step = config.scroll_step.unwrap_or(1);
+        let state = LiveThreadState {
+            thread_id: String::default(),
+            loading: false,
+            thread: LiveThread::default(),
+            updates: Vec::default(),
+            error: None,
+            scroll_state: VirtualScrollState::new(scroll_step),
+            poll_handle: None,
+        };
+        Self {
+            reddit_api,
+            app_event_sender,
+            state,
+            clock,
+            scroll_step,
+            wrap_options: WrapOptions::new(config),
+            opener: Opener::new(config),
+        }
+    }
+
+    pub fn load(&mut self, thread_id: String) {
+        if self.state.thread_id == thread_id {
+            return;
+        }
+        self.state.thread_id = thread_id.clone();
+        self.state.loading = true;
+
+        let poll_handle = tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                // Polls until the thread is marked complete, or the handle is aborted on close
+                loop {
+                    let res = async {
+                        let thread = reddit_api
+                            .get_live_thread_about(&thread_id)
+                            .await?
+                            .into_live_update_event()
+                            .map(LiveThread::from)
+                            .ok_or_else(|| NgoredError::NotLiveThread(thread_id.clone()))?;
+                        let updates = reddit_api
+                            .get_live_thread_updates(&thread_id)
+                            .await?
+                            .into_children()
+                            .into_iter()
+                            .filter_map(|v| v.into_live_update().map(LiveUpdate::from))
+                            .collect();
+                        Ok::<_, NgoredError>((thread, updates))
+                    }
+                    .await;
+
+                    let thread_id = thread_id.clone();
+                    let (is_live, event) = match res {
+                        Ok((thread, updates)) => (
+                            thread.is_live,
+                            LiveThreadEvent::UpdatesLoaded {
+                                thread_id,
+                                thread,
+                                updates,
+                            },
+                        ),
+                        Err(error) => (true, LiveThreadEvent::PollFailed { thread_id, error }),
+                    };
+                    app_event_sender
+                        .send(AppEvent::LiveThread(event))
+                        .await
+                        .unwrap();
+
+                    if !is_live {
+                        break;
+                    }
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        });
+        self.state.poll_handle = Some(poll_handle);
+    }
+
+    /// Apply a polled result, results of a thread no longer shown are dropped
+    pub fn handle_loaded(&mut self, event: LiveThreadEvent) {
+        match event {
+            LiveThreadEvent::UpdatesLoaded {
+                thread_id,
+                thread,
+                updates,
+            } => {
+                if thread_id != self.state.thread_id {
+                    return;
+                }
+                self.state.loading = false;
+                self.state.error = None;
+                self.state.thread = thread;
+                Self::merge_updates(&mut self.state.updates, updates);
+            }
+            LiveThreadEvent::PollFailed { thread_id, error } => {
+                if thread_id != self.state.thread_id {
+                    return;
+                }
+                self.state.error = Some(error.to_string());
+            }
+        }
+    }
+
+    /// Merge freshly polled updates (newest first) into the feed, refreshing
+    /// already known ones since they can be stricken after being posted.
+    fn merge_updates(updates: &mut Vec<LiveUpdate>, fresh_updates: Vec<LiveUpdate>) {
+        let mut new_updates = Vec::new();
+        for fresh_update in fresh_updates {
+            if let Some(update) = updates.iter_mut().find(|v| v.id == fresh_update.id) {
+                *update = fresh_update;
+            } else {
+                new_updates.push(fresh_update);
+            }
+        }
+        new_updates.append(updates);
+        *updates = new_updates;
+    }
+
+    fn reset(&mut self) {
+        let state = &mut self.state;
+        if let Some(poll_handle) = state.poll_handle.take() {
+            poll_handle.abort();
+        }
+        state.thread_id = String::default();
+        state.loading = false;
+        state.thread = LiveThread::default();
+        state.updates.clear();
+        state.error = None;
+        state.scroll_state = VirtualScrollState::new(self.scroll_step);
+    }
+}
+
+#[async_trait]
+impl Component for LiveThreadComponent {
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::LiveThread)
+    }
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        match action {
+            Action::Back => {
+                self.reset();
+                self.app_event_sender.send(AppEvent::Back).await?;
+            }
+            Action::OpenInBrowser => {
+                let thread_id = self.state.thread_id.clone();
+                self.opener
+                    .open(&format!("https://www.reddit.com/live/{}", thread_id))?;
+            }
+            Action::Down => {
+                self.state.scroll_state.scroll_down();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Up => {
+                self.state.scroll_state.scroll_up();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PageDown => {
+                self.state.scroll_state.scroll_page_down();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PageUp => {
+                self.state.scroll_state.scroll_page_up();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::HalfPageDown => {
+                self.state.scroll_state.scroll_half_page_down();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::HalfPageUp => {
+                self.state.scroll_state.scroll_half_page_up();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Top => {
+                self.state.scroll_state.scroll_to_top();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Bottom => {
+                self.state.scroll_state.scroll_to_bottom();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame, root_area: Rect) {
+        let root_buf = frame.buffer_mut();
+        let state = &mut self.state;
+        let now = self.clock.now();
+        let wrap_options = self.wrap_options;
+
+        let mut root_block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Line::from(format!("live • {}", state.thread.title)).italic());
+        if state.thread.is_live {
+            let viewers = state
+                .thread
+                .viewer_count
+                .map(|v| format!(" • {} viewers", v))
+                .unwrap_or_default();
+            root_block = root_block.title(
+                Line::from(vec!["● LIVE".fg(Color::Red).bold(), viewers.into()]).right_aligned(),
+            );
+        } else if !state.loading {
+            root_block = root_block.title(Line::from("complete").right_aligned());
+        }
+
+        if let Some(error) = &state.error
+            && !state.loading
+        {
+            root_block = root_block.title_bottom(Line::from(error.clone().fg(Color::Red)));
+        }
+
+        let root_block_inner = root_block.inner(root_area);
+        root_block.render(root_area, root_buf);
+
+        if state.loading {
+            let text = match &state.error {
+                Some(error) => Text::raw(error.clone()),
+                None => Text::raw("Loading..."),
+            };
+            let [area] = Layout::vertical([Constraint::Length(text.height() as u16)])
+                .flex(Flex::Center)
+                .areas(root_block_inner);
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .render(area, root_buf);
+            return;
+        }
+
+        let [root_block_inner_no_scrollbar, _] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(2)])
+                .areas(root_block_inner);
+
+        let description_lines = if state.thread.description.is_empty() {
+            Vec::default()
+        } else {
+            text::wrap(
+                &state.thread.description,
+                root_block_inner_no_scrollbar.width,
+                wrap_options,
+            )
+            .into_iter()
+            .map(|v| Line::from(v).italic())
+            .collect::<Vec<Line>>()
+        };
+        let description_height = description_lines.len() as u16;
+
+        let update_widgets: Vec<LiveUpdateWidget> = state
+            .updates
+            .iter()
+            .map(|v| {
+                LiveUpdateWidget::new(v, root_block_inner_no_scrollbar.width, now, wrap_options)
+            })
+            .collect();
+        let updates_height = update_widgets.iter().fold(0, |a, b| a + b.height() as u16);
+
+        let content_height = description_height + 1 + updates_height; // + 1 for separator
+        let content_area = Rect::new(
+            0,
+            0,
+            root_block_inner.width.saturating_sub(1),
+            content_height,
+        );
+        let [description_area, separator_area, updates_area] = Layout::vertical([
+            Constraint::Length(description_height),
+            Constraint::Length(1),
+            Constraint::Length(updates_height),
+        ])
+        .areas(content_area);
+
+        let mut scroll = VirtualScroll::new(
+            root_block_inner,
+            content_height,
+            true,
+            &mut state.scroll_state,
+            root_buf,
+        );
+        scroll.render_widget(Paragraph::new(description_lines), description_area);
+        scroll.render_widget(
+            Block::new()
+                .borders(Borders::BOTTOM)
+                .title_bottom(format!("{} updates", state.updates.len())),
+            separator_area,
+        );
+
+        let mut y = updates_area.y;
+        update_widgets.into_iter().for_each(|i| {
+            let height = i.height() as u16;
+            scroll.render_widget(i, Rect::new(updates_area.x, y, updates_area.width, height));
+            y += height;
+        });
+    }
+}
@@ -0,0 +1,447 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Text},
+    widgets::{Block, BorderType, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+use tokio::{sync::mpsc::Sender, task::JoinHandle};
+
+use crate::{
+    app::AppEvent,
+    component::Component,
+    config::Config,
+    format::Clock,
+    keymap::{Action, KeyContext},
+    model::modmail::{Message, ModmailConversation},
+    ngored_error::NgoredError,
+    opener::Opener,
+    reddit_api::RedditApi,
+    text::WrapOptions,
+    theme::Theme,
+    widget::{
+        message_widget::MessageWidget,
+        virtual_scroll::{VirtualScroll, VirtualScrollState},
+    },
+};
+
+pub struct OpenedConversation {
+    conversation: ModmailConversation,
+    loading: bool,
+    messages: Vec<Message>,
+    error: Option<String>,
+    scroll_state: VirtualScrollState,
+}
+
+pub struct ModmailState {
+    loading: bool,
+    conversations: Vec<ModmailConversation>,
+    /// Why the conversations couldn't be loaded, shown instead of the list
+    error: Option<String>,
+    list_state: ListState,
+    opened: Option<OpenedConversation>,
+    load_handle: Option<JoinHandle<()>>,
+}
+
+/// Results of the modmail's loader tasks, delivered through `AppEvent::Modmail`
+pub enum ModmailEvent {
+    ConversationsLoaded(Result<Vec<ModmailConversation>, NgoredError>),
+    MessagesLoaded {
+        conversation_id: String,
+        messages: Result<Vec<Message>, NgoredError>,
+    },
+}
+
+pub struct ModmailComponent {
+    reddit_api: Arc<dyn RedditApi>,
+    app_event_sender: Sender<AppEvent>,
+    state: ModmailState,
+    theme: Theme,
+    clock: Clock,
+    /// Lines a conversation scrolls by
+    scroll_step: u16,
+    wrap_options: WrapOptions,
+    opener: Opener,
+}
+
+impl ModmailComponent {
+    pub fn new(
+        reddit_api: Arc<dyn RedditApi>,
+        config: &Config,
+        clock: Clock,
+        app_event_sender: Sender<AppEvent>,
+    ) -> Self {
+        let state = ModmailState {
+            loading: false,
+            conversations: Vec::default(),
+            error: None,
+            list_state: ListState::default(),
+            opened: None,
+            load_handle: None,
+        };
+        Self {
+            reddit_api,
+            app_event_sender,
+            state,
+            theme: Theme::new(config),
+            clock,
+            scroll_step: config.scroll_step.unwrap_or(1),
+            wrap_options: WrapOptions::new(config),
+            opener: Opener::new(config),
+        }
+    }
+
+    pub fn load(&mut self) {
+        if !self.reddit_api.has_account() {
+            return;
+        }
+        self.state.loading = true;
+        self.state.error = None;
+
+        let load_handle = tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let conversations = reddit_api.get_modmail_conversations().await.map(|mut res| {
+                    res.conversation_ids
+                        .iter()
+                        .filter_map(|id| res.conversations.remove(id))
+                        .map(ModmailConversation::from)
+                        .collect()
+                });
+                app_event_sender
+                    .send(AppEvent::Modmail(ModmailEvent::ConversationsLoaded(
+                        conversations,
+                    )))
+                    .await
+                    .unwrap();
+            }
+        });
+        self.state.load_handle = Some(load_handle);
+    }
+
+    fn open_conversation(&mut self) {
+        let state = &self.state;
+        let Some(conversation) = state
+            .list_state
+            .selected()
+            .and_then(|i| state.conversations.get(i))
+            .cloned()
+        else {
+            return;
+        };
+        let conversation_id = conversation.id.clone();
+        self.state.opened = Some(OpenedConversation {
+            conversation,
+            loading: true,
+            messages: Vec::default(),
+            error: None,
+            scroll_state: VirtualScrollState::new(self.scroll_step),
+        });
+
+        let load_handle = tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let messages = reddit_api
+                    .get_modmail_conversation(&conversation_id)
+                    .await
+                    .map(|res| {
+                        let mut messages: Vec<Message> =
+                            res.messages.into_values().map(Message::from).collect();
+                        messages.sort_by_key(|v| v.created_at);
+                        messages
+                    });
+                app_event_sender
+                    .send(AppEvent::Modmail(ModmailEvent::MessagesLoaded {
+                        conversation_id,
+                        messages,
+                    }))
+                    .await
+                    .unwrap();
+            }
+        });
+        self.state.load_handle = Some(load_handle);
+    }
+
+    /// Apply the result of a loader task, messages of a closed conversation are dropped
+    pub fn handle_loaded(&mut self, event: ModmailEvent) {
+        let state = &mut self.state;
+        match event {
+            ModmailEvent::ConversationsLoaded(conversations) => {
+                state.loading = false;
+                match conversations {
+                    Ok(conversations) => {
+                        if !conversations.is_empty() {
+                            state.list_state.select(Some(0));
+                        }
+                        state.conversations = conversations;
+                    }
+                    Err(err) => state.error = Some(err.to_string()),
+                }
+            }
+            ModmailEvent::MessagesLoaded {
+                conversation_id,
+                messages,
+            } => {
+                if let Some(opened) = state
+                    .opened
+                    .as_mut()
+                    .filter(|v| v.conversation.id == conversation_id)
+                {
+                    opened.loading = false;
+                    match messages {
+                        Ok(messages) => opened.messages = messages,
+                        Err(err) => opened.error = Some(err.to_string()),
+                    }
+                }
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        let state = &mut self.state;
+        if let Some(load_handle) = state.load_handle.take() {
+            load_handle.abort();
+        }
+        state.loading = false;
+        state.conversations.clear();
+        state.error = None;
+        state.list_state = ListState::default();
+        state.opened = None;
+    }
+
+    async fn handle_conversation_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        {
+            let state = &mut self.state;
+            match action {
+                Action::Back => {
+                    if let Some(load_handle) = state.load_handle.take() {
+                        load_handle.abort();
+                    }
+                    state.opened = None;
+                }
+                Action::Down => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_down();
+                    }
+                }
+                Action::Up => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_up();
+                    }
+                }
+                Action::PageDown => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_page_down();
+                    }
+                }
+                Action::PageUp => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_page_up();
+                    }
+                }
+                Action::HalfPageDown => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_half_page_down();
+                    }
+                }
+                Action::HalfPageUp => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_half_page_up();
+                    }
+                }
+                Action::Top => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_to_top();
+                    }
+                }
+                Action::Bottom => {
+                    if let Some(opened) = state.opened.as_mut() {
+                        opened.scroll_state.scroll_to_bottom();
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+        self.app_event_sender.send(AppEvent::Draw).await?;
+        Ok(())
+    }
+
+    fn draw_conversation(
+        opened: &mut OpenedConversation,
+        now: DateTime<Utc>,
+        wrap_options: WrapOptions,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let participant = opened
+            .conversation
+            .participant
+            .as_ref()
+            .map(|v| format!(" • u/{}", v))
+            .unwrap_or_default();
+        let block = Block::bordered().border_type(BorderType::Rounded).title(
+            Line::from(format!(
+                "r/{}{} • {}",
+                opened.conversation.subreddit, participant, opened.conversation.subject
+            ))
+            .italic(),
+        );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if opened.loading || opened.error.is_some() {
+            let text = opened.error.as_deref().unwrap_or("Loading...");
+            let [center] = Layout::vertical([Constraint::Length(1)])
+                .flex(Flex::Center)
+                .areas(inner);
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .render(center, buf);
+            return;
+        }
+
+        let [inner_no_scrollbar, _] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(2)]).areas(inner);
+        let message_widgets: Vec<MessageWidget> = opened
+            .messages
+            .iter()
+            .map(|v| MessageWidget::new(v, inner_no_scrollbar.width, now, wrap_options))
+            .collect();
+        let content_height = message_widgets.iter().fold(0, |a, b| a + b.height() as u16);
+
+        let mut scroll =
+            VirtualScroll::new(inner, content_height, true, &mut opened.scroll_state, buf);
+        let width = inner.width.saturating_sub(1);
+        let mut y = 0;
+        message_widgets.into_iter().for_each(|i| {
+            let height = i.height() as u16;
+            scroll.render_widget(i, Rect::new(0, y, width, height));
+            y += height;
+        });
+    }
+}
+
+#[async_trait]
+impl Component for ModmailComponent {
+    fn key_context(&self) -> Option<KeyContext> {
+        if self.state.opened.is_some() {
+            Some(KeyContext::ModmailConversation)
+        } else {
+            Some(KeyContext::Modmail)
+        }
+    }
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        if self.state.opened.is_some() {
+            return self.handle_conversation_action(action).await;
+        }
+        match action {
+            Action::Back => {
+                self.reset();
+                self.app_event_sender.send(AppEvent::Back).await?;
+            }
+            Action::Down => {
+                self.state.list_state.select_next();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Up => {
+                self.state.list_state.select_previous();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Top => {
+                self.state.list_state.select_first();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Bottom => {
+                self.state.list_state.select_last();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Open => self.open_conversation(),
+            Action::OpenInBrowser => {
+                let state = &self.state;
+                if let Some(conversation) = state
+                    .list_state
+                    .selected()
+                    .and_then(|i| state.conversations.get(i))
+                {
+                    self.opener.open(&format!(
+                        "https://mod.reddit.com/mail/all/{}",
+                        conversation.id
+                    ))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        let buf = frame.buffer_mut();
+        let state = &mut self.state;
+        let now = self.clock.now();
+
+        if let Some(opened) = state.opened.as_mut() {
+            Self::draw_conversation(opened, now, self.wrap_options, area, buf);
+            return;
+        }
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title("Modmail".italic());
+
+        if !self.reddit_api.has_account() || state.loading || state.conversations.is_empty() {
+            let text = if !self.reddit_api.has_account() {
+                Text::raw("Add an [account] section to config.toml to read modmail")
+            } else if let Some(error) = &state.error {
+                Text::raw(error.clone())
+            } else if state.loading {
+                Text::raw("Loading...")
+            } else {
+                Text::raw("No conversations")
+            };
+            let inner = block.inner(area);
+            block.render(area, buf);
+            let [center] = Layout::vertical([Constraint::Length(text.height() as u16)])
+                .flex(Flex::Center)
+                .areas(inner);
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .render(center, buf);
+            return;
+        }
+
+        let items = state.conversations.iter().map(|conversation| {
+            let participant = conversation
+                .participant
+                .as_ref()
+                .map(|v| format!(" • u/{}", v))
+                .unwrap_or_default();
+            ListItem::new(vec![
+                Line::from(
+                    format!(
+                        "r/{}{} • {} messages • {}",
+                        conversation.subreddit,
+                        participant,
+                        conversation.num_messages,
+                        HumanTime::from(conversation.last_updated - now)
+                    )
+                    .italic(),
+                ),
+                Line::from(conversation.subject.clone().bold()),
+                Line::default(),
+            ])
+        });
+        let list = List::new(items)
+            .highlight_style(self.theme.selected_style())
+            .highlight_symbol(self.theme.highlight_symbol())
+            .block(block);
+        StatefulWidget::render(list, area, buf, &mut state.list_state);
+    }
+}
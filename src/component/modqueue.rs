@@ -0,0 +1,435 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono_humanize::HumanTime;
+use ratatui::{
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+use tokio::{sync::mpsc::Sender, task::JoinHandle};
+
+use crate::{
+    app::AppEvent,
+    component::Component,
+    config::Config,
+    format::{self, Clock},
+    keymap::{Action, KeyContext},
+    model::mod_item::{ModItem, ModItemKind},
+    ngored_error::NgoredError,
+    opener::Opener,
+    reddit_api::{Data, ModAction, RedditApi},
+    text::{self, WrapOptions},
+    theme::Theme,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModQueueLocation {
+    Modqueue,
+    Reports,
+    Spam,
+    Unmoderated,
+}
+
+impl ModQueueLocation {
+    const ALL: [ModQueueLocation; 4] = [
+        ModQueueLocation::Modqueue,
+        ModQueueLocation::Reports,
+        ModQueueLocation::Spam,
+        ModQueueLocation::Unmoderated,
+    ];
+
+    fn path(&self) -> &'static str {
+        match self {
+            ModQueueLocation::Modqueue => "modqueue",
+            ModQueueLocation::Reports => "reports",
+            ModQueueLocation::Spam => "spam",
+            ModQueueLocation::Unmoderated => "unmoderated",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            ModQueueLocation::Modqueue => "Queue",
+            ModQueueLocation::Reports => "Reports",
+            ModQueueLocation::Spam => "Spam",
+            ModQueueLocation::Unmoderated => "Unmoderated",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|v| v == self).unwrap()
+    }
+
+    fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn previous(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+pub fn mod_action_label(action: ModAction) -> Span<'static> {
+    let (label, color) = match action {
+        ModAction::Approve => ("✔ approved", Color::Green),
+        ModAction::Remove => ("✖ removed", Color::Red),
+        ModAction::Spam => ("✖ spammed", Color::Red),
+        ModAction::Lock => ("🔒 locked", Color::Yellow),
+        ModAction::Unlock => ("🔓 unlocked", Color::Yellow),
+    };
+    label.fg(color).bold()
+}
+
+pub struct ModQueueState {
+    loading: bool,
+    location: ModQueueLocation,
+    items: Vec<ModItem>,
+    list_state: ListState,
+    /// Why the items couldn't be loaded, shown instead of the list
+    error: Option<String>,
+    status: Option<Line<'static>>,
+    load_handle: Option<JoinHandle<()>>,
+}
+
+/// Results of the mod queue's tasks, delivered through `AppEvent::ModQueue`
+pub enum ModQueueEvent {
+    Loaded {
+        location: ModQueueLocation,
+        items: Result<Vec<ModItem>, NgoredError>,
+    },
+    Moderated {
+        item: ModItem,
+        action: ModAction,
+    },
+    ModerationFailed {
+        item: ModItem,
+        error: NgoredError,
+    },
+}
+
+pub struct ModQueueComponent {
+    reddit_api: Arc<dyn RedditApi>,
+    app_event_sender: Sender<AppEvent>,
+    state: ModQueueState,
+    /// Show scores as `12.3k`, off with `full_scores`
+    compact_scores: bool,
+    clock: Clock,
+    theme: Theme,
+    wrap_options: WrapOptions,
+    opener: Opener,
+}
+
+impl ModQueueComponent {
+    pub fn new(
+        reddit_api: Arc<dyn RedditApi>,
+        config: &Config,
+        clock: Clock,
+        app_event_sender: Sender<AppEvent>,
+    ) -> Self {
+        let state = ModQueueState {
+            loading: false,
+            location: ModQueueLocation::Modqueue,
+            items: Vec::default(),
+            list_state: ListState::default(),
+            error: None,
+            status: None,
+            load_handle: None,
+        };
+        Self {
+            reddit_api,
+            app_event_sender,
+            state,
+            compact_scores: !config.full_scores,
+            clock,
+            theme: Theme::new(config),
+            wrap_options: WrapOptions::new(config),
+            opener: Opener::new(config),
+        }
+    }
+
+    pub fn load(&mut self) {
+        if !self.reddit_api.has_account() {
+            return;
+        }
+        if let Some(load_handle) = self.state.load_handle.take() {
+            load_handle.abort();
+        }
+        self.state.loading = true;
+        self.state.items.clear();
+        self.state.error = None;
+        self.state.list_state.select(None);
+        let location = self.state.location;
+
+        let load_handle = tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                // r/mod aggregates every sub the account moderates
+                let items = reddit_api
+                    .get_mod_queue("mod", location.path())
+                    .await
+                    .map(|res| {
+                        res.into_children()
+                            .into_iter()
+                            .filter_map(|v| match v {
+                                Data::Post(data) => Some(ModItem::from(*data)),
+                                Data::Comment(data) => Some(ModItem::from(data)),
+                                _ => None,
+                            })
+                            .collect()
+                    });
+                app_event_sender
+                    .send(AppEvent::ModQueue(ModQueueEvent::Loaded {
+                        location,
+                        items,
+                    }))
+                    .await
+                    .unwrap();
+            }
+        });
+        self.state.load_handle = Some(load_handle);
+    }
+
+    fn moderate(&self, action: ModAction) {
+        let state = &self.state;
+        let Some(item) = state
+            .list_state
+            .selected()
+            .and_then(|i| state.items.get(i))
+            .cloned()
+        else {
+            return;
+        };
+
+        tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let event = match reddit_api.moderate(&item.fullname, action).await {
+                    Ok(()) => ModQueueEvent::Moderated { item, action },
+                    Err(error) => ModQueueEvent::ModerationFailed { item, error },
+                };
+                app_event_sender
+                    .send(AppEvent::ModQueue(event))
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
+    /// Apply the result of a task, items of another location than the shown one are dropped
+    pub fn handle_loaded(&mut self, event: ModQueueEvent) {
+        let state = &mut self.state;
+        match event {
+            ModQueueEvent::Loaded { location, items } => {
+                if location != state.location {
+                    return;
+                }
+                state.loading = false;
+                match items {
+                    Ok(items) => {
+                        if !items.is_empty() {
+                            state.list_state.select(Some(0));
+                        }
+                        state.items = items;
+                    }
+                    Err(err) => state.error = Some(err.to_string()),
+                }
+            }
+            ModQueueEvent::Moderated { item, action } => {
+                state.items.retain(|v| v.fullname != item.fullname);
+                if state.items.is_empty() {
+                    state.list_state.select(None);
+                } else if let Some(selected) = state.list_state.selected() {
+                    let last = state.items.len() - 1;
+                    state.list_state.select(Some(selected.min(last)));
+                }
+                state.status = Some(Line::from(vec![
+                    mod_action_label(action),
+                    format!(" u/{} • {}", item.author, item.title).into(),
+                ]));
+            }
+            ModQueueEvent::ModerationFailed { item, error } => {
+                state.status = Some(Line::from(
+                    format!("u/{} • {}: {}", item.author, item.title, error).fg(Color::Red),
+                ));
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        let state = &mut self.state;
+        if let Some(load_handle) = state.load_handle.take() {
+            load_handle.abort();
+        }
+        state.loading = false;
+        state.items.clear();
+        state.error = None;
+        state.list_state = ListState::default();
+        state.status = None;
+    }
+}
+
+#[async_trait]
+impl Component for ModQueueComponent {
+    fn key_context(&self) -> Option<KeyContext> {
+        Some(KeyContext::ModQueue)
+    }
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        match action {
+            Action::Back => {
+                self.reset();
+                self.app_event_sender.send(AppEvent::Back).await?;
+            }
+            Action::Down => {
+                self.state.list_state.select_next();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Up => {
+                self.state.list_state.select_previous();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Top => {
+                self.state.list_state.select_first();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Bottom => {
+                self.state.list_state.select_last();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::NextTab => {
+                {
+                    let state = &mut self.state;
+                    state.location = state.location.next();
+                    state.status = None;
+                }
+                self.load();
+            }
+            Action::PreviousTab => {
+                {
+                    let state = &mut self.state;
+                    state.location = state.location.previous();
+                    state.status = None;
+                }
+                self.load();
+            }
+            Action::Approve => self.moderate(ModAction::Approve),
+            Action::Remove => self.moderate(ModAction::Remove),
+            Action::Spam => self.moderate(ModAction::Spam),
+            Action::Open => {
+                let permalink = {
+                    let state = &self.state;
+                    state
+                        .list_state
+                        .selected()
+                        .and_then(|i| state.items.get(i))
+                        .filter(|v| v.kind == ModItemKind::Comment)
+                        .map(|v| v.permalink.clone())
+                };
+                if let Some(permalink) = permalink {
+                    self.app_event_sender
+                        .send(AppEvent::OpenPermalink(permalink))
+                        .await?;
+                }
+            }
+            Action::OpenInBrowser => {
+                let state = &self.state;
+                if let Some(item) = state.list_state.selected().and_then(|i| state.items.get(i)) {
+                    self.opener
+                        .open(&format!("https://www.reddit.com{}", item.permalink))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        let buf = frame.buffer_mut();
+        let state = &mut self.state;
+
+        let tabs = ModQueueLocation::ALL
+            .iter()
+            .flat_map(|v| {
+                let tab = if *v == state.location {
+                    v.title().bold().reversed()
+                } else {
+                    v.title().into()
+                };
+                [" ".into(), tab]
+            })
+            .collect::<Vec<_>>();
+        let mut block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title("Mod queue".italic())
+            .title(Line::from(tabs).right_aligned())
+            .title_bottom("[a] approve • [r] remove • [s] spam • [l] view comment • [o] open");
+        if let Some(status) = state.status.clone() {
+            block = block.title_bottom(status.right_aligned());
+        }
+
+        if !self.reddit_api.has_account() || state.loading || state.items.is_empty() {
+            let text = if !self.reddit_api.has_account() {
+                Text::raw("Add an [account] section to config.toml to use moderation tools")
+            } else if let Some(error) = &state.error {
+                Text::raw(error.clone())
+            } else if state.loading {
+                Text::raw("Loading...")
+            } else {
+                Text::raw("Nothing to review")
+            };
+            let inner = block.inner(area);
+            block.render(area, buf);
+            let [center] = Layout::vertical([Constraint::Length(text.height() as u16)])
+                .flex(Flex::Center)
+                .areas(inner);
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .render(center, buf);
+            return;
+        }
+
+        let width = block.inner(area).width;
+        let items = state.items.iter().map(|item| {
+            let kind = match item.kind {
+                ModItemKind::Post => "post",
+                ModItemKind::Comment => "comment",
+            };
+            let mut header = vec![
+                format!(
+                    "r/{} • u/{} • {} • 👍🏻{} • {}",
+                    item.subreddit,
+                    item.author,
+                    kind,
+                    format::score(item.score, self.compact_scores),
+                    HumanTime::from(item.created_at - self.clock.now())
+                )
+                .italic(),
+            ];
+            if !item.report_reasons.is_empty() {
+                header.push(
+                    format!(" • ⚑ {}", item.report_reasons.join(", "))
+                        .fg(Color::Yellow)
+                        .bold(),
+                );
+            }
+            let mut lines = vec![Line::from(header), Line::from(item.title.clone().bold())];
+            if let Some(body) = text::wrap(&item.body, width, self.wrap_options)
+                .into_iter()
+                .next()
+            {
+                lines.push(Line::from(body).dim());
+            }
+            lines.push(Line::default());
+            ListItem::new(lines)
+        });
+        let list = List::new(items)
+            .highlight_style(self.theme.selected_style())
+            .highlight_symbol(self.theme.highlight_symbol())
+            .block(block);
+        StatefulWidget::render(list, area, buf, &mut state.list_state);
+    }
+}
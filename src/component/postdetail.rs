@@ -1,287 +1,2131 @@
 use std::{
-    ops::Deref,
+    collections::{HashMap, HashSet},
     process::Stdio,
     sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 
-use chrono::Utc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use chrono_humanize::HumanTime;
+use color_quant::NeuQuant;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 use futures::future::join_all;
+use image::{DynamicImage, imageops::FilterType};
 use log::debug;
 use ratatui::{
-    layout::{Constraint, Flex, Layout, Rect, Size},
-    style::{Modifier, Stylize},
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Stylize},
+    symbols::border,
     text::Line,
-    widgets::{Block, BorderType, Borders, Paragraph, StatefulWidget, Widget},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListState, Paragraph, StatefulWidget, Widget,
+    },
 };
-use ratatui_image::{Resize, StatefulImage, picker::Picker, protocol::StatefulProtocol};
+use ratatui_image::picker::{Picker, ProtocolType};
 use tokio::{
     process::Command,
     sync::{mpsc::Sender, oneshot},
     task::JoinHandle,
 };
-use tui_scrollview::{ScrollView, ScrollViewState, ScrollbarVisibility};
+use tui_input::{Input, backend::crossterm::EventHandler};
 
 use crate::{
     app::AppEvent,
-    component::Component,
-    model::{comment::Comment, post::Post},
+    clipboard,
+    component::{
+        Component, StatusInfo,
+        link_hints::{LinkHints, LinkHintsEvent},
+        modqueue::mod_action_label,
+        share_popup::{SharePopup, SharePopupEvent, share_post},
+        user_tag_popup::{UserTagPopup, UserTagPopupEvent},
+    },
+    config::{Config, Hooks, ImageProtocol},
+    format::Clock,
+    hooks,
+    keymap::{Action, KeyContext, Keymap},
+    model::{
+        comment::{Comment, CommentRow},
+        image::ImageRef,
+        post::Post,
+    },
     ngored_error::NgoredError,
-    reddit_api::RedditApi,
-    widget::comment_widget::CommentWidget,
+    opener::Opener,
+    reddit_api::{Data, ModAction, RedditApi},
+    state_store::StateStore,
+    text::{self, WrapCache, WrapOptions},
+    theme::{self, SubAccents, Theme},
+    widget::{
+        comment_widget::{CommentWidget, ContinueWidget},
+        link_card_widget::LinkCardWidget,
+        media_widget::{ImageOptions, LoadedImage, Media},
+        poll_widget::PollWidget,
+        virtual_scroll::{VirtualScroll, VirtualScrollState},
+    },
 };
 
+/// Posts opened within this time of each other wait this long before loading, so only the last
+/// of a burst (e.g. holding a key) reaches the network
+const LOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Percent of the view's height `GrowImage` and `ShrinkImage` change images by
+const IMAGE_HEIGHT_STEP: u16 = 10;
+
+/// Size of a tile of the gallery grid, border included
+const GRID_TILE_WIDTH: u16 = 24;
+const GRID_TILE_HEIGHT: u16 = 10;
+
+/// Palette size of sixel images
+const SIXEL_COLORS: usize = 256;
+
 pub struct PostDetailState {
     post: Post,
-    scroll_state: ScrollViewState,
-    preview_image: Option<StatefulProtocol>,
-    medias: Option<(usize, Vec<StatefulProtocol>)>,
-    crosspost_parents_medias: Option<Vec<(usize, Vec<StatefulProtocol>)>>,
+    scroll_state: VirtualScrollState,
+    preview_image: Option<Media>,
+    medias: Option<(usize, Vec<Media>)>,
+    crosspost_parents_medias: Option<Vec<(usize, Vec<Media>)>>,
     loading_comment: bool,
     loading_video: bool,
     comments: Vec<Comment>,
+    max_comment_depth: Option<usize>,
+    collapse_contest_replies: bool,
+    /// Lines kept in view around the selected comment
+    scrolloff: u16,
+    /// Show scores as `12.3k`, off with `full_scores`
+    compact_scores: bool,
+    theme: Theme,
+    clock: Clock,
+    wrap_cache: WrapCache,
+    show_images: bool,
+    /// Images aren't downloaded, toggled at runtime
+    text_only: bool,
+    image_options: ImageOptions,
+    /// Images fill the view's width instead of fitting `image_height`
+    fit_width: bool,
+    /// Percent of the view's height images fit in
+    image_height: u16,
+    /// `image_height` from the config, restored on `ResetImage`
+    default_image_height: u16,
+    /// Widest the post is drawn, centered in the rest
+    max_content_width: Option<u16>,
+    /// Comments whose replies are shown past `max_comment_depth`
+    expanded_comments: HashSet<String>,
+    /// Comments whose replies are hidden, kept in the state store for the thread
+    collapsed_comments: HashSet<String>,
+    /// Comments, or the post, whose spoilers are shown
+    revealed_spoilers: HashSet<String>,
     load_handle: Option<JoinHandle<()>>,
+    /// When the last post was opened, kept across `reset` for `LOAD_DEBOUNCE`
+    last_load: Option<Instant>,
+    duplicates: Option<DuplicatesState>,
+    duplicates_handle: Option<JoinHandle<()>>,
+    gallery_grid: Option<GalleryGridState>,
+    slideshow: Option<Slideshow>,
+    slideshow_interval: Duration,
+    medias_handle: Option<JoinHandle<()>>,
+    selected_comment: Option<usize>,
+    scroll_to_selected_comment: bool,
+    moderated_subs: Option<Vec<String>>,
+    friends: Option<Vec<String>>,
+    status: Option<Line<'static>>,
+    /// Lowercased search query, matched against the post body and the comments
+    search: Option<String>,
+    /// Body line of the current search match, `None` when it's on a comment
+    search_line: Option<usize>,
+    /// Body line to bring into view at the next draw
+    scroll_to_body_line: Option<usize>,
+    /// Link moved to with Tab/Shift-Tab, Enter opens it
+    focused_link: Option<LinkPosition>,
+    /// Width the body was last wrapped at, body search matches are lines at this width
+    body_width: u16,
+    /// Previous visit to this post, comments created after it are highlighted
+    last_visit: Option<DateTime<Utc>>,
+    /// Comment opened from a permalink, only it and its parents are loaded
+    focused_comment: Option<String>,
+    /// Scroll offset and selected comment of each post left this session, keyed by post id
+    positions: HashMap<String, (u16, Option<usize>)>,
+    /// Gallery image shown of each post left this session, keyed by post id
+    gallery_positions: HashMap<String, usize>,
+    /// Posts this one was opened from, `Back` returns to the last
+    history: Vec<HistoryEntry>,
+}
+
+/// Results of the post detail's loader tasks, delivered through `AppEvent::PostDetail`
+pub enum PostDetailEvent {
+    /// `post` replaces the stub post when the comments are the context of a permalink
+    CommentsLoaded {
+        post_id: String,
+        post: Option<Box<Post>>,
+        comments: Result<Vec<Comment>, NgoredError>,
+    },
+    ImageLoaded {
+        post_id: String,
+        slot: MediaSlot,
+        media: Media,
+    },
+    AccountDataLoaded {
+        moderated_subs: Vec<String>,
+        friends: Vec<String>,
+    },
+    FriendUpdated {
+        username: String,
+        is_friend: bool,
+    },
+    Moderated {
+        post_id: String,
+        comment_id: Option<String>,
+        action: ModAction,
+    },
+    DuplicatesLoaded {
+        post_id: String,
+        items: Result<Vec<Post>, NgoredError>,
+    },
+    /// A friend or moderation request failed, the error replaces the status
+    Failed {
+        post_id: String,
+        error: NgoredError,
+    },
+    /// The player exited, or couldn't be started
+    VideoClosed(Result<(), NgoredError>),
+    SlideshowTick {
+        post_id: String,
+    },
+}
+
+/// A crosspost parent's frame measured for one draw
+struct CrosspostFrame {
+    header: Line<'static>,
+    title_lines: Vec<Line<'static>>,
+    /// Where the parent's image fits, empty without one
+    image_size: Rect,
+    caption_lines: Vec<Line<'static>>,
+    /// Position in the parent's gallery
+    info: Option<String>,
+    body_lines: Vec<Line<'static>>,
+}
+
+impl CrosspostFrame {
+    fn height(&self) -> u16 {
+        let lines = self.title_lines.len() + self.caption_lines.len() + self.body_lines.len();
+        // + 2 for the borders
+        lines as u16 + self.image_size.height + self.info.is_some() as u16 + 2
+    }
+}
+
+/// Where a loaded image goes in the post
+pub enum MediaSlot {
+    Preview,
+    Gallery(usize),
+    /// Image shown in the frame of a crosspost parent
+    CrosspostParent(usize, usize),
+}
+
+/// A post left for another opened from it, e.g. its crosspost parent
+struct HistoryEntry {
+    post: Post,
+    focused_comment: Option<String>,
+    /// Kept so the comments new before it was left are still highlighted
+    last_visit: Option<DateTime<Utc>>,
+}
+
+pub struct DuplicatesState {
+    loading: bool,
+    items: Vec<Post>,
+    error: Option<String>,
+    list_state: ListState,
+}
+
+/// Gallery auto-advance, the timer task sends `SlideshowTick`s while it plays
+pub enum Slideshow {
+    Playing(JoinHandle<()>),
+    Paused,
+}
+
+/// Tiles of every image of the gallery, the cursor picks the one shown in the post
+pub struct GalleryGridState {
+    cursor: usize,
+    /// Tiles per row at the last draw, moving up or down skips this many
+    columns: usize,
+    /// First row in view
+    top_row: usize,
 }
 
 pub struct PostDetailComponent {
-    reddit_api: Arc<RedditApi>,
+    reddit_api: Arc<dyn RedditApi>,
+    state_store: Arc<RwLock<StateStore>>,
     app_event_sender: Sender<AppEvent>,
-    state: Arc<RwLock<PostDetailState>>,
+    state: PostDetailState,
     picker: Arc<Picker>,
+    user_tag_popup: Option<UserTagPopup>,
+    share_popup: Option<SharePopup>,
+    link_hints: Option<LinkHints>,
+    search_input: Option<Input>,
+    /// Render plain linear text for screen readers, see `draw_linear`
+    screen_reader: bool,
+    wrapped_comments: WrappedComments,
+    opener: Opener,
+    hooks: Hooks,
+    sub_accents: SubAccents,
+    /// Keys named by the hints, following the `[keys]` config
+    hint_keys: HashMap<Action, String>,
+}
+
+/// Wrapped comment bodies by comment id, so a draw only wraps comments it hasn't seen at this
+/// width. Kept out of `PostDetailState` so the draw can fill it while borrowing the comments
+#[derive(Default)]
+struct WrappedComments {
+    width: u16,
+    options: WrapOptions,
+    bodies: HashMap<String, Vec<String>>,
+}
+
+impl WrappedComments {
+    fn get(&mut self, depth: u16, comment: &Comment, width: u16) -> &Vec<String> {
+        if self.width != width {
+            self.width = width;
+            self.bodies.clear();
+        }
+        self.bodies
+            .entry(comment.id.clone())
+            .or_insert_with(|| CommentWidget::wrap_body(depth, &comment.body, width, self.options))
+    }
+}
+
+/// The `index`th link of the post body, or of a comment, ordered like they're shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct LinkPosition {
+    /// Index in the flattened visible comments, `None` for the body
+    comment: Option<usize>,
+    index: usize,
+}
+
+/// A search hit, ordered like they're shown: body lines before comments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SearchMatch {
+    BodyLine(usize),
+    /// Index in the flattened visible comments
+    Comment(usize),
 }
 
 impl PostDetailComponent {
     pub fn new(
-        reddit_api: Arc<RedditApi>,
+        reddit_api: Arc<dyn RedditApi>,
         picker: Arc<Picker>,
+        state_store: Arc<RwLock<StateStore>>,
+        config: &Config,
+        clock: Clock,
         app_event_sender: Sender<AppEvent>,
     ) -> Self {
         let state = PostDetailState {
             post: Post::default(),
-            scroll_state: ScrollViewState::default(),
+            scroll_state: VirtualScrollState::new(config.scroll_step.unwrap_or(1)),
             preview_image: None,
             medias: None,
             crosspost_parents_medias: None,
             loading_comment: false,
             loading_video: false,
             comments: Vec::default(),
+            max_comment_depth: config.max_comment_depth,
+            collapse_contest_replies: config.collapse_contest_replies,
+            scrolloff: config.scrolloff.unwrap_or(0),
+            compact_scores: !config.full_scores,
+            theme: Theme::new(config),
+            clock,
+            wrap_cache: WrapCache::new(WrapOptions::new(config)),
+            show_images: config.image_protocol != Some(ImageProtocol::None)
+                && !config.screen_reader,
+            text_only: config.text_only,
+            image_options: ImageOptions::new(config),
+            fit_width: false,
+            image_height: Self::clamp_image_height(config.image_height.unwrap_or(50)),
+            default_image_height: Self::clamp_image_height(config.image_height.unwrap_or(50)),
+            max_content_width: config.max_content_width,
+            expanded_comments: HashSet::default(),
+            collapsed_comments: HashSet::default(),
+            revealed_spoilers: HashSet::default(),
             load_handle: None,
+            last_load: None,
+            duplicates: None,
+            duplicates_handle: None,
+            gallery_grid: None,
+            slideshow: None,
+            slideshow_interval: Duration::from_secs(config.slideshow_interval.unwrap_or(5).max(1)),
+            medias_handle: None,
+            selected_comment: None,
+            scroll_to_selected_comment: false,
+            moderated_subs: None,
+            friends: None,
+            status: None,
+            search: None,
+            search_line: None,
+            scroll_to_body_line: None,
+            focused_link: None,
+            body_width: 0,
+            last_visit: None,
+            focused_comment: None,
+            positions: HashMap::default(),
+            gallery_positions: HashMap::default(),
+            history: Vec::new(),
         };
         Self {
             reddit_api,
+            state_store,
             app_event_sender,
-            state: Arc::new(RwLock::new(state)),
+            state,
             picker,
+            user_tag_popup: None,
+            share_popup: None,
+            link_hints: None,
+            search_input: None,
+            screen_reader: config.screen_reader,
+            wrapped_comments: WrappedComments {
+                options: WrapOptions::new(config),
+                ..WrappedComments::default()
+            },
+            opener: Opener::new(config),
+            hooks: config.hooks.clone(),
+            sub_accents: SubAccents::new(config),
+            hint_keys: Keymap::new(config).hint_keys(KeyContext::PostDetail),
         }
     }
 
-    pub fn load(&self, post: Post) {
-        {
-            let state = self.state.read().unwrap();
-            if state.post.id == post.id {
-                return;
+    pub fn load(&mut self, post: Post) {
+        self.open(post, None);
+    }
+
+    /// Open a single comment with its parents, `[T]` loads the rest of the thread
+    pub fn load_permalink(&mut self, permalink: &str) {
+        if let Some((post, comment_id)) = Post::from_permalink(permalink) {
+            self.open(post, comment_id);
+        }
+    }
+
+    /// Drop the post and the ones it was opened from, when the screen is left for another
+    pub fn close(&mut self) {
+        self.reset();
+        self.state.history.clear();
+    }
+
+    /// Load `post` over the one shown, which `Back` then returns to
+    fn open(&mut self, post: Post, focused_comment: Option<String>) {
+        let state = &mut self.state;
+        if !state.post.id.is_empty() && state.post.id != post.id {
+            state.history.push(HistoryEntry {
+                post: state.post.clone(),
+                focused_comment: state.focused_comment.clone(),
+                last_visit: state.last_visit,
+            });
+        }
+        self.load_post(post, focused_comment);
+    }
+
+    /// Play `url` with mpv until it's closed
+    async fn play_video(url: &str) -> Result<(), NgoredError> {
+        let mut mpv = Command::new("mpv")
+            .arg(url)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| NgoredError::Command {
+                command: "mpv".to_string(),
+                reason: err.to_string(),
+            })?;
+        mpv.wait().await?;
+        Ok(())
+    }
+
+    /// Reload the post and its comments, e.g. to reveal hidden scores
+    fn refresh(&mut self) {
+        Self::save_position(&mut self.state);
+        let post = self.state.post.clone();
+        let focused_comment = self.state.focused_comment.clone();
+        // Keep highlighting what was new before the refresh
+        let last_visit = self.state.last_visit;
+        self.show_post(post, focused_comment, last_visit);
+    }
+
+    fn load_post(&mut self, post: Post, focused_comment: Option<String>) {
+        if self.state.post.id == post.id && self.state.focused_comment == focused_comment {
+            return;
+        }
+        // Refreshing or focusing another comment isn't opening the post again
+        let last_visit = if self.state.post.id == post.id {
+            Ok(self.state.last_visit)
+        } else {
+            hooks::post_opened(&self.hooks, &post);
+            self.state_store.write().unwrap().visit_post(&post.id)
+        };
+        match last_visit {
+            Ok(last_visit) => self.show_post(post, focused_comment, last_visit),
+            Err(err) => {
+                self.show_post(post, focused_comment, None);
+                self.state.status = Some(Self::error_status(&err));
             }
         }
+    }
+
+    /// Load `post` without counting it as opened, e.g. when going back to it or refreshing
+    fn show_post(
+        &mut self,
+        post: Post,
+        focused_comment: Option<String>,
+        last_visit: Option<DateTime<Utc>>,
+    ) {
+        self.reset();
+        self.load_account_data();
+
+        self.state.last_visit = last_visit;
+        self.state.collapsed_comments = self
+            .state_store
+            .read()
+            .unwrap()
+            .collapsed_comments(&post.id);
+        self.state.post = post;
+        self.state.focused_comment = focused_comment.clone();
+        self.state.loading_comment = true;
+        self.state.scroll_state.scroll_to_top();
+        self.init_medias();
 
-        self.state.write().unwrap().post = post;
-        self.state.write().unwrap().load_handle = Some(tokio::spawn({
-            let state = self.state.clone();
+        let post = self.state.post.clone();
+        let skip_images = self.skip_images_reason();
+        let image_options = self.state.image_options.clone();
+        let hooks = self.hooks.clone();
+        let debounce = self
+            .state
+            .last_load
+            .is_some_and(|v| v.elapsed() < LOAD_DEBOUNCE);
+        self.state.last_load = Some(Instant::now());
+        self.state.load_handle = Some(tokio::spawn({
             let reddit_api = self.reddit_api.clone();
             let app_event_sender = self.app_event_sender.clone();
             let picker = self.picker.clone();
-            let (sub, post_id) = {
-                let state = state.read().unwrap();
-                (state.post.subreddit.clone(), state.post.id.clone())
-            };
             async move {
-                {
-                    let mut state = state.write().unwrap();
-                    state.scroll_state.scroll_to_top();
-                }
-                app_event_sender.send(AppEvent::Draw).await.unwrap();
-
-                tokio::join!(
-                    Self::load_preivew_image(
-                        state.clone(),
-                        app_event_sender.clone(),
-                        reddit_api.clone(),
-                        picker.clone(),
-                    ),
-                    Self::load_crosspost_parent_medias(
-                        state.clone(),
-                        app_event_sender.clone(),
-                        reddit_api.clone(),
-                        picker.clone(),
-                    ),
-                    Self::load_gallery_images(
-                        state.clone(),
-                        app_event_sender.clone(),
-                        reddit_api.clone(),
-                        picker.clone(),
-                    ),
-                    Self::load_comments(
-                        state.clone(),
-                        app_event_sender.clone(),
-                        &sub,
-                        &post_id,
-                        reddit_api.clone()
+                // Aborted by the `reset` of the next post if it comes before the wait is over
+                if debounce {
+                    tokio::time::sleep(LOAD_DEBOUNCE).await;
+                }
+                if focused_comment.is_some() || post.is_stub() {
+                    // Only a stub of the post is known until the context arrives
+                    let post = Self::load_comment_context(
+                        &app_event_sender,
+                        &post,
+                        focused_comment.as_deref(),
+                        &*reddit_api,
                     )
-                );
+                    .await;
+                    Self::load_medias(
+                        post,
+                        skip_images,
+                        image_options,
+                        hooks,
+                        app_event_sender,
+                        reddit_api,
+                        picker,
+                    )
+                    .await;
+                } else {
+                    tokio::join!(
+                        Self::load_medias(
+                            post.clone(),
+                            skip_images,
+                            image_options,
+                            hooks,
+                            app_event_sender.clone(),
+                            reddit_api.clone(),
+                            picker,
+                        ),
+                        Self::load_comments(&app_event_sender, &post, &*reddit_api)
+                    );
+                }
             }
         }));
     }
 
-    async fn load_preivew_image(
-        state: Arc<RwLock<PostDetailState>>,
+    /// Why images aren't downloaded, if they aren't
+    fn skip_images_reason(&self) -> Option<&'static str> {
+        if !self.state.show_images {
+            Some("images disabled")
+        } else if self.state.text_only {
+            Some("text-only • [i] load images")
+        } else {
+            None
+        }
+    }
+
+    /// Placeholders for every image of the post, replaced as `ImageLoaded` events arrive
+    fn init_medias(&mut self) {
+        let loading = |image: &ImageRef| Media::Placeholder {
+            image: image.clone(),
+            reason: "loading...".to_string(),
+        };
+        let post = &self.state.post;
+        self.state.preview_image = Self::preview_image(post).map(loading);
+        let index = self.state.gallery_positions.get(&post.id).copied();
+        self.state.medias = post.galleries.as_ref().map(|v| {
+            let index = index.unwrap_or(0).min(v.len().saturating_sub(1));
+            (index, v.iter().map(loading).collect())
+        });
+        self.state.crosspost_parents_medias = Some(
+            post.crosspost_parent
+                .iter()
+                .map(|v| {
+                    (
+                        0,
+                        Self::crosspost_parent_images(v)
+                            .iter()
+                            .map(loading)
+                            .collect(),
+                    )
+                })
+                .collect(),
+        );
+    }
+
+    /// The image shown above the post, crossposts show their parent's in its frame instead
+    fn preview_image(post: &Post) -> Option<&ImageRef> {
+        if !post.crosspost_parent.is_empty() {
+            return None;
+        }
+        post.preview_images.as_ref().and_then(|v| v.last())
+    }
+
+    /// Images in the frame of a crosspost parent, its gallery or else its preview
+    fn crosspost_parent_images(parent: &Post) -> &[ImageRef] {
+        match (&parent.galleries, &parent.preview_images) {
+            (Some(galleries), _) => galleries,
+            // The largest preview only, like the one above posts
+            (None, Some(previews)) => &previews[previews.len().saturating_sub(1)..],
+            (None, None) => &[],
+        }
+    }
+
+    /// Load every image of the post concurrently, each is sent as soon as it's ready
+    async fn load_medias(
+        post: Post,
+        skip_images: Option<&'static str>,
+        image_options: ImageOptions,
+        hooks: Hooks,
         app_event_sender: Sender<AppEvent>,
-        reddit_api: Arc<RedditApi>,
+        reddit_api: Arc<dyn RedditApi>,
         picker: Arc<Picker>,
     ) {
-        let i = state
-            .read()
-            .unwrap()
-            .post
-            .preview_image_urls
-            .as_ref()
-            .and_then(|v| v.last().map(|v| v.clone()));
-        if let Some(image_url) = i {
-            let image_bytes = {
-                reddit_api
-                    .client
-                    .get(image_url)
-                    .send()
-                    .await
-                    .unwrap()
-                    .bytes()
+        let mut images = Vec::new();
+        if let Some(image) = Self::preview_image(&post) {
+            images.push((MediaSlot::Preview, image.clone()));
+        }
+        if let Some(galleries) = post.galleries.as_ref() {
+            images.extend(
+                galleries
+                    .iter()
+                    .enumerate()
+                    .map(|(index, image)| (MediaSlot::Gallery(index), image.clone())),
+            );
+        }
+        post.crosspost_parent
+            .iter()
+            .enumerate()
+            .for_each(|(parent_index, parent)| {
+                images.extend(
+                    Self::crosspost_parent_images(parent)
+                        .iter()
+                        .enumerate()
+                        .map(|(index, image)| {
+                            (
+                                MediaSlot::CrosspostParent(parent_index, index),
+                                image.clone(),
+                            )
+                        }),
+                )
+            });
+
+        let images = images.into_iter().map(|(slot, image)| {
+            let app_event_sender = app_event_sender.clone();
+            let reddit_api = reddit_api.clone();
+            let picker = picker.clone();
+            let image_options = image_options.clone();
+            let hooks = hooks.clone();
+            let post_id = post.id.clone();
+            async move {
+                let media = match skip_images {
+                    Some(reason) => Media::Placeholder {
+                        image,
+                        reason: reason.to_string(),
+                    },
+                    None => {
+                        Self::load_image(&*reddit_api, &picker, image, image_options, &hooks).await
+                    }
+                };
+                app_event_sender
+                    .send(AppEvent::PostDetail(PostDetailEvent::ImageLoaded {
+                        post_id,
+                        slot,
+                        media,
+                    }))
                     .await
-                    .unwrap()
-            };
-            let image_source = image::load_from_memory(&image_bytes).unwrap();
-            {
-                let mut state = state.write().unwrap();
-                state.preview_image = Some(picker.new_resize_protocol(image_source));
+                    .unwrap();
             }
-            app_event_sender.send(AppEvent::Draw).await.unwrap();
+        });
+        join_all(images).await;
+    }
+
+    /// Download and decode an image, falling back to a placeholder on any failure
+    pub async fn load_image(
+        reddit_api: &dyn RedditApi,
+        picker: &Arc<Picker>,
+        image: ImageRef,
+        image_options: ImageOptions,
+        hooks: &Hooks,
+    ) -> Media {
+        let image_source = match reddit_api.get_media(&image.url).await {
+            Ok(bytes) => {
+                hooks::media_downloaded(hooks, &image.url, bytes.len());
+                let max_size = Self::max_image_size(picker);
+                let quantize =
+                    !image_options.dither && picker.protocol_type() == ProtocolType::Sixel;
+                // Decoding and resizing big images takes long enough to stall the runtime
+                let url = image.url.clone();
+                tokio::task::spawn_blocking(move || {
+                    image::load_from_memory(&bytes)
+                        .map(|v| Self::downscale_image(v, max_size))
+                        .map(|v| if quantize { Self::quantize_image(v) } else { v })
+                        .map_err(|source| NgoredError::Image { url, source }.to_string())
+                })
+                .await
+                .unwrap_or_else(|err| Err(err.to_string()))
+            }
+            Err(err) => Err(err.to_string()),
         };
+        match image_source {
+            Ok(image_source) => Media::Image(Box::new(LoadedImage::new(
+                image,
+                image_source,
+                picker.clone(),
+                image_options.resize,
+            ))),
+            Err(reason) => Media::Placeholder { image, reason },
+        }
     }
 
-    async fn load_crosspost_parent_medias(
-        state: Arc<RwLock<PostDetailState>>,
-        app_event_sender: Sender<AppEvent>,
-        reddit_api: Arc<RedditApi>,
-        picker: Arc<Picker>,
+    /// Pixel size of the whole terminal, no image is ever drawn bigger than this
+    fn max_image_size(picker: &Picker) -> (u32, u32) {
+        let (columns, rows) = crossterm::terminal::size().unwrap_or((200, 60));
+        let (font_width, font_height) = picker.font_size();
+        (
+            columns as u32 * font_width as u32,
+            rows as u32 * font_height as u32,
+        )
+    }
+
+    /// Shrink the image once here so the draw only resizes a small image
+    fn downscale_image(image: DynamicImage, (max_width, max_height): (u32, u32)) -> DynamicImage {
+        if image.width() <= max_width && image.height() <= max_height {
+            return image;
+        }
+        image.resize(max_width, max_height, FilterType::Triangle)
+    }
+
+    /// Map the image to a palette without error diffusion, the sixel encoder only dithers images
+    /// with more colors than it can show
+    fn quantize_image(image: DynamicImage) -> DynamicImage {
+        let mut pixels = image.into_rgba8();
+        // Transparent pixels would be blended with the background into new colors
+        for pixel in pixels.pixels_mut() {
+            let [r, g, b, a] = pixel.0.map(u16::from);
+            pixel.0 = [r * a / 255, g * a / 255, b * a / 255, 255].map(|v| v as u8);
+        }
+        // One color is left for the background the image is padded with
+        let quant = NeuQuant::new(10, SIXEL_COLORS - 1, pixels.as_raw());
+        for pixel in pixels.pixels_mut() {
+            quant.map_pixel(&mut pixel.0);
+            // The encoder's palette keeps 5 bits of each channel, anything finer is diffused
+            for channel in &mut pixel.0[..3] {
+                *channel &= 0xf8;
+            }
+        }
+        DynamicImage::ImageRgba8(pixels)
+    }
+
+    async fn load_comments(
+        app_event_sender: &Sender<AppEvent>,
+        post: &Post,
+        reddit_api: &dyn RedditApi,
     ) {
-        let crosspost_parents = state.read().unwrap().post.crosspost_parent.clone();
-        let crosspost_parents_medias = crosspost_parents.into_iter().filter_map(|mut v| {
-            if let Some(gallery_images) = v.galleries.take() {
-                let reddit_api = reddit_api.clone();
-                let picker = picker.clone();
-
-                let gallery_images = gallery_images.into_iter().map(move |v| {
-                    let reddit_api = reddit_api.clone();
-                    let picker = picker.clone();
-                    async move {
-                        let image_bytes = {
-                            reddit_api
-                                .client
-                                .get(v)
-                                .send()
-                                .await
-                                .unwrap()
-                                .bytes()
-                                .await
-                                .unwrap()
-                        };
-                        let image_source = image::load_from_memory(&image_bytes).unwrap();
-                        Some(picker.new_resize_protocol(image_source))
+        let comments = reddit_api
+            .get_post_comment(&post.subreddit, &post.id)
+            .await
+            .map(|res| {
+                res.into_children()
+                    .into_iter()
+                    .filter_map(|d| d.into_comment().map(Comment::from))
+                    .collect()
+            })
+            .map_err(|err| err.in_post(&post.id));
+        app_event_sender
+            .send(AppEvent::PostDetail(PostDetailEvent::CommentsLoaded {
+                post_id: post.id.clone(),
+                post: None,
+                comments,
+            }))
+            .await
+            .unwrap();
+    }
+
+    /// Load the focused comment with its parents, or every comment without one, returns the full
+    /// post replacing the stub
+    async fn load_comment_context(
+        app_event_sender: &Sender<AppEvent>,
+        post: &Post,
+        comment_id: Option<&str>,
+        reddit_api: &dyn RedditApi,
+    ) -> Post {
+        let res = match comment_id {
+            Some(comment_id) => {
+                reddit_api
+                    .get_comment_context(&post.subreddit, &post.id, comment_id, 8)
+                    .await
+            }
+            None => reddit_api.get_post(&post.subreddit, &post.id).await,
+        }
+        .map_err(|err| err.in_post(&post.id));
+        let (context_post, comments) = match res {
+            Ok((context_post, comments)) => (
+                context_post
+                    .into_children()
+                    .into_iter()
+                    .next()
+                    .and_then(Data::into_post)
+                    .map(Post::from),
+                Ok(comments
+                    .into_children()
+                    .into_iter()
+                    .filter_map(|d| d.into_comment().map(Comment::from))
+                    .collect()),
+            ),
+            Err(err) => (None, Err(err)),
+        };
+        app_event_sender
+            .send(AppEvent::PostDetail(PostDetailEvent::CommentsLoaded {
+                post_id: post.id.clone(),
+                post: context_post.clone().map(Box::new),
+                comments,
+            }))
+            .await
+            .unwrap();
+        context_post.unwrap_or_else(|| post.clone())
+    }
+
+    /// Apply the result of a loader task, results for a post no longer shown are dropped
+    pub fn handle_loaded(&mut self, event: PostDetailEvent) {
+        match event {
+            PostDetailEvent::CommentsLoaded {
+                post_id,
+                post,
+                comments,
+            } => {
+                if post_id != self.state.post.id {
+                    return;
+                }
+                self.state.loading_comment = false;
+                match comments {
+                    Ok(comments) => self.state.comments = comments,
+                    Err(err) => self.state.status = Some(Self::error_status(&err)),
+                }
+                if self.state.focused_comment.is_none()
+                    && let Some((offset, selected_comment)) =
+                        self.state.positions.get(&post_id).copied()
+                {
+                    let visible = Self::visible_comments(&self.state).count();
+                    self.state.scroll_state.set_offset(offset);
+                    self.state.selected_comment = selected_comment.filter(|v| *v < visible);
+                }
+                if let Some(post) = post {
+                    self.state.post = *post;
+                    self.init_medias();
+                }
+                if let Some(comment_id) = self.state.focused_comment.as_ref() {
+                    let selected_comment =
+                        Self::visible_comments(&self.state).position(|v| v.id == *comment_id);
+                    self.state.selected_comment = selected_comment;
+                    self.state.scroll_to_selected_comment = true;
+                }
+            }
+            PostDetailEvent::ImageLoaded {
+                post_id,
+                slot,
+                media,
+            } => {
+                if post_id != self.state.post.id {
+                    return;
+                }
+                let target = match slot {
+                    MediaSlot::Preview => self.state.preview_image.as_mut(),
+                    MediaSlot::Gallery(index) => self
+                        .state
+                        .medias
+                        .as_mut()
+                        .and_then(|(_, v)| v.get_mut(index)),
+                    MediaSlot::CrosspostParent(parent_index, index) => self
+                        .state
+                        .crosspost_parents_medias
+                        .as_mut()
+                        .and_then(|v| v.get_mut(parent_index))
+                        .and_then(|(_, v)| v.get_mut(index)),
+                };
+                if let Some(target) = target {
+                    *target = media;
+                }
+            }
+            PostDetailEvent::AccountDataLoaded {
+                moderated_subs,
+                friends,
+            } => {
+                self.state.moderated_subs = Some(moderated_subs);
+                self.state.friends = Some(friends);
+            }
+            PostDetailEvent::FriendUpdated {
+                username,
+                is_friend,
+            } => {
+                if let Some(friends) = self.state.friends.as_mut() {
+                    friends.retain(|v| !v.eq_ignore_ascii_case(&username));
+                    if is_friend {
+                        friends.push(username.clone());
                     }
+                }
+                self.state.status = Some(if is_friend {
+                    Line::from(vec![
+                        "★ friended".fg(Color::Yellow).bold(),
+                        format!(" u/{}", username).into(),
+                    ])
+                } else {
+                    Line::from(vec![
+                        "☆ unfriended".bold(),
+                        format!(" u/{}", username).into(),
+                    ])
                 });
-                Some(async move {
-                    join_all(gallery_images)
-                        .await
-                        .into_iter()
-                        .flatten()
-                        .collect::<Vec<_>>()
-                })
-            } else {
-                None
+            }
+            PostDetailEvent::Moderated {
+                post_id,
+                comment_id,
+                action,
+            } => {
+                if post_id != self.state.post.id {
+                    return;
+                }
+                let state = &mut self.state;
+                let flags = match &comment_id {
+                    Some(comment_id) => Comment::find_mut(&mut state.comments, comment_id)
+                        .map(|v| (&mut v.approved, &mut v.removed, &mut v.locked)),
+                    None => Some((
+                        &mut state.post.approved,
+                        &mut state.post.removed,
+                        &mut state.post.locked,
+                    )),
+                };
+                if let Some((approved, removed, locked)) = flags {
+                    match action {
+                        ModAction::Approve => (*approved, *removed) = (true, false),
+                        ModAction::Remove | ModAction::Spam => {
+                            (*approved, *removed) = (false, true)
+                        }
+                        ModAction::Lock => *locked = true,
+                        ModAction::Unlock => *locked = false,
+                    }
+                }
+                let target = if comment_id.is_some() {
+                    " comment"
+                } else {
+                    " post"
+                };
+                state.status = Some(Line::from(vec![mod_action_label(action), target.into()]));
+            }
+            PostDetailEvent::DuplicatesLoaded { post_id, items } => {
+                if post_id != self.state.post.id {
+                    return;
+                }
+                if let Some(duplicates) = self.state.duplicates.as_mut() {
+                    duplicates.loading = false;
+                    match items {
+                        Ok(items) => {
+                            if !items.is_empty() {
+                                duplicates.list_state.select(Some(0));
+                            }
+                            duplicates.items = items;
+                        }
+                        Err(err) => duplicates.error = Some(err.to_string()),
+                    }
+                }
+            }
+            PostDetailEvent::Failed { post_id, error } => {
+                if post_id != self.state.post.id {
+                    return;
+                }
+                self.state.status = Some(Self::error_status(&error));
+            }
+            PostDetailEvent::VideoClosed(result) => {
+                self.state.loading_video = false;
+                if let Err(err) = result {
+                    self.state.status = Some(Self::error_status(&err));
+                }
+            }
+            PostDetailEvent::SlideshowTick { post_id } => {
+                if post_id == self.state.post.id {
+                    Self::next_image(&mut self.state);
+                }
+            }
+        }
+    }
+
+    /// Share or copy the post
+    fn share(&mut self, action: Action) {
+        let state = &mut self.state;
+        if let Some(status) = share_post(&state.post, action, &self.opener) {
+            state.status = Some(status);
+        }
+    }
+
+    fn error_status(error: &NgoredError) -> Line<'static> {
+        Line::from(error.to_string().fg(Color::Red))
+    }
+
+    fn load_account_data(&mut self) {
+        if !self.reddit_api.has_account() {
+            return;
+        }
+        if self.state.moderated_subs.is_some() {
+            return;
+        }
+        // Fetched once per session, mark as loaded right away to avoid duplicate requests
+        self.state.moderated_subs = Some(Vec::default());
+        self.state.friends = Some(Vec::default());
+        tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let (moderated_subs, friends) =
+                    tokio::join!(reddit_api.get_moderated_subs(), reddit_api.get_friends());
+                // Moderation and friend markers are extras, the post reads fine without them
+                let moderated_subs = moderated_subs
+                    .map(|res| {
+                        res.into_children()
+                            .into_iter()
+                            .filter_map(|v| v.into_subreddit().map(|v| v.display_name))
+                            .collect()
+                    })
+                    .unwrap_or_else(|err| {
+                        debug!("Cannot load moderated subs: {}", err);
+                        Vec::default()
+                    });
+                let friends = friends.unwrap_or_else(|err| {
+                    debug!("Cannot load friends: {}", err);
+                    Vec::default()
+                });
+                app_event_sender
+                    .send(AppEvent::PostDetail(PostDetailEvent::AccountDataLoaded {
+                        moderated_subs,
+                        friends,
+                    }))
+                    .await
+                    .unwrap();
             }
         });
+    }
 
-        let crosspost_parents_medias = join_all(crosspost_parents_medias)
-            .await
+    /// Friend or unfriend the author of the selected comment, or the post if no comment is selected
+    fn toggle_friend(&self) {
+        let Some(friends) = self.state.friends.as_ref() else {
+            return;
+        };
+        let username = Self::selected_comment(&self.state)
+            .map(|v| v.author.clone())
+            .unwrap_or_else(|| self.state.post.author.clone());
+        let is_friend = !friends.iter().any(|v| v.eq_ignore_ascii_case(&username));
+
+        tokio::spawn({
+            let post_id = self.state.post.id.clone();
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let event = match reddit_api.set_friend(&username, is_friend).await {
+                    Ok(()) => PostDetailEvent::FriendUpdated {
+                        username,
+                        is_friend,
+                    },
+                    Err(error) => PostDetailEvent::Failed { post_id, error },
+                };
+                app_event_sender
+                    .send(AppEvent::PostDetail(event))
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
+    fn is_moderator(state: &PostDetailState) -> bool {
+        state.moderated_subs.as_ref().is_some_and(|v| {
+            v.iter()
+                .any(|v| v.eq_ignore_ascii_case(&state.post.subreddit))
+        })
+    }
+
+    fn comment_rows(state: &PostDetailState) -> Vec<CommentRow<'_>> {
+        let max_depth = if state.post.contest_mode && state.collapse_contest_replies {
+            Some(0)
+        } else {
+            state.max_comment_depth
+        };
+        state
+            .comments
+            .iter()
+            .flat_map(|v| {
+                v.flatten(
+                    max_depth,
+                    &state.expanded_comments,
+                    &state.collapsed_comments,
+                )
+            })
+            .collect()
+    }
+
+    /// Visible comments in display order, `selected_comment` indexes into this
+    fn visible_comments(state: &PostDetailState) -> impl Iterator<Item = &Comment> {
+        Self::comment_rows(state)
             .into_iter()
-            .map(|v| (0, v))
+            .filter_map(|v| match v {
+                CommentRow::Comment(_, comment) => Some(comment),
+                CommentRow::Continue { .. } => None,
+            })
+    }
+
+    fn selected_comment(state: &PostDetailState) -> Option<&Comment> {
+        let selected_comment = state.selected_comment?;
+        Self::visible_comments(state).nth(selected_comment)
+    }
+
+    /// Show or hide the replies of the selected comment cut off by the depth limit, a collapsed
+    /// comment is expanded instead
+    fn toggle_expand_comment(&mut self) {
+        let state = &mut self.state;
+        let Some(comment_id) = Self::selected_comment(state).map(|v| v.id.clone()) else {
+            return;
+        };
+        if state.collapsed_comments.contains(&comment_id) {
+            self.toggle_collapse_comment();
+            return;
+        }
+        if !state.expanded_comments.remove(&comment_id) {
+            let is_cut_off = Self::comment_rows(state).iter().any(
+                |v| matches!(v, CommentRow::Continue { parent_id, .. } if *parent_id == comment_id),
+            );
+            if is_cut_off {
+                state.expanded_comments.insert(comment_id);
+            }
+        }
+    }
+
+    /// Hide or show the replies of the selected comment, remembered for the thread
+    fn toggle_collapse_comment(&mut self) {
+        let state = &mut self.state;
+        let Some(comment) = Self::selected_comment(state) else {
+            return;
+        };
+        if comment.replies.is_empty() {
+            return;
+        }
+        let comment_id = comment.id.clone();
+        if !state.collapsed_comments.remove(&comment_id) {
+            state.collapsed_comments.insert(comment_id);
+        }
+        let saved = self
+            .state_store
+            .write()
+            .unwrap()
+            .set_collapsed_comments(&state.post.id, &state.collapsed_comments);
+        if let Err(err) = saved {
+            state.status = Some(Self::error_status(&err));
+        }
+    }
+
+    fn select_comment(&mut self, forward: bool) {
+        let state = &mut self.state;
+        state.search_line = None;
+        state.focused_link = None;
+        let comment_count = Self::visible_comments(state).count();
+        state.selected_comment = match (state.selected_comment, forward) {
+            (None, true) if comment_count > 0 => Some(0),
+            (Some(i), true) => Some((i + 1).min(comment_count.saturating_sub(1))),
+            (Some(0), false) => None,
+            (Some(i), false) => Some(i - 1),
+            (selected_comment, _) => selected_comment,
+        };
+        state.scroll_to_selected_comment = true;
+    }
+
+    /// Select the next comment by the author of the selected comment, or by OP if no comment is
+    /// selected, wrapping around
+    fn select_same_author_comment(&mut self) {
+        let state = &mut self.state;
+        let author = Self::selected_comment(state)
+            .map(|v| v.author.clone())
+            .unwrap_or_else(|| state.post.author.clone());
+        let same_author: Vec<usize> = Self::visible_comments(state)
+            .enumerate()
+            .filter(|(_, comment)| comment.author == author)
+            .map(|(index, _)| index)
             .collect();
+        let next = match state.selected_comment {
+            Some(selected) => same_author
+                .iter()
+                .find(|v| **v > selected)
+                .or(same_author.first()),
+            None => same_author.first(),
+        };
+        if let Some(next) = next.copied() {
+            state.search_line = None;
+            state.focused_link = None;
+            state.selected_comment = Some(next);
+            state.scroll_to_selected_comment = true;
+        }
+    }
 
-        state.write().unwrap().crosspost_parents_medias = Some(crosspost_parents_medias);
+    /// Body lines then flattened comments matching the search query, in the order they're shown
+    fn search_matches(state: &PostDetailState) -> Vec<SearchMatch> {
+        let Some(search) = state.search.as_ref() else {
+            return Vec::default();
+        };
+        let body_lines = state
+            .wrap_cache
+            .wrap(&state.post.body, state.body_width)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, line)| line.to_lowercase().contains(search.as_str()))
+            .map(|(index, _)| SearchMatch::BodyLine(index));
+        let comments = Self::visible_comments(state)
+            .enumerate()
+            .filter(|(_, comment)| comment.matches(search))
+            .map(|(index, _)| SearchMatch::Comment(index));
+        body_lines.chain(comments).collect()
+    }
 
-        app_event_sender.send(AppEvent::Draw).await.unwrap();
+    /// Where the search continues from, the body line it's on or else the selected comment
+    fn search_position(state: &PostDetailState) -> Option<SearchMatch> {
+        state
+            .search_line
+            .map(SearchMatch::BodyLine)
+            .or(state.selected_comment.map(SearchMatch::Comment))
     }
 
-    async fn load_gallery_images(
-        state: Arc<RwLock<PostDetailState>>,
-        app_event_sender: Sender<AppEvent>,
-        reddit_api: Arc<RedditApi>,
-        picker: Arc<Picker>,
-    ) {
-        let gallery_images = state.read().unwrap().post.galleries.clone();
-        if let Some(gallery_images) = gallery_images {
-            let gallery_images = gallery_images.into_iter().map(|v| async {
-                let image_bytes = {
-                    reddit_api
-                        .client
-                        .get(v)
-                        .send()
-                        .await
-                        .unwrap()
-                        .bytes()
-                        .await
-                        .unwrap()
+    /// Go to the next or previous search match, wrapping around
+    fn select_search_match(&mut self, forward: bool) {
+        let state = &mut self.state;
+        let matches = Self::search_matches(state);
+        let next = match (Self::search_position(state), forward) {
+            (None, true) => matches.first(),
+            (None, false) => matches.last(),
+            (Some(current), true) => matches.iter().find(|v| **v > current).or(matches.first()),
+            (Some(current), false) => matches
+                .iter()
+                .rev()
+                .find(|v| **v < current)
+                .or(matches.last()),
+        };
+        match next.copied() {
+            Some(SearchMatch::BodyLine(line)) => {
+                state.focused_link = None;
+                state.selected_comment = None;
+                state.search_line = Some(line);
+                state.scroll_to_body_line = Some(line);
+            }
+            Some(SearchMatch::Comment(index)) => {
+                state.focused_link = None;
+                state.search_line = None;
+                state.selected_comment = Some(index);
+                state.scroll_to_selected_comment = true;
+            }
+            None => {}
+        }
+    }
+
+    /// Links of the body then of the visible comments, in the order they're shown
+    fn link_positions(state: &PostDetailState) -> Vec<LinkPosition> {
+        let body = (0..text::links(&state.post.body).len()).map(|index| LinkPosition {
+            comment: None,
+            index,
+        });
+        let comments = Self::visible_comments(state)
+            .enumerate()
+            .flat_map(|(comment, v)| {
+                (0..text::links(&v.body).len()).map(move |index| LinkPosition {
+                    comment: Some(comment),
+                    index,
+                })
+            });
+        body.chain(comments).collect()
+    }
+
+    /// The caption of a gallery image and the link attached to it, wrapped to `width`
+    fn caption_lines(
+        image: &ImageRef,
+        width: u16,
+        theme: Theme,
+        wrap_cache: &WrapCache,
+    ) -> Vec<Line<'static>> {
+        let caption = image.caption.iter().flat_map(|v| {
+            wrap_cache
+                .wrap(v, width)
+                .into_iter()
+                .map(|v| Line::from(v).italic())
+        });
+        let outbound_url = image.outbound_url.iter().flat_map(|v| {
+            wrap_cache
+                .wrap(v, width)
+                .into_iter()
+                .map(|v| Line::from(v).fg(theme.muted()))
+        });
+        caption.chain(outbound_url).collect()
+    }
+
+    /// Every url and sub mention of the post and the visible comments, what link hints are
+    /// looked for in
+    fn links(state: &PostDetailState) -> Vec<String> {
+        let body = text::links(&state.post.body);
+        let comments = Self::visible_comments(state).flat_map(|v| text::links(&v.body));
+        // Links attached to the gallery images in view
+        let galleries = state
+            .medias
+            .iter()
+            .chain(state.crosspost_parents_medias.iter().flatten())
+            .filter_map(|(index, images)| images.get(*index)?.image().outbound_url.as_deref());
+        state
+            .post
+            .external_url()
+            .into_iter()
+            .chain(galleries)
+            .chain(body)
+            .chain(comments)
+            .map(String::from)
+            .collect()
+    }
+
+    fn focused_link_url(state: &PostDetailState) -> Option<String> {
+        let position = state.focused_link?;
+        let text = match position.comment {
+            Some(comment) => &Self::visible_comments(state).nth(comment)?.body,
+            None => &state.post.body,
+        };
+        text::links(text).get(position.index).map(|v| v.to_string())
+    }
+
+    /// Open a link from `links`, a sub mention in its post list and anything else in the browser.
+    /// There's no profile screen, user mentions open the profile on reddit
+    async fn open_link(&mut self, link: &str) -> Result<(), NgoredError> {
+        if let Some(sub) = text::mentioned_sub(link) {
+            self.app_event_sender
+                .send(AppEvent::OpenPostList(sub.to_string()))
+                .await?;
+            return Ok(());
+        }
+        let url = match text::mentioned_user(link) {
+            Some(user) => format!("https://www.reddit.com/user/{}", user),
+            None => link.to_string(),
+        };
+        if let Err(err) = self.opener.open(&url) {
+            self.state.status = Some(Self::error_status(&err));
+        }
+        Ok(())
+    }
+
+    /// Focus the next or previous link, starting from the selected comment and wrapping around
+    fn select_link(&mut self, forward: bool) {
+        let state = &mut self.state;
+        let links = Self::link_positions(state);
+        let selected = state.selected_comment;
+        let next = match (state.focused_link, forward) {
+            (None, true) => links
+                .iter()
+                .find(|v| v.comment >= selected)
+                .or(links.first()),
+            (None, false) => links
+                .iter()
+                .rev()
+                .find(|v| v.comment <= selected)
+                .or(links.last()),
+            (Some(current), true) => links.iter().find(|v| **v > current).or(links.first()),
+            (Some(current), false) => links.iter().rev().find(|v| **v < current).or(links.last()),
+        };
+        let Some(next) = next.copied() else {
+            state.status = Some(Line::from("no links"));
+            return;
+        };
+        state.focused_link = Some(next);
+        state.search_line = None;
+        state.selected_comment = next.comment;
+        match next.comment {
+            Some(_) => state.scroll_to_selected_comment = true,
+            None => {
+                let url = text::links(&state.post.body)[next.index].to_string();
+                state.scroll_to_body_line = state
+                    .wrap_cache
+                    .wrap(&state.post.body, state.body_width)
+                    .iter()
+                    .position(|line| {
+                        // Urls longer than the line are broken, their start ends the line
+                        line.contains(&url)
+                            || line.split_whitespace().next_back().is_some_and(|word| {
+                                word.starts_with("http") && url.starts_with(word)
+                            })
+                    });
+            }
+        }
+    }
+
+    /// Scroll so rows `y..y + height` of the content are in view with `scrolloff` lines around
+    /// them, rows taller than the page are shown from their top
+    fn scroll_into_view(state: &mut PostDetailState, y: u16, height: u16, page_height: u16) {
+        let offset = state.scroll_state.offset();
+        let scrolloff = state.scrolloff.min(page_height / 2);
+        let top = y.saturating_sub(scrolloff);
+        let bottom = y + height + scrolloff;
+        if top < offset {
+            state.scroll_state.set_offset(top);
+        } else if bottom > offset + page_height {
+            state
+                .scroll_state
+                .set_offset(bottom.saturating_sub(page_height).min(y));
+        }
+    }
+
+    async fn handle_search_input_event(&mut self, event: &Event) -> Result<(), NgoredError> {
+        let Some(search_input) = self.search_input.as_mut() else {
+            return Ok(());
+        };
+        match event {
+            Event::Key(KeyEvent {
+                code: KeyCode::Esc,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.search_input = None;
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                let query = search_input.value().trim().to_lowercase();
+                self.search_input = None;
+                self.state.search = (!query.is_empty()).then_some(query);
+                self.select_search_match(true);
+            }
+            _ => {
+                search_input.handle_event(event);
+            }
+        }
+        self.app_event_sender.send(AppEvent::Draw).await?;
+        Ok(())
+    }
+
+    fn draw_search_input(search_input: &Input, frame: &mut ratatui::Frame, area: Rect) {
+        let [center_vertical] = Layout::vertical([Constraint::Length(3)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [center] = Layout::horizontal([Constraint::Percentage(75)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        let buf = frame.buffer_mut();
+        Clear.render(center, buf);
+        Paragraph::new(search_input.value())
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("Search"),
+            )
+            .render(center, buf);
+        let scroll = search_input.visual_scroll(center.width.max(3) as usize - 3);
+        let x = search_input.visual_cursor().max(scroll) - scroll + 1;
+        frame.set_cursor_position((center.x + x as u16, center.y + 1));
+    }
+
+    /// Apply a mod action to the selected comment, or the post if no comment is selected
+    fn moderate(&self, action: impl FnOnce(bool) -> ModAction) {
+        if !Self::is_moderator(&self.state) {
+            return;
+        }
+        let (fullname, comment_id, action) = match Self::selected_comment(&self.state) {
+            Some(comment) => (
+                comment.fullname(),
+                Some(comment.id.clone()),
+                action(comment.locked),
+            ),
+            None => (
+                self.state.post.fullname(),
+                None,
+                action(self.state.post.locked),
+            ),
+        };
+
+        tokio::spawn({
+            let post_id = self.state.post.id.clone();
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let event = match reddit_api.moderate(&fullname, action).await {
+                    Ok(()) => PostDetailEvent::Moderated {
+                        post_id,
+                        comment_id,
+                        action,
+                    },
+                    Err(error) => PostDetailEvent::Failed { post_id, error },
                 };
-                let image_source = image::load_from_memory(&image_bytes).unwrap();
-                picker.new_resize_protocol(image_source)
+                app_event_sender
+                    .send(AppEvent::PostDetail(event))
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
+    fn load_duplicates(&mut self) {
+        self.state.duplicates = Some(DuplicatesState {
+            loading: true,
+            items: Vec::default(),
+            error: None,
+            list_state: ListState::default(),
+        });
+        let duplicates_handle = tokio::spawn({
+            let post_id = self.state.post.id.clone();
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let items = reddit_api.get_duplicates(&post_id).await.map(|res| {
+                    res.into_children()
+                        .into_iter()
+                        .filter_map(|i| i.into_post().map(Post::from))
+                        .collect()
+                });
+                app_event_sender
+                    .send(AppEvent::PostDetail(PostDetailEvent::DuplicatesLoaded {
+                        post_id,
+                        items,
+                    }))
+                    .await
+                    .unwrap();
+            }
+        });
+        self.state.duplicates_handle = Some(duplicates_handle);
+    }
+
+    fn close_duplicates(&mut self) {
+        if let Some(duplicates_handle) = self.state.duplicates_handle.take() {
+            duplicates_handle.abort();
+        }
+        self.state.duplicates = None;
+    }
+
+    async fn handle_duplicates_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        match action {
+            Action::Back => {
+                self.close_duplicates();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Down => {
+                if let Some(duplicates) = self.state.duplicates.as_mut() {
+                    duplicates.list_state.select_next();
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Up => {
+                if let Some(duplicates) = self.state.duplicates.as_mut() {
+                    duplicates.list_state.select_previous();
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Open => {
+                let post = self
+                    .state
+                    .duplicates
+                    .as_ref()
+                    .and_then(|v| v.list_state.selected().and_then(|i| v.items.get(i)))
+                    .cloned();
+                if let Some(post) = post {
+                    self.load(post);
+                    self.app_event_sender.send(AppEvent::Draw).await?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw_duplicates(
+        duplicates: &mut DuplicatesState,
+        compact_scores: bool,
+        theme: Theme,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let [center_vertical] = Layout::vertical([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [center] = Layout::horizontal([Constraint::Percentage(75)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        Clear.render(center, buf);
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title("Other discussions");
+        if duplicates.loading || duplicates.items.is_empty() {
+            let text = if let Some(error) = &duplicates.error {
+                error.as_str()
+            } else if duplicates.loading {
+                "Loading..."
+            } else {
+                "No other discussions"
+            };
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .block(block)
+                .render(center, buf);
+        } else {
+            let items = duplicates.items.iter().map(|post| {
+                Line::from(vec![
+                    format!("r/{}", post.subreddit).italic(),
+                    format!(
+                        " • 👍🏻{} • 💬{} • ",
+                        post.score_text(compact_scores),
+                        post.num_comments
+                    )
+                    .into(),
+                    post.title.clone().bold(),
+                ])
             });
-            let gallery_images = join_all(gallery_images).await;
-            state.write().unwrap().medias = Some((0, gallery_images));
+            let list = List::new(items)
+                .highlight_style(theme.selected_style())
+                .highlight_symbol(theme.highlight_symbol())
+                .block(block);
+            StatefulWidget::render(list, center, buf, &mut duplicates.list_state);
+        }
+    }
 
-            app_event_sender.send(AppEvent::Draw).await.unwrap();
+    /// The current image of the gallery, or else the preview
+    fn image_in_view(state: &mut PostDetailState) -> Option<&mut Media> {
+        match state.medias.as_mut() {
+            Some((index, images)) => images.get_mut(*index),
+            None => state.preview_image.as_mut(),
         }
     }
 
-    async fn load_comments(
-        state: Arc<RwLock<PostDetailState>>,
-        app_event_sender: Sender<AppEvent>,
-        sub: &str,
-        post_id: &str,
-        reddit_api: Arc<RedditApi>,
+    fn previous_image(state: &mut PostDetailState) {
+        if let Some((index, images)) = state.medias.as_mut() {
+            if *index == 0 {
+                *index = images.len() - 1;
+            } else {
+                *index -= 1;
+            }
+        };
+        if let Some(crosspost_parents_medias) = state.crosspost_parents_medias.as_mut() {
+            crosspost_parents_medias
+                .iter_mut()
+                .for_each(|(index, images)| {
+                    if *index == 0 {
+                        *index = images.len().saturating_sub(1);
+                    } else {
+                        *index -= 1;
+                    }
+                });
+        }
+    }
+
+    /// Advance every gallery of the post, wrapping back to the first image
+    fn next_image(state: &mut PostDetailState) {
+        if let Some((index, images)) = state.medias.as_mut() {
+            *index += 1;
+            if *index >= images.len() {
+                *index = 0;
+            }
+        };
+        if let Some(crosspost_parents_medias) = state.crosspost_parents_medias.as_mut() {
+            crosspost_parents_medias
+                .iter_mut()
+                .for_each(|(index, images)| {
+                    *index += 1;
+                    if *index >= images.len() {
+                        *index = 0;
+                    }
+                });
+        }
+    }
+
+    /// Advance the gallery every `slideshow_interval` until stopped or paused
+    fn play_slideshow(&mut self) {
+        let interval = self.state.slideshow_interval;
+        let post_id = self.state.post.id.clone();
+        let app_event_sender = self.app_event_sender.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let tick = PostDetailEvent::SlideshowTick {
+                    post_id: post_id.clone(),
+                };
+                if app_event_sender
+                    .send(AppEvent::PostDetail(tick))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        self.state.slideshow = Some(Slideshow::Playing(handle));
+    }
+
+    fn handle_gallery_grid_action(&mut self, action: Action) {
+        let state = &mut self.state;
+        let (Some(grid), Some((index, images))) = (state.gallery_grid.as_mut(), &mut state.medias)
+        else {
+            return;
+        };
+        let last = images.len().saturating_sub(1);
+        match action {
+            Action::Back => state.gallery_grid = None,
+            Action::Open => {
+                *index = grid.cursor;
+                state.gallery_grid = None;
+            }
+            Action::NextImage => grid.cursor = (grid.cursor + 1).min(last),
+            Action::PreviousImage => grid.cursor = grid.cursor.saturating_sub(1),
+            Action::Down if grid.cursor + grid.columns <= last => grid.cursor += grid.columns,
+            Action::Up => grid.cursor = grid.cursor.saturating_sub(grid.columns),
+            Action::Top => grid.cursor = 0,
+            Action::Bottom => grid.cursor = last,
+            _ => {}
+        }
+    }
+
+    /// Tiles as many images as fit in `area`, scrolled by rows to keep the cursor in view. The
+    /// images out of view give up their buffers
+    fn draw_gallery_grid(
+        grid: &mut GalleryGridState,
+        images: &mut [Media],
+        theme: Theme,
+        area: Rect,
+        buf: &mut Buffer,
     ) {
-        state.write().unwrap().loading_comment = true;
-        app_event_sender.send(AppEvent::Draw).await.unwrap();
+        Clear.render(area, buf);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(format!("Gallery {}/{}", grid.cursor + 1, images.len()))
+            .title_bottom(Line::from("[Enter] view • [Esc] back").right_aligned());
+        let inner = block.inner(area);
+        block.render(area, buf);
 
-        let comments = reddit_api.get_post_comment(sub, post_id).await;
+        grid.columns = (inner.width / GRID_TILE_WIDTH).max(1) as usize;
+        let rows = (inner.height / GRID_TILE_HEIGHT).max(1) as usize;
+        let cursor_row = grid.cursor / grid.columns;
+        if cursor_row < grid.top_row {
+            grid.top_row = cursor_row;
+        } else if cursor_row >= grid.top_row + rows {
+            grid.top_row = cursor_row + 1 - rows;
+        }
+        let visible = grid.top_row * grid.columns..(grid.top_row + rows) * grid.columns;
 
-        let comments = comments
-            .as_listing()
-            .children
-            .into_iter()
-            .filter_map(|d| d.as_comment_opt().map(|v| Comment::from(v)))
-            .collect();
+        for (index, media) in images.iter_mut().enumerate() {
+            if !visible.contains(&index) {
+                media.release();
+                continue;
+            }
+            let position = index - visible.start;
+            let tile = Rect::new(
+                inner.x + (position % grid.columns) as u16 * GRID_TILE_WIDTH,
+                inner.y + (position / grid.columns) as u16 * GRID_TILE_HEIGHT,
+                GRID_TILE_WIDTH,
+                GRID_TILE_HEIGHT,
+            )
+            .intersection(inner);
+            let mut block = Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(format!("{}", index + 1));
+            if index == grid.cursor {
+                block = block
+                    .border_set(theme.selected_border_set(border::ROUNDED))
+                    .border_style(theme.selected_comment_style());
+            }
+            let tile_inner = block.inner(tile);
+            block.render(tile, buf);
+            let size = media.size_for(tile_inner);
+            let [image_area] = Layout::vertical([Constraint::Length(size.height)])
+                .flex(Flex::Center)
+                .areas(tile_inner);
+            let [image_area] = Layout::horizontal([Constraint::Length(size.width)])
+                .flex(Flex::Center)
+                .areas(image_area);
+            media.render(image_area, buf, theme);
+        }
+    }
+
+    /// Switch text-only mode and reload the post's images accordingly
+    fn toggle_text_only(&mut self) {
+        if let Some(medias_handle) = self.state.medias_handle.take() {
+            medias_handle.abort();
+        }
+        self.state.text_only = !self.state.text_only;
+        self.init_medias();
+        let medias_handle = tokio::spawn(Self::load_medias(
+            self.state.post.clone(),
+            self.skip_images_reason(),
+            self.state.image_options.clone(),
+            self.hooks.clone(),
+            self.app_event_sender.clone(),
+            self.reddit_api.clone(),
+            self.picker.clone(),
+        ));
+        self.state.medias_handle = Some(medias_handle);
+    }
+
+    /// `image_height` kept between a sliver and the whole view
+    fn clamp_image_height(percent: u16) -> u16 {
+        percent.clamp(IMAGE_HEIGHT_STEP, 100)
+    }
+
+    /// Where the post goes in `area`, a column of at most `max_content_width` in its middle
+    fn content_area(state: &PostDetailState, area: Rect) -> Rect {
+        let Some(max_content_width) = state.max_content_width else {
+            return area;
+        };
+        let [area] = Layout::horizontal([Constraint::Max(max_content_width)])
+            .flex(Flex::Center)
+            .areas(area);
+        area
+    }
+
+    /// Post and comments as plain lines without borders, images or indentation, each comment
+    /// announced with its depth and author so the structure survives being read aloud
+    fn draw_linear(&mut self, frame: &mut ratatui::Frame, frame_area: Rect) {
+        let area = Self::content_area(&self.state, frame_area);
+        let [area, _for_scrollbar] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+        let state = &mut self.state;
+        state.body_width = area.width;
+        let state_store = self.state_store.read().unwrap();
+        let post = &state.post;
+        let now = state.clock.now();
+        let wrap_cache = &state.wrap_cache;
+        let wrap_options = wrap_cache.options();
+
+        let mut lines: Vec<Line> = Vec::new();
+        if let Some(status) = state.status.clone() {
+            lines.push(status);
+        }
+        if let Some(url) = Self::focused_link_url(state) {
+            lines.push(Line::from(format!("Link: {}, Enter to open", url)));
+        }
+        let edited = post
+            .edited_at
+            .map(|v| format!(", edited {}", HumanTime::from(v - now)))
+            .unwrap_or_default();
+        lines.push(Line::from(format!(
+            "Post in r/{} by u/{}, {}{}",
+            post.subreddit,
+            post.author,
+            HumanTime::from(post.created_at - now),
+            edited
+        )));
+        lines.extend(
+            wrap_cache
+                .wrap(&format!("Title: {}", post.title), area.width)
+                .into_iter()
+                .map(Line::from),
+        );
+        lines.push(Line::from(format!(
+            "Score {}, {} comments",
+            post.score_text(state.compact_scores),
+            post.num_comments
+        )));
+        if post.preview_images.is_some() || post.galleries.is_some() {
+            lines.push(Line::from(
+                match self.hint_keys.get(&Action::OpenImageExternally) {
+                    Some(key) => format!("Has images, press {} to open externally", key),
+                    None => "Has images".to_string(),
+                },
+            ));
+        }
+        for (index, image) in post.galleries.iter().flatten().enumerate() {
+            let caption = image.caption.as_deref().map(|v| format!(", {}", v));
+            let link = image
+                .outbound_url
+                .as_deref()
+                .map(|v| format!(", links to {}", v));
+            if caption.is_some() || link.is_some() {
+                let line = format!(
+                    "Image {}{}{}",
+                    index + 1,
+                    caption.unwrap_or_default(),
+                    link.unwrap_or_default()
+                );
+                lines.extend(
+                    wrap_cache
+                        .wrap(&line, area.width)
+                        .into_iter()
+                        .map(Line::from),
+                );
+            }
+        }
+        if let Some(url) = post.outbound_link() {
+            lines.push(Line::from(format!("Links to {}", url)));
+        }
+        if let Some(embed) = &post.embed {
+            let byline = embed
+                .byline()
+                .map(|v| format!(", {}", v))
+                .unwrap_or_default();
+            lines.extend(
+                wrap_cache
+                    .wrap(&format!("Embed: {}{}", embed.title, byline), area.width)
+                    .into_iter()
+                    .map(Line::from),
+            );
+        }
+        for parent in &post.crosspost_parent {
+            lines.push(Line::from(format!(
+                "Crossposted from r/{} by u/{}, {}",
+                parent.subreddit,
+                parent.author,
+                HumanTime::from(parent.created_at - now)
+            )));
+            let body = if state.revealed_spoilers.contains(&post.id) {
+                parent.body.clone()
+            } else {
+                text::hide_spoilers(&parent.body)
+            };
+            let text = format!("Title: {}\n{}", parent.title, body);
+            lines.extend(
+                wrap_cache
+                    .wrap(text.trim_end(), area.width)
+                    .into_iter()
+                    .map(Line::from),
+            );
+        }
+        let body_start = lines.len() as u16;
+        let body = if state.revealed_spoilers.contains(&post.id) {
+            post.body.clone()
+        } else {
+            text::hide_spoilers(&post.body)
+        };
+        lines.extend(
+            wrap_cache
+                .wrap(&body, area.width)
+                .into_iter()
+                .map(|v| Line::from(theme::highlight(v, state.search.as_deref()))),
+        );
+        if let Some(poll) = &post.poll {
+            lines.push(Line::from(format!(
+                "Poll, {} votes:",
+                poll.total_vote_count
+            )));
+            for option in &poll.options {
+                let votes = option
+                    .vote_count
+                    .map(|v| format!(", {} votes", v))
+                    .unwrap_or_default();
+                lines.extend(
+                    text::wrap(
+                        &format!("Option: {}{}", option.text, votes),
+                        area.width,
+                        wrap_options,
+                    )
+                    .into_iter()
+                    .map(Line::from),
+                );
+            }
+        }
+        lines.push(Line::default());
+
+        let mut selected_line = None;
+        if state.loading_comment {
+            lines.push(Line::from("Loading comments"));
+        } else {
+            let mut index = 0;
+            for row in Self::comment_rows(state) {
+                let (depth, comment) = match row {
+                    CommentRow::Comment(depth, comment) => (depth, comment),
+                    CommentRow::Continue {
+                        depth,
+                        count,
+                        collapsed,
+                        ..
+                    } => {
+                        lines.push(Line::from(if collapsed {
+                            format!(
+                                "{} replies collapsed, press Enter on the parent to expand",
+                                count
+                            )
+                        } else {
+                            format!(
+                                "{} more replies at depth {}, press Enter on the parent to expand",
+                                count, depth
+                            )
+                        }));
+                        lines.push(Line::default());
+                        continue;
+                    }
+                };
+                let mut header = Vec::new();
+                if state.selected_comment == Some(index) {
+                    selected_line = Some(lines.len());
+                    header.push("Selected. ".to_string());
+                }
+                header.push(format!("Comment depth {} by u/{}", depth, comment.author));
+                if let Some(user_tag) = state_store.user_tag(&comment.author) {
+                    header.push(format!(" tagged {}", user_tag.text));
+                }
+                header.push(format!(
+                    ", score {}, {}",
+                    comment.score_text(state.compact_scores),
+                    HumanTime::from(comment.created_at - now)
+                ));
+                if state.last_visit.is_some_and(|v| comment.created_at > v) {
+                    header.push(", new".to_string());
+                }
+                header.push(":".to_string());
+                lines.extend(
+                    text::wrap(&header.concat(), area.width, wrap_options)
+                        .into_iter()
+                        .map(Line::from),
+                );
+                let body = if state.revealed_spoilers.contains(&comment.id) {
+                    comment.body.clone()
+                } else {
+                    text::hide_spoilers(&comment.body)
+                };
+                lines.extend(
+                    wrap_cache
+                        .wrap(&body, area.width)
+                        .into_iter()
+                        .map(Line::from),
+                );
+                lines.push(Line::default());
+                index += 1;
+            }
+        }
+
+        if let Some(line) = state.scroll_to_body_line.take() {
+            let y = body_start + line as u16;
+            Self::scroll_into_view(state, y, 1, area.height);
+        }
+        if let Some(selected_line) = selected_line.map(|v| v as u16)
+            && state.scroll_to_selected_comment
         {
-            let mut state = state.write().unwrap();
-            state.loading_comment = false;
-            state.comments = comments;
+            state.scroll_to_selected_comment = false;
+            let offset = state.scroll_state.offset();
+            let scrolloff = state.scrolloff.min(area.height / 2);
+            if selected_line < offset + scrolloff
+                || selected_line + scrolloff >= offset + area.height
+            {
+                state
+                    .scroll_state
+                    .set_offset(selected_line.saturating_sub(scrolloff));
+            }
+        }
+
+        VirtualScroll::new(
+            area,
+            lines.len() as u16,
+            false,
+            &mut state.scroll_state,
+            frame.buffer_mut(),
+        );
+        Paragraph::new(lines)
+            .scroll((state.scroll_state.offset(), 0))
+            .render(area, frame.buffer_mut());
+
+        if let Some(link_hints) = self.link_hints.as_mut() {
+            link_hints.render(
+                area,
+                frame.buffer_mut(),
+                &Self::links(state),
+                Vec::default(),
+            );
+        }
+        if let Some(duplicates) = state.duplicates.as_mut() {
+            Self::draw_duplicates(
+                duplicates,
+                state.compact_scores,
+                state.theme,
+                frame_area,
+                frame.buffer_mut(),
+            );
+        }
+        if let Some(user_tag_popup) = self.user_tag_popup.as_ref() {
+            user_tag_popup.draw(frame, frame_area);
+        }
+        if let Some(share_popup) = self.share_popup.as_mut() {
+            share_popup.draw(frame, frame_area);
+        }
+        if let Some(search_input) = self.search_input.as_ref() {
+            Self::draw_search_input(search_input, frame, frame_area);
         }
+    }
+
+    /// Only the current image of a gallery is drawn, the others don't need their buffers
+    fn release_hidden_gallery_images(index: usize, images: &mut [Media]) {
+        images
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .for_each(|(_, v)| v.release());
+    }
 
-        app_event_sender.send(AppEvent::Draw).await.unwrap();
+    /// Remember where the post was left, restored when its comments are loaded again
+    fn save_position(state: &mut PostDetailState) {
+        if !state.post.id.is_empty() {
+            state.positions.insert(
+                state.post.id.clone(),
+                (state.scroll_state.offset(), state.selected_comment),
+            );
+            if let Some((index, _)) = &state.medias {
+                state
+                    .gallery_positions
+                    .insert(state.post.id.clone(), *index);
+            }
+        }
     }
 
-    fn reset(&self) {
-        let mut state = self.state.write().unwrap();
+    fn reset(&mut self) {
+        self.wrapped_comments.bodies.clear();
+        let state = &mut self.state;
+        if let Some(load_handle) = state.load_handle.take() {
+            load_handle.abort();
+        }
+        if let Some(duplicates_handle) = state.duplicates_handle.take() {
+            duplicates_handle.abort();
+        }
+        if let Some(medias_handle) = state.medias_handle.take() {
+            medias_handle.abort();
+        }
+        Self::save_position(state);
+        state.duplicates = None;
+        state.gallery_grid = None;
+        if let Some(Slideshow::Playing(handle)) = state.slideshow.take() {
+            handle.abort();
+        }
+        state.selected_comment = None;
+        state.scroll_to_selected_comment = false;
+        state.status = None;
+        state.search = None;
+        state.search_line = None;
+        state.scroll_to_body_line = None;
+        state.focused_link = None;
+        state.last_visit = None;
+        state.focused_comment = None;
         state.post = Post::default();
         state.preview_image = None;
         state.comments.clear();
+        state.expanded_comments.clear();
+        state.collapsed_comments.clear();
+        state.revealed_spoilers.clear();
         state.loading_comment = false;
         state.loading_video = false;
         if let Some((_, mut galleries)) = state.medias.take() {
@@ -296,174 +2140,612 @@ impl PostDetailComponent {
     }
 }
 
+#[async_trait]
 impl Component for PostDetailComponent {
-    async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
-        if self.state.read().unwrap().loading_video {
+    fn key_context(&self) -> Option<KeyContext> {
+        if self.state.loading_video
+            || self.user_tag_popup.is_some()
+            || self.search_input.is_some()
+            || self.link_hints.is_some()
+        {
+            None
+        } else if self.state.duplicates.is_some() {
+            Some(KeyContext::Duplicates)
+        } else if self.state.gallery_grid.is_some() {
+            Some(KeyContext::GalleryGrid)
+        } else if self.share_popup.is_some() {
+            Some(KeyContext::Share)
+        } else {
+            Some(KeyContext::PostDetail)
+        }
+    }
+
+    fn resized(&mut self) {
+        self.state.wrap_cache.clear();
+    }
+
+    fn status_info(&self) -> StatusInfo {
+        let state = &self.state;
+        let comments = Self::visible_comments(state).collect::<Vec<_>>();
+        StatusInfo {
+            sub: Some(state.post.subreddit.clone()).filter(|v| !v.is_empty()),
+            sort: None,
+            position: state.selected_comment.map(|v| (v + 1, comments.len())),
+            unread: state.last_visit.map(|last_visit| {
+                comments
+                    .iter()
+                    .filter(|v| v.created_at > last_visit)
+                    .count()
+            }),
+        }
+    }
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        if self.state.duplicates.is_some() {
+            return self.handle_duplicates_action(action).await;
+        }
+        if self.state.gallery_grid.is_some() {
+            self.handle_gallery_grid_action(action);
+            self.app_event_sender.send(AppEvent::Draw).await?;
             return Ok(());
         }
-        match event {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char(char),
-                kind: KeyEventKind::Press,
-                ..
-            }) => match char {
-                'o' => {
-                    let state = self.state.read().unwrap();
-                    open::that(format!(
-                        "https://www.reddit.com/r/{}/comments/{}",
-                        state.post.subreddit, state.post.id
-                    ))
-                    .unwrap();
+        if let Some(share_popup) = self.share_popup.as_mut() {
+            match share_popup.handle_action(action) {
+                SharePopupEvent::None => {}
+                SharePopupEvent::Close => self.share_popup = None,
+                SharePopupEvent::Share(action) => {
+                    self.share_popup = None;
+                    self.share(action);
                 }
-                'v' => {
-                    let video_url = self.state.read().unwrap().post.video_url.clone();
-                    if let Some(video_url) = video_url {
-                        self.state.write().unwrap().loading_video = true;
-                        let (done_tx, done_rx) = oneshot::channel();
-                        self.app_event_sender
-                            .send(AppEvent::DrawWithCallback(done_tx))
-                            .await?;
-                        let state = self.state.clone();
-                        let app_event = self.app_event_sender.clone();
-                        tokio::spawn(async move {
-                            done_rx.await.unwrap();
-                            let mut mpv = Command::new("mpv")
-                                .arg(video_url)
-                                .stdin(Stdio::null())
-                                .stdout(Stdio::null())
-                                .stderr(Stdio::null())
-                                .spawn()
-                                .expect("failed to start mpv");
-                            mpv.wait().await.unwrap();
-                            state.write().unwrap().loading_video = false;
-                            app_event.send(AppEvent::Draw).await.unwrap();
-                        });
-                    }
-                }
-                'h' => {
-                    if let Some(load_handle) = self.state.write().unwrap().load_handle.take() {
-                        load_handle.abort();
-                    }
-                    self.reset();
-                    self.app_event_sender
-                        .send(AppEvent::ClosePostDetail)
-                        .await?;
+            }
+            self.app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
+        match action {
+            Action::Cancel => {
+                self.state.search = None;
+                self.state.search_line = None;
+                self.state.focused_link = None;
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::ToggleCollapse => {
+                self.toggle_collapse_comment();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Open => {
+                // Enter opens the focused link, or else expands the selected comment
+                let state = &mut self.state;
+                if let Some(url) = Self::focused_link_url(state) {
+                    self.open_link(&url).await?;
+                } else {
+                    self.toggle_expand_comment();
                 }
-                'j' => {
-                    self.state.write().unwrap().scroll_state.scroll_down();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::LinkHints | Action::CopyLinkHints => {
+                self.link_hints = Some(LinkHints::new(action == Action::CopyLinkHints));
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::NextLink | Action::PreviousLink => {
+                self.select_link(action == Action::NextLink);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Share => {
+                self.share_popup = Some(SharePopup::new(&self.state.post, self.state.theme));
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::OpenImageExternally => {
+                // The image in view, for when it can't be rendered in the terminal
+                let url = {
+                    let state = &self.state;
+                    state
+                        .medias
+                        .as_ref()
+                        .and_then(|(index, images)| images.get(*index))
+                        .or(state.preview_image.as_ref())
+                        .or_else(|| {
+                            state
+                                .crosspost_parents_medias
+                                .iter()
+                                .flatten()
+                                .find_map(|(index, images)| images.get(*index))
+                        })
+                        .map_or_else(|| state.post.url.clone(), |v| v.image().url.clone())
+                };
+                if let Err(err) = self.opener.open(&url) {
+                    self.state.status = Some(Self::error_status(&err));
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
-                'k' => {
-                    self.state.write().unwrap().scroll_state.scroll_up();
+            }
+            Action::ToggleTextOnly => self.toggle_text_only(),
+            Action::ShowDuplicates => {
+                self.load_duplicates();
+            }
+            Action::GalleryGrid => {
+                // Images aren't drawn for screen readers, nothing to pick from
+                let state = &mut self.state;
+                if let Some((index, images)) = state.medias.as_ref()
+                    && images.len() > 1
+                    && !self.screen_reader
+                {
+                    state.gallery_grid = Some(GalleryGridState {
+                        cursor: *index,
+                        columns: 1,
+                        top_row: 0,
+                    });
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
-                'J' => {
-                    self.state.write().unwrap().scroll_state.scroll_page_down();
+            }
+            Action::Search => {
+                let search = self.state.search.clone();
+                self.search_input = Some(Input::new(search.unwrap_or_default()));
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Refresh => {
+                self.refresh();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::ShowFullThread => {
+                let (post, is_focused) = {
+                    let state = &self.state;
+                    (state.post.clone(), state.focused_comment.is_some())
+                };
+                if is_focused {
+                    self.load_post(post, None);
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
-                'K' => {
-                    self.state.write().unwrap().scroll_state.scroll_page_up();
+            }
+            Action::NextSameAuthorComment => {
+                self.select_same_author_comment();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::NextSearchMatch => {
+                self.select_search_match(true);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PreviousSearchMatch => {
+                self.select_search_match(false);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::NextComment => {
+                self.select_comment(true);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PreviousComment => {
+                self.select_comment(false);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::TagUser => {
+                let author = {
+                    let state = &self.state;
+                    Self::selected_comment(state)
+                        .map(|v| v.author.clone())
+                        .unwrap_or_else(|| state.post.author.clone())
+                };
+                let user_tag = self.state_store.read().unwrap().user_tag(&author).cloned();
+                self.user_tag_popup = Some(UserTagPopup::new(author, user_tag.as_ref()));
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::CopyComment => {
+                let state = &mut self.state;
+                let Some(comment) = Self::selected_comment(state) else {
+                    return Ok(());
+                };
+                let status = clipboard::copy_with_status(
+                    &comment.body,
+                    &format!("comment by u/{}", comment.author),
+                );
+                state.status = Some(status);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::CopyTitle | Action::CopyPermalink | Action::CopyLink => {
+                self.share(action);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::ToggleFriend => self.toggle_friend(),
+            Action::Approve => self.moderate(|_| ModAction::Approve),
+            Action::Remove => self.moderate(|_| ModAction::Remove),
+            Action::ToggleLock => self.moderate(|locked| {
+                if locked {
+                    ModAction::Unlock
+                } else {
+                    ModAction::Lock
+                }
+            }),
+            Action::OpenLiveThread => {
+                let live_thread_id = self.state.post.live_thread_id().map(String::from);
+                if let Some(live_thread_id) = live_thread_id {
+                    self.app_event_sender
+                        .send(AppEvent::OpenLiveThread(live_thread_id))
+                        .await?;
+                }
+            }
+            Action::OpenCrosspostParent => {
+                let crosspost_parent = self.state.post.crosspost_parent.first().cloned();
+                if let Some(crosspost_parent) = crosspost_parent {
+                    self.load(crosspost_parent);
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
-                '[' => {
-                    let mut state = self.state.write().unwrap();
-                    if let Some((index, images)) = state.medias.as_mut() {
-                        if *index == 0 {
-                            *index = images.len() - 1;
-                        } else {
-                            *index -= 1;
-                        }
-                    };
-                    if let Some(crosspost_parents_medias) = state.crosspost_parents_medias.as_mut()
-                    {
-                        crosspost_parents_medias
-                            .iter_mut()
-                            .for_each(|(index, images)| {
-                                if *index == 0 {
-                                    *index = images.len() - 1;
-                                } else {
-                                    *index -= 1;
-                                }
-                            });
+            }
+            Action::PlayVideo => {
+                let post = &self.state.post;
+                if let Some(video_url) = post.video_url.clone() {
+                    self.state.loading_video = true;
+                    let (done_tx, done_rx) = oneshot::channel();
+                    self.app_event_sender
+                        .send(AppEvent::DrawWithCallback(done_tx))
+                        .await?;
+                    let app_event = self.app_event_sender.clone();
+                    tokio::spawn(async move {
+                        // Drawn or not, the player still has to start
+                        let _ = done_rx.await;
+                        let result = Self::play_video(&video_url).await;
+                        app_event
+                            .send(AppEvent::PostDetail(PostDetailEvent::VideoClosed(result)))
+                            .await
+                            .unwrap();
+                    });
+                } else if let Some(url) = post.embed.as_ref().and(post.outbound_link()) {
+                    // Embedded videos from other sites are opened from their link like any other,
+                    // url handlers can send them to a player
+                    if let Err(err) = self.opener.open(url) {
+                        self.state.status = Some(Self::error_status(&err));
                     }
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
-                ']' => {
-                    let mut state = self.state.write().unwrap();
-                    if let Some((index, images)) = state.medias.as_mut() {
-                        *index += 1;
-                        if *index >= images.len() {
-                            *index = 0;
-                        }
-                    };
-                    if let Some(crosspost_parents_medias) = state.crosspost_parents_medias.as_mut()
-                    {
-                        crosspost_parents_medias
-                            .iter_mut()
-                            .for_each(|(index, images)| {
-                                *index += 1;
-                                if *index >= images.len() {
-                                    *index = 0;
-                                }
-                            });
-                    }
+            }
+            Action::Back => match self.state.history.pop() {
+                Some(entry) => {
+                    self.show_post(entry.post, entry.focused_comment, entry.last_visit);
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
-                _ => {}
+                None => {
+                    self.reset();
+                    self.app_event_sender.send(AppEvent::Back).await?;
+                }
             },
+            Action::Down => {
+                self.state.scroll_state.scroll_down();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Up => {
+                self.state.scroll_state.scroll_up();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PageDown => {
+                self.state.scroll_state.scroll_page_down();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PageUp => {
+                self.state.scroll_state.scroll_page_up();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::HalfPageDown => {
+                self.state.scroll_state.scroll_half_page_down();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::HalfPageUp => {
+                self.state.scroll_state.scroll_half_page_up();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Top => {
+                self.state.scroll_state.scroll_to_top();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Bottom => {
+                self.state.scroll_state.scroll_to_bottom();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PreviousImage => {
+                Self::previous_image(&mut self.state);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::NextImage => {
+                Self::next_image(&mut self.state);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::RotateImage => {
+                if let Some(media) = Self::image_in_view(&mut self.state) {
+                    media.rotate();
+                    self.app_event_sender.send(AppEvent::Draw).await?;
+                }
+            }
+            Action::RevealSpoilers => {
+                let state = &mut self.state;
+                let id = Self::selected_comment(state)
+                    .map_or_else(|| state.post.id.clone(), |v| v.id.clone());
+                if !state.revealed_spoilers.remove(&id) {
+                    state.revealed_spoilers.insert(id);
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::GrowImage | Action::ShrinkImage => {
+                let state = &mut self.state;
+                state.image_height = Self::clamp_image_height(if action == Action::GrowImage {
+                    state.image_height + IMAGE_HEIGHT_STEP
+                } else {
+                    state.image_height.saturating_sub(IMAGE_HEIGHT_STEP)
+                });
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::ToggleImageFit => {
+                self.state.fit_width = !self.state.fit_width;
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::ResetImage => {
+                let state = &mut self.state;
+                state.fit_width = false;
+                state.image_height = state.default_image_height;
+                let galleries = state.medias.iter_mut().chain(
+                    state
+                        .crosspost_parents_medias
+                        .iter_mut()
+                        .flat_map(|v| v.iter_mut()),
+                );
+                galleries
+                    .flat_map(|(_, images)| images.iter_mut())
+                    .chain(state.preview_image.as_mut())
+                    .for_each(Media::reset_rotation);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::ToggleSlideshow => {
+                if let Some(Slideshow::Playing(handle)) = self.state.slideshow.take() {
+                    handle.abort();
+                } else if self
+                    .state
+                    .medias
+                    .as_ref()
+                    .is_some_and(|(_, images)| images.len() > 1)
+                {
+                    self.play_slideshow();
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PauseSlideshow => {
+                match self.state.slideshow.take() {
+                    Some(Slideshow::Playing(handle)) => {
+                        handle.abort();
+                        self.state.slideshow = Some(Slideshow::Paused);
+                    }
+                    Some(Slideshow::Paused) => self.play_slideshow(),
+                    None => {}
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
             _ => {}
         }
         Ok(())
     }
-    fn draw(&mut self, frame: &mut ratatui::Frame) {
-        let root_area = frame.area();
+
+    async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
+        if self.state.loading_video {
+            return Ok(());
+        }
+        if let Some(user_tag_popup) = self.user_tag_popup.as_mut() {
+            match user_tag_popup.handle_event(event) {
+                UserTagPopupEvent::None => {}
+                UserTagPopupEvent::Cancel => self.user_tag_popup = None,
+                UserTagPopupEvent::Submit(user_tag) => {
+                    let saved = self
+                        .state_store
+                        .write()
+                        .unwrap()
+                        .set_user_tag(&user_tag_popup.username, user_tag);
+                    if let Err(err) = saved {
+                        self.state.status = Some(Self::error_status(&err));
+                    }
+                    self.user_tag_popup = None;
+                }
+            }
+            self.app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
+        if self.search_input.is_some() {
+            return self.handle_search_input_event(event).await;
+        }
+        if let Some(link_hints) = self.link_hints.as_mut() {
+            match link_hints.handle_event(event) {
+                LinkHintsEvent::None => {}
+                LinkHintsEvent::Cancel => self.link_hints = None,
+                LinkHintsEvent::Select(url) => {
+                    if link_hints.copy {
+                        self.state.status = Some(clipboard::copy_with_status(&url, "link"));
+                    } else {
+                        self.open_link(&url).await?;
+                    }
+                    self.link_hints = None;
+                }
+            }
+            self.app_event_sender.send(AppEvent::Draw).await?;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame, frame_area: Rect) {
+        if self.screen_reader {
+            self.draw_linear(frame, frame_area);
+            return;
+        }
+        let hint_keys = &self.hint_keys;
+        let theme = self.state.theme;
+        let now = self.state.clock.now();
+        let wrap_options = self.state.wrap_cache.options();
+        let root_area = Self::content_area(&self.state, frame_area);
         let root_buf = frame.buffer_mut();
+        // The grid takes the whole view, the post under it would only redraw its images for
+        // nothing
+        if let (Some(grid), Some((_, images))) =
+            (self.state.gallery_grid.as_mut(), self.state.medias.as_mut())
+        {
+            Self::draw_gallery_grid(grid, images, theme, root_area, root_buf);
+            return;
+        }
+        // Inside the borders and beside the scrollbar, also what body search matches are lines at
+        self.state.body_width = Block::bordered().inner(root_area).width.saturating_sub(2);
         let (
             sub,
             created,
+            edited,
             author,
-            title,
             score,
             num_comments,
             has_video_url,
-            body,
-            comments,
+            video_badge,
+            crosspost_source,
+            has_live_thread,
+            is_focused,
+            contest_mode,
             loading_comment,
             loading_video,
+            selected_comment,
+            is_moderator,
+            mod_flags,
+            status,
+            search,
+            search_matches,
+            search_position,
+            focused_link_url,
+            last_visit,
         ) = {
-            let state = self.state.read().unwrap();
+            let state = &self.state;
             (
                 state.post.subreddit.clone(),
-                state.post.created_at.clone(),
+                state.post.created_at,
+                state.post.edited_at,
                 state.post.author.clone(),
-                state.post.title.clone(),
-                state.post.score,
+                state.post.score_text(state.compact_scores),
                 state.post.num_comments,
                 state.post.video_url.is_some(),
-                state.post.body.clone(),
-                state.comments.clone(),
+                state.post.video_badge(),
+                state.post.crosspost_source(),
+                state.post.live_thread_id().is_some(),
+                state.focused_comment.is_some(),
+                state.post.contest_mode,
                 state.loading_comment,
                 state.loading_video,
+                state.selected_comment,
+                Self::is_moderator(state),
+                (state.post.approved, state.post.removed, state.post.locked),
+                state.status.clone(),
+                state.search.clone(),
+                Self::search_matches(state),
+                Self::search_position(state),
+                Self::focused_link_url(state),
+                state.last_visit,
             )
         };
         debug!("Draw loading vid: {}", loading_video);
-        let is_body_empty = body.is_empty();
-
-        let mut root_block = Block::bordered().border_type(BorderType::Rounded).title(
-            Line::from(format!(
-                "r/{} • u/{} • {}",
-                sub,
-                author,
-                HumanTime::from(created - Utc::now())
-            ))
-            .left_aligned()
-            .italic(),
-        );
+
+        let edited = edited
+            .map(|v| format!("* (edited {})", HumanTime::from(v - now)))
+            .unwrap_or_default();
+        let mut root_block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(self.sub_accents.border_style(&sub))
+            .title(
+                Line::from(format!(
+                    "r/{} • u/{} • {}{}",
+                    sub,
+                    author,
+                    HumanTime::from(created - now),
+                    edited
+                ))
+                .left_aligned()
+                .italic(),
+            );
 
         if loading_video {
             root_block = root_block.title(Line::from("Loading video...").right_aligned())
-        } else if has_video_url {
-            root_block = root_block.title(Line::from("[v] play video").right_aligned())
+        } else if let Some(video_badge) = video_badge {
+            let play = key_hint(hint_keys, Action::PlayVideo, "play video")
+                .filter(|_| has_video_url)
+                .map(|v| format!(" • {}", v))
+                .unwrap_or_default();
+            root_block =
+                root_block.title(Line::from(format!("{}{}", video_badge, play)).right_aligned())
+        }
+        if has_live_thread
+            && let Some(hint) = key_hint(hint_keys, Action::OpenLiveThread, "live thread")
+        {
+            root_block = root_block.title(Line::from(hint).right_aligned())
+        }
+        if contest_mode {
+            root_block = root_block.title(
+                Line::from("🏆 contest mode: scores hidden, order randomized")
+                    .fg(Color::Yellow)
+                    .right_aligned(),
+            )
+        }
+        if is_focused && let Some(hint) = key_hint(hint_keys, Action::ShowFullThread, "full thread")
+        {
+            root_block = root_block.title(Line::from(hint).right_aligned())
+        }
+        if let Some(crosspost_source) = crosspost_source {
+            let open = key_hint(hint_keys, Action::OpenCrosspostParent, "open")
+                .map(|v| format!(" • {}", v))
+                .unwrap_or_default();
+            root_block = root_block
+                .title(Line::from(format!("🔁 {}{}", crosspost_source, open)).right_aligned())
+        }
+        if is_moderator {
+            let (approved, removed, locked) = mod_flags;
+            let mut badges = Vec::new();
+            if approved {
+                badges.push("✔ approved ".fg(Color::Green));
+            }
+            if removed {
+                badges.push("✖ removed ".fg(Color::Red));
+            }
+            if locked {
+                badges.push("🔒 locked ".fg(Color::Yellow));
+            }
+            let hints = [
+                (Action::Approve, "approve"),
+                (Action::Remove, "remove"),
+                (Action::ToggleLock, "lock"),
+            ]
+            .into_iter()
+            .filter_map(|(action, what)| key_hint(hint_keys, action, what))
+            .collect::<Vec<_>>();
+            badges.push(hints.join(" • ").into());
+            root_block = root_block.title(Line::from(badges).right_aligned());
+        }
+        if let Some(status) = status {
+            root_block = root_block.title_bottom(status.right_aligned());
+        }
+        if let Some(search) = search.as_ref() {
+            let position = search_position
+                .and_then(|current| search_matches.iter().position(|v| *v == current))
+                .map_or("-".to_string(), |v| (v + 1).to_string());
+            let search_info = if search_matches.is_empty() {
+                format!("/{} • no matches", search)
+            } else {
+                let next_prev = match (
+                    hint_keys.get(&Action::NextSearchMatch),
+                    hint_keys.get(&Action::PreviousSearchMatch),
+                ) {
+                    (Some(next), Some(prev)) => format!(" • [{}/{}] next/prev", next, prev),
+                    _ => String::new(),
+                };
+                format!(
+                    "/{} • {}/{}{}",
+                    search,
+                    position,
+                    search_matches.len(),
+                    next_prev
+                )
+            };
+            root_block = root_block.title_bottom(Line::from(search_info).left_aligned());
+        }
+        if let Some(url) = focused_link_url {
+            root_block = root_block.title_bottom(
+                Line::from(vec![
+                    "🔗 ".into(),
+                    url.underlined(),
+                    " • [Enter] open • [Tab] next".into(),
+                ])
+                .left_aligned(),
+            );
         }
 
         let root_block_inner = root_block.inner(root_area);
@@ -475,224 +2757,461 @@ impl Component for PostDetailComponent {
 
         let mut content_height = 0;
 
-        let title_wrap = textwrap::wrap(&title, root_block_inner_no_scrollbar.width as usize);
-        let title_lines = title_wrap
-            .into_iter()
-            .map(|i| Line::from(i))
-            .collect::<Vec<Line>>();
-        content_height += title_lines.len() as u16;
-
-        let preview_image_size =
-            if let Some(preview_image) = &self.state.read().unwrap().preview_image {
-                let [preview_image_area] = Layout::vertical([Constraint::Percentage(50)])
-                    .areas(root_block_inner_no_scrollbar);
-                preview_image.size_for(Resize::Scale(None), preview_image_area)
+        // Only derived render data leaves the lock, the post and comments are never cloned
+        let width = root_block_inner_no_scrollbar.width;
+        let (title_lines, body_lines, poll_widget) = {
+            let state = &self.state;
+            let title_lines = state
+                .wrap_cache
+                .wrap(&state.post.title, width)
+                .into_iter()
+                .map(Line::from)
+                .collect::<Vec<Line>>();
+            let body_lines = if state.post.body.is_empty() {
+                Vec::default()
             } else {
-                Rect::ZERO
+                theme.body_lines(
+                    state.wrap_cache.wrap(&state.post.body, width),
+                    search.as_deref(),
+                    state.revealed_spoilers.contains(&state.post.id),
+                )
             };
-        content_height += preview_image_size.height;
+            let poll_widget = state
+                .post
+                .poll
+                .as_ref()
+                .map(|v| PollWidget::new(v, width, theme, now, wrap_options));
+            (title_lines, body_lines, poll_widget)
+        };
+        content_height += title_lines.len() as u16;
 
-        let crosspost_parents_medias_sizes = if let Some(crosspost_parents_medias) =
-            &self.state.read().unwrap().crosspost_parents_medias
-        {
-            crosspost_parents_medias
-                .iter()
-                .map(|(index, images)| {
-                    let media_image = &images[*index];
-                    let [media_image_area] = Layout::vertical([Constraint::Percentage(50)])
-                        .areas(root_block_inner_no_scrollbar);
-                    let media_image_size =
-                        media_image.size_for(Resize::Scale(None), media_image_area);
-                    Rect::new(
-                        media_image_size.x,
-                        media_image_size.y,
-                        media_image_size.width,
-                        media_image_size.height + 1,
-                    ) // + 1 for image index info
+        // Heights of every row come from the wrap cache, widgets are only built for rows in view
+        let mut new_comment_count = 0;
+        let comment_row_heights = (!loading_comment).then(|| {
+            let wrapped_comments = &mut self.wrapped_comments;
+            Self::comment_rows(&self.state)
+                .into_iter()
+                .map(|row| match row {
+                    CommentRow::Comment(depth, comment) => {
+                        if last_visit.is_some_and(|v| comment.created_at > v) {
+                            new_comment_count += 1;
+                        }
+                        let body_lines = wrapped_comments.get(depth as u16, comment, width).len();
+                        CommentWidget::height_for(body_lines) as u16
+                    }
+                    CommentRow::Continue { .. } => ContinueWidget::HEIGHT,
                 })
                 .collect::<Vec<_>>()
+        });
+
+        // Link posts show their preview in a card saying where the link goes
+        let outbound_link = self.state.post.outbound_link().map(String::from);
+        let embed = self.state.post.embed.clone();
+        let link_card = outbound_link
+            .as_deref()
+            .map(|url| LinkCardWidget::new(url, embed.as_ref(), theme));
+        let (card_width, card_height) = match &link_card {
+            Some(card) => (LinkCardWidget::EXTRA_WIDTH, card.extra_height()),
+            None => (0, 0),
+        };
+        // Images fit part of the view's height, or fill its width up to a few views tall
+        let [image_bounds] = if self.state.fit_width {
+            [Rect {
+                height: root_block_inner_no_scrollbar.height.saturating_mul(3),
+                ..root_block_inner_no_scrollbar
+            }]
         } else {
-            Vec::default()
+            Layout::vertical([Constraint::Percentage(self.state.image_height)])
+                .areas(root_block_inner_no_scrollbar)
         };
-        let crosspost_parents_height = crosspost_parents_medias_sizes
-            .iter()
-            .fold(0, |a, b| a + b.height);
+        let preview_image_size = if let Some(preview_image) = &mut self.state.preview_image {
+            let preview_image_area = image_bounds;
+            preview_image.size_for(Rect {
+                width: preview_image_area.width.saturating_sub(card_width),
+                ..preview_image_area
+            })
+        } else {
+            Rect::ZERO
+        };
+        let preview_height = preview_image_size.height + card_height;
+        content_height += preview_height;
+
+        // Crossposts show their parents framed, with their own title, text and image
+        let crosspost_frames = {
+            let state = &mut self.state;
+            let frame_width = width.saturating_sub(2);
+            let image_bounds = Rect {
+                width: image_bounds.width.saturating_sub(2),
+                ..image_bounds
+            };
+            let reveal_spoilers = state.revealed_spoilers.contains(&state.post.id);
+            let medias = &mut state.crosspost_parents_medias;
+            state
+                .post
+                .crosspost_parent
+                .iter()
+                .enumerate()
+                .map(|(parent_index, parent)| {
+                    let header = Line::from(format!(
+                        "🔁 crossposted from r/{} • u/{} • {}",
+                        parent.subreddit,
+                        parent.author,
+                        HumanTime::from(parent.created_at - now)
+                    ))
+                    .italic();
+                    let title_lines = state
+                        .wrap_cache
+                        .wrap(&parent.title, frame_width)
+                        .into_iter()
+                        .map(|v| Line::from(v).bold())
+                        .collect();
+                    let body_lines = if parent.body.is_empty() {
+                        Vec::default()
+                    } else {
+                        theme.body_lines(
+                            state.wrap_cache.wrap(&parent.body, frame_width),
+                            search.as_deref(),
+                            reveal_spoilers,
+                        )
+                    };
+                    let mut frame = CrosspostFrame {
+                        header,
+                        title_lines,
+                        image_size: Rect::ZERO,
+                        caption_lines: Vec::default(),
+                        info: None,
+                        body_lines,
+                    };
+                    if let Some((index, images)) = medias
+                        .as_mut()
+                        .and_then(|v| v.get_mut(parent_index))
+                        .filter(|(_, images)| !images.is_empty())
+                    {
+                        Self::release_hidden_gallery_images(*index, images);
+                        if parent.galleries.is_some() {
+                            frame.info = Some(format!("{}/{}", *index + 1, images.len()));
+                        }
+                        let media_image = &mut images[*index];
+                        frame.image_size = media_image.size_for(image_bounds);
+                        frame.caption_lines = Self::caption_lines(
+                            media_image.image(),
+                            frame_width,
+                            theme,
+                            &state.wrap_cache,
+                        );
+                    }
+                    frame
+                })
+                .collect::<Vec<_>>()
+        };
+        let crosspost_parents_height = crosspost_frames.iter().map(CrosspostFrame::height).sum();
         content_height += crosspost_parents_height;
 
-        let media_image_size = if let Some((index, images)) = &self.state.read().unwrap().medias {
-            let media_image = &images[*index];
-            let [media_image_area] =
-                Layout::vertical([Constraint::Percentage(50)]).areas(root_block_inner_no_scrollbar);
-            let media_image_size = media_image.size_for(Resize::Scale(None), media_image_area);
+        let media_image_size = if let Some((index, images)) = &mut self.state.medias {
+            Self::release_hidden_gallery_images(*index, images);
+            let media_image = &mut images[*index];
+            let media_image_size = media_image.size_for(image_bounds);
+            let caption_height =
+                Self::caption_lines(media_image.image(), width, theme, &self.state.wrap_cache)
+                    .len();
             Rect::new(
                 media_image_size.x,
                 media_image_size.y,
                 media_image_size.width,
-                media_image_size.height + 1,
+                media_image_size.height + caption_height as u16 + 1,
             ) // + 1 for image index info
         } else {
             Rect::ZERO
         };
         content_height += media_image_size.height;
 
-        let body_wrap = if is_body_empty {
-            Vec::default()
-        } else {
-            textwrap::wrap(&body, root_block_inner_no_scrollbar.width as usize)
-        };
-        let body_lines = body_wrap
-            .into_iter()
-            .map(|i| Line::from(i))
-            .collect::<Vec<Line>>();
         let body_height = body_lines.len() as u16;
         content_height += body_height;
 
-        let (comment_widgets, comment_height) = if loading_comment {
-            let comment_height = 1;
-            content_height += comment_height;
-            (None, comment_height)
-        } else {
-            let all_comments: Vec<(usize, Comment)> =
-                comments.into_iter().flat_map(|v| v.flatten(0)).collect();
+        let poll_height = poll_widget.as_ref().map_or(0, |v| v.height() as u16);
+        content_height += poll_height;
 
-            let comment_widgets: Vec<CommentWidget> = all_comments
-                .into_iter()
-                .map(|i| {
-                    let (depth, comment) = i;
-                    let comment_widget = CommentWidget::new(
-                        depth as u16,
-                        comment,
-                        false,
-                        root_block_inner_no_scrollbar.width,
-                    );
-                    comment_widget
-                })
-                .collect();
-            let comments_height = comment_widgets.iter().fold(0, |a, b| a + b.height() as u16);
-            content_height += comments_height;
-            (Some(comment_widgets), comments_height)
+        let comment_height = match comment_row_heights.as_ref() {
+            Some(heights) => heights.iter().sum(),
+            None => 1,
         };
+        content_height += comment_height;
 
         content_height += 1; // for post info
 
-        let mut scrollview =
-            ScrollView::new(Size::new(root_block_inner.width, content_height as u16 + 2))
-                .horizontal_scrollbar_visibility(ScrollbarVisibility::Never);
-        let scrollview_area = scrollview.area();
-        let [scrollview_area, _for_scrollbar] =
-            Layout::horizontal([Constraint::Fill(1), Constraint::Length(1)]).areas(scrollview_area);
-        let scrollview_buf = scrollview.buf_mut();
-
+        let content_area = Rect::new(
+            0,
+            0,
+            root_block_inner.width.saturating_sub(1),
+            content_height + 2,
+        );
         let [
             title_area,
             preview_image_area,
             crosspost_parents_area,
             gallery_image_area,
             body_area,
+            poll_area,
             info_area,
             comments_area,
         ] = Layout::vertical([
             Constraint::Length(title_lines.len() as u16),
-            Constraint::Length(preview_image_size.height),
+            Constraint::Length(preview_height),
             Constraint::Length(crosspost_parents_height),
             Constraint::Length(media_image_size.height),
             Constraint::Length(body_height),
+            Constraint::Length(poll_height),
             Constraint::Length(1),
             Constraint::Length(comment_height),
         ])
-        .areas(scrollview_area);
+        .areas(content_area);
+
+        let state = &mut self.state;
+        if let Some(line) = state.scroll_to_body_line.take() {
+            let y = body_area.y + line as u16;
+            Self::scroll_into_view(state, y, 1, root_block_inner.height);
+        }
+        if state.scroll_to_selected_comment
+            && let (Some(selected), Some(heights)) = (selected_comment, &comment_row_heights)
+        {
+            state.scroll_to_selected_comment = false;
+            // Continue rows take no comment index, skip them to find the selected comment
+            let mut y = comments_area.y;
+            let mut index = 0;
+            for (row, height) in Self::comment_rows(state).iter().zip(heights) {
+                if let CommentRow::Comment(..) = row {
+                    if index == selected {
+                        Self::scroll_into_view(state, y, *height, root_block_inner.height);
+                        break;
+                    }
+                    index += 1;
+                }
+                y += height;
+            }
+        }
+
+        let mut scroll = VirtualScroll::new(
+            root_block_inner,
+            content_area.height,
+            true,
+            &mut state.scroll_state,
+            root_buf,
+        );
+
+        scroll.render_widget(
+            Paragraph::new(title_lines).add_modifier(Modifier::BOLD),
+            title_area,
+        );
+
+        let preview_image_area = match link_card {
+            Some(card) => {
+                let image_area = card.image_area(preview_image_area);
+                scroll.render_widget(card, preview_image_area);
+                image_area
+            }
+            None => preview_image_area,
+        };
 
-        Paragraph::new(title_lines)
-            .add_modifier(Modifier::BOLD)
-            .render(title_area, scrollview_buf);
+        // Frames of the crosspost parents, their images are drawn in them along with the others
+        let mut media_infos = Vec::new();
+        let mut media_captions = Vec::new();
+        let mut crosspost_image_areas = Vec::new();
+        let mut frames_area = crosspost_parents_area;
+        for frame in crosspost_frames {
+            let [frame_area, remaining_area] =
+                Layout::vertical([Constraint::Length(frame.height()), Constraint::Fill(1)])
+                    .areas(frames_area);
+            frames_area = remaining_area;
+            let block = Block::bordered()
+                .border_type(BorderType::Rounded)
+                .border_style(theme.muted())
+                .title(frame.header);
+            let frame_inner = block.inner(frame_area);
+            scroll.render_widget(block, frame_area);
+            let [title_area, image_area, caption_area, info_area, body_area] = Layout::vertical([
+                Constraint::Length(frame.title_lines.len() as u16),
+                Constraint::Length(frame.image_size.height),
+                Constraint::Length(frame.caption_lines.len() as u16),
+                Constraint::Length(frame.info.is_some() as u16),
+                Constraint::Length(frame.body_lines.len() as u16),
+            ])
+            .areas(frame_inner);
+            scroll.render_widget(Paragraph::new(frame.title_lines), title_area);
+            scroll.render_widget(Paragraph::new(frame.body_lines), body_area);
+            let [image_center] = Layout::horizontal([Constraint::Length(frame.image_size.width)])
+                .flex(Flex::Center)
+                .areas(image_area);
+            crosspost_image_areas.push(image_center);
+            media_captions.push((frame.caption_lines, caption_area));
+            if let Some(info) = frame.info {
+                media_infos.push((info, info_area));
+            }
+        }
 
-        let mut state = self.state.write().unwrap();
+        // Images scrolled out of view give up their buffers until they come back
+        let mut render_media = |media: &mut Media, area: Rect| {
+            if scroll.is_visible(area) {
+                scroll.render_with(area, |area, buf| media.render(area, buf, theme));
+            } else {
+                media.release();
+            }
+        };
+        // Images in view get a link hint too
+        let mut image_areas = Vec::new();
         if let Some(image) = &mut state.preview_image {
             let [image_center] = Layout::horizontal([Constraint::Length(preview_image_size.width)])
                 .flex(Flex::Center)
                 .areas(preview_image_area);
-            let image_widget = StatefulImage::new().resize(Resize::Scale(None));
-            image_widget.render(image_center, scrollview_buf, image);
+            image_areas.push((image_center, image.image().url.clone()));
+            render_media(image, image_center);
         }
 
         if let Some(crosspost_parents_medias) = &mut state.crosspost_parents_medias {
-            let mut crosspost_parents_area = crosspost_parents_area;
-            crosspost_parents_medias.iter_mut().enumerate().for_each(
-                |(index, crosspost_parent_medias)| {
-                    let size = crosspost_parents_medias_sizes[index];
-                    let (index, images) = crosspost_parent_medias;
-
-                    let [crosspost_parent_area, crosspost_info_area, remaining_area] =
-                        Layout::vertical([
-                            Constraint::Length(size.height - 1),
-                            Constraint::Length(1),
-                            Constraint::Fill(1),
-                        ])
-                        .areas(crosspost_parents_area);
-                    crosspost_parents_area = remaining_area;
-
-                    let [image_center] = Layout::horizontal([Constraint::Length(size.width)])
-                        .flex(Flex::Center)
-                        .areas(crosspost_parent_area);
-                    let image_widget = StatefulImage::new().resize(Resize::Scale(None));
-                    let image = &mut images[*index];
-                    image_widget.render(image_center, scrollview_buf, image);
-
-                    let info_text = format!("{}/{}", *index + 1, images.len());
-                    let [info_center] =
-                        Layout::horizontal([Constraint::Length(info_text.len() as u16)])
-                            .flex(Flex::Center)
-                            .areas(crosspost_info_area);
-                    Paragraph::new(info_text).render(info_center, scrollview_buf);
-                },
-            );
+            for ((index, images), image_center) in crosspost_parents_medias
+                .iter_mut()
+                .zip(crosspost_image_areas)
+            {
+                if let Some(media) = images.get_mut(*index) {
+                    image_areas.push((image_center, media.image().url.clone()));
+                    render_media(media, image_center);
+                }
+            }
         }
 
         if let Some((index, images)) = state.medias.as_mut() {
-            let [gallery_image_area, gallery_info_area] =
-                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
-                    .areas(gallery_image_area);
+            let caption_lines =
+                Self::caption_lines(images[*index].image(), width, theme, &state.wrap_cache);
+            let [gallery_image_area, gallery_caption_area, gallery_info_area] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(caption_lines.len() as u16),
+                Constraint::Length(1),
+            ])
+            .areas(gallery_image_area);
+            media_captions.push((caption_lines, gallery_caption_area));
 
             let [image_center] = Layout::horizontal([Constraint::Length(media_image_size.width)])
                 .flex(Flex::Center)
                 .areas(gallery_image_area);
-            let image_widget = StatefulImage::new().resize(Resize::Scale(None));
-            let image = &mut images[*index];
-            image_widget.render(image_center, scrollview_buf, image);
+            image_areas.push((image_center, images[*index].image().url.clone()));
+            render_media(&mut images[*index], image_center);
+            let grid_hint = key_hint(hint_keys, Action::GalleryGrid, "grid")
+                .filter(|_| images.len() > 1)
+                .map(|v| format!(" • {}", v))
+                .unwrap_or_default();
+            let slideshow = match &state.slideshow {
+                Some(Slideshow::Playing(_)) => " • ▶ slideshow",
+                Some(Slideshow::Paused) => " • ⏸ paused",
+                None => "",
+            };
+            media_infos.push((
+                format!("{}/{}{}{}", *index + 1, images.len(), grid_hint, slideshow),
+                gallery_info_area,
+            ));
+        }
 
-            let info_text = format!("{}/{}", *index + 1, images.len());
-            let [info_center] = Layout::horizontal([Constraint::Length(info_text.len() as u16)])
+        for (caption_lines, caption_area) in media_captions {
+            scroll.render_widget(Paragraph::new(caption_lines).centered(), caption_area);
+        }
+        for (info_text, info_area) in media_infos {
+            let [info_center] = Layout::horizontal([Constraint::Length(text::width(&info_text))])
                 .flex(Flex::Center)
-                .areas(gallery_info_area);
-            Paragraph::new(info_text).render(info_center, scrollview_buf);
+                .areas(info_area);
+            scroll.render_widget(Paragraph::new(info_text), info_center);
         }
 
-        Paragraph::new(body_lines).render(body_area, scrollview_buf);
+        scroll.render_widget(Paragraph::new(body_lines), body_area);
+
+        if let Some(poll_widget) = poll_widget {
+            scroll.render_widget(poll_widget, poll_area);
+        }
 
-        Block::new()
-            .borders(Borders::BOTTOM)
-            .title_bottom(format!("👍🏻{} • 💬{}", score, num_comments))
-            .render(info_area, scrollview_buf);
+        scroll.render_widget(
+            Block::new()
+                .borders(Borders::BOTTOM)
+                .title_bottom(if new_comment_count > 0 {
+                    Line::from(vec![
+                        format!("👍🏻{} • 💬{} • ", score, num_comments).into(),
+                        format!("{} new", new_comment_count).fg(Color::Cyan),
+                    ])
+                } else {
+                    Line::from(format!("👍🏻{} • 💬{}", score, num_comments))
+                }),
+            info_area,
+        );
 
         if loading_comment {
             let loading_comment_text = "Loading comment...";
             let [center] =
-                Layout::horizontal([Constraint::Length(loading_comment_text.len() as u16)])
+                Layout::horizontal([Constraint::Length(text::width(loading_comment_text))])
                     .flex(Flex::Center)
                     .areas(comments_area);
-            Paragraph::new(loading_comment_text).render(center, scrollview_buf);
-        } else if let Some(comment_widgets) = comment_widgets {
-            let mut comments_area = comments_area;
-            comment_widgets.into_iter().for_each(|i| {
-                let [comment_area, remaining_comments_area] =
-                    Layout::vertical([Constraint::Length(i.height() as u16), Constraint::Fill(1)])
-                        .areas(comments_area);
-                i.render(comment_area, scrollview_buf);
-                comments_area = remaining_comments_area;
-            });
+            scroll.render_widget(Paragraph::new(loading_comment_text), center);
+        } else if let Some(heights) = comment_row_heights {
+            let state_store = self.state_store.read().unwrap();
+            let mut y = comments_area.y;
+            let mut index = 0;
+            for (row, height) in Self::comment_rows(state).into_iter().zip(heights) {
+                let area = Rect::new(comments_area.x, y, comments_area.width, height);
+                y += height;
+                let (depth, comment) = match row {
+                    CommentRow::Comment(depth, comment) => (depth, comment),
+                    CommentRow::Continue {
+                        depth,
+                        count,
+                        collapsed,
+                        ..
+                    } => {
+                        scroll.render_widget(
+                            ContinueWidget::new(depth as u16, count, collapsed),
+                            area,
+                        );
+                        continue;
+                    }
+                };
+                index += 1;
+                if !scroll.is_visible(area) {
+                    continue;
+                }
+                let body_texts = self
+                    .wrapped_comments
+                    .get(depth as u16, comment, width)
+                    .clone();
+                let comment_widget = CommentWidget::new(
+                    depth as u16,
+                    comment,
+                    body_texts,
+                    state_store.user_tag(&comment.author).cloned(),
+                    search.as_deref(),
+                    selected_comment == Some(index - 1),
+                    last_visit.is_some_and(|v| comment.created_at > v),
+                )
+                .reveal_spoilers(state.revealed_spoilers.contains(&comment.id))
+                .compact_scores(state.compact_scores)
+                .theme(theme)
+                .now(now);
+                scroll.render_widget(comment_widget, area);
+            }
         }
 
-        scrollview.render(root_block_inner, root_buf, &mut state.scroll_state);
+        if let Some(link_hints) = self.link_hints.as_mut() {
+            let images = image_areas
+                .into_iter()
+                .filter_map(|(area, url)| Some((scroll.screen_position(area)?, url)))
+                .collect();
+            link_hints.render(root_block_inner, root_buf, &Self::links(state), images);
+        }
+        if let Some(duplicates) = state.duplicates.as_mut() {
+            Self::draw_duplicates(duplicates, state.compact_scores, theme, root_area, root_buf);
+        }
+        if let Some(user_tag_popup) = self.user_tag_popup.as_ref() {
+            user_tag_popup.draw(frame, root_area);
+        }
+        if let Some(share_popup) = self.share_popup.as_mut() {
+            share_popup.draw(frame, root_area);
+        }
+        if let Some(search_input) = self.search_input.as_ref() {
+            Self::draw_search_input(search_input, frame, root_area);
+        }
         // if loading_video {
         //     let text = "Loading video...";
         //     let [center_v] = Layout::vertical([Constraint::Length(1)])
@@ -705,3 +3224,74 @@ impl Component for PostDetailComponent {
         // }
     }
 }
+
+/// `[key] what` with the key bound to `action`, nothing when the config left it unbound
+fn key_hint(hint_keys: &HashMap<Action, String>, action: Action, what: &str) -> Option<String> {
+    hint_keys
+        .get(&action)
+        .map(|key| format!("[{}] {}", key, what))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tokio::sync::mpsc::{self, Receiver};
+
+    use super::*;
+    use crate::reddit_api::MockRedditApi;
+
+    fn component(reddit_api: Arc<MockRedditApi>) -> (PostDetailComponent, Receiver<AppEvent>) {
+        let (sender, receiver) = mpsc::channel(100);
+        let component = PostDetailComponent::new(
+            reddit_api,
+            Arc::new(Picker::from_fontsize((8, 12))),
+            Arc::new(RwLock::new(StateStore::in_memory())),
+            &Config::default(),
+            Clock::default(),
+            sender,
+        );
+        (component, receiver)
+    }
+
+    #[tokio::test]
+    async fn duplicates_skip_what_isnt_a_post() {
+        let listing = |children| json!({ "kind": "Listing", "data": { "children": children } });
+        let duplicates = json!([
+            listing(json!([])),
+            listing(json!([
+                {
+                    "kind": "t3",
+                    "data": {
+                        "id": "2cabcde",
+                        "subreddit": "news",
+                        "author": "ferris",
+                        "title": "Same",
+                        "selftext": "",
+                        "url": "https://example.com/",
+                        "num_comments": 0,
+                        "score": 1,
+                        "created_utc": 1_700_000_000.0,
+                    },
+                },
+                { "kind": "t5", "data": { "display_name": "news" } },
+            ])),
+        ]);
+        let reddit_api =
+            Arc::new(MockRedditApi::default().with("duplicates/1cabcde", duplicates.to_string()));
+        let (mut component, mut receiver) = component(reddit_api);
+        component.state.post.id = "1cabcde".to_string();
+
+        component.load_duplicates();
+        while let Some(event) = receiver.recv().await {
+            if let AppEvent::PostDetail(event @ PostDetailEvent::DuplicatesLoaded { .. }) = event {
+                component.handle_loaded(event);
+                break;
+            }
+        }
+
+        let duplicates = component.state.duplicates.as_ref().unwrap();
+        assert_eq!(duplicates.error, None);
+        let ids: Vec<_> = duplicates.items.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(ids, ["2cabcde"]);
+    }
+}
@@ -1,237 +1,994 @@
 use std::{
-    borrow::Cow,
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
     sync::{Arc, RwLock},
 };
 
-use chrono::{DateTime, Utc};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use chrono_humanize::HumanTime;
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::Event;
 use ratatui::{
-    layout::{Alignment, Constraint, Flex, Layout},
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Stylize},
+    symbols::border,
     text::{Line, Text},
-    widgets::{Block, BorderType, Paragraph, StatefulWidget, Widget},
+    widgets::{Block, BorderType, Borders, Paragraph, StatefulWidget, Widget},
 };
+use ratatui_image::picker::Picker;
 use tokio::{sync::mpsc::Sender, task::JoinHandle};
 use tui_widget_list::{ListBuilder, ListState, ListView};
 
 use crate::{
-    app::AppEvent, component::Component, model::post::Post, ngored_error::NgoredError,
-    reddit_api::RedditApi,
+    app::AppEvent,
+    component::{
+        Component, StatusInfo,
+        batch_popup::{BatchPopup, BatchPopupEvent},
+        postdetail::PostDetailComponent,
+        share_popup::{SharePopup, SharePopupEvent, share_post},
+        user_tag_popup::{UserTagPopup, UserTagPopupEvent},
+    },
+    config::{Config, Hooks},
+    format::Clock,
+    keymap::{Action, KeyContext},
+    model::{
+        image::ImageRef,
+        post::{Post, PostKind},
+        subreddit::SubredditAbout,
+    },
+    ngored_error::NgoredError,
+    opener::Opener,
+    reddit_api::{Data, RedditApi},
+    state_store::{StateStore, UserTag},
+    text::{self, WrapCache, WrapOptions},
+    theme::{SubAccents, Theme},
+    widget::{
+        media_widget::{ImageOptions, Media},
+        sub_header_widget::SubHeaderWidget,
+    },
 };
 
 pub struct PostlistState {
     loading: bool,
     sub: String,
     items: Vec<Post>,
+    /// Why the posts couldn't be loaded, shown instead of the list
+    error: Option<String>,
     list_state: ListState,
+    /// Selected post of each sub left this session, keyed by lowercased sub
+    positions: HashMap<String, usize>,
+    /// Last sub loaded, kept across `reset` unlike `sub`
+    last_sub: Option<String>,
+    /// Sub loaded before `last_sub`, `PreviousSub` switches to it
+    previous_sub: Option<String>,
     load_handle: Option<JoinHandle<()>>,
+    /// Insert a date separator before the first post of each day
+    group_by_day: bool,
+    /// Posts in view at the last draw, moved by PageUp/PageDown
+    page_len: usize,
+    scrolloff: u16,
+    /// Show scores as `12.3k`, off with `full_scores`
+    compact_scores: bool,
+    theme: Theme,
+    clock: Clock,
+    wrap_cache: WrapCache,
+    /// Result of the last share or copy, until another sub is opened
+    status: Option<Line<'static>>,
+    /// Strip with the sub's title, size and icon above the posts, toggled with `b`
+    show_header: bool,
+    /// The bottom third shows the selected post's text, toggled with `p`
+    peek: bool,
+    /// Posts marked for a batch action, by id
+    marked: HashSet<String>,
+    /// Where visual select started, the posts from there to the selected one are marked too
+    visual_start: Option<usize>,
+    about: Option<SubredditAbout>,
+    about_icon: Option<Media>,
+    about_handle: Option<JoinHandle<()>>,
+    image_options: ImageOptions,
+}
+
+/// Results of the post list's loader task, delivered through `AppEvent::Postlist`
+pub enum PostlistEvent {
+    PostsLoaded {
+        sub: String,
+        items: Result<Vec<Post>, NgoredError>,
+    },
+    AboutLoaded {
+        sub: String,
+        about: SubredditAbout,
+    },
+    AboutIcon {
+        sub: String,
+        icon: Media,
+    },
+    PostsHidden {
+        sub: String,
+        post_ids: Vec<String>,
+        result: Result<(), NgoredError>,
+    },
 }
 
 pub struct PostlistComponent {
-    reddit_api: Arc<RedditApi>,
+    reddit_api: Arc<dyn RedditApi>,
+    state_store: Arc<RwLock<StateStore>>,
     app_event_sender: Sender<AppEvent>,
-    state: Arc<RwLock<PostlistState>>,
+    picker: Arc<Picker>,
+    state: PostlistState,
+    user_tag_popup: Option<UserTagPopup>,
+    share_popup: Option<SharePopup>,
+    batch_popup: Option<BatchPopup>,
+    opener: Opener,
+    hooks: Hooks,
+    sub_accents: SubAccents,
 }
 
 impl PostlistComponent {
-    pub fn new(reddit_api: Arc<RedditApi>, app_event_sender: Sender<AppEvent>) -> Self {
+    pub fn new(
+        reddit_api: Arc<dyn RedditApi>,
+        picker: Arc<Picker>,
+        state_store: Arc<RwLock<StateStore>>,
+        config: &Config,
+        clock: Clock,
+        app_event_sender: Sender<AppEvent>,
+    ) -> Self {
         let state = PostlistState {
             loading: false,
             sub: String::default(),
             items: Vec::default(),
+            error: None,
             list_state: ListState::default(),
+            positions: HashMap::default(),
+            last_sub: None,
+            previous_sub: None,
             load_handle: None,
+            group_by_day: false,
+            page_len: 1,
+            scrolloff: config.scrolloff.unwrap_or(0),
+            compact_scores: !config.full_scores,
+            theme: Theme::new(config),
+            clock,
+            wrap_cache: WrapCache::new(WrapOptions::new(config)),
+            status: None,
+            show_header: true,
+            peek: false,
+            marked: HashSet::default(),
+            visual_start: None,
+            about: None,
+            about_icon: None,
+            about_handle: None,
+            image_options: ImageOptions::new(config),
         };
         Self {
             reddit_api,
+            state_store,
             app_event_sender,
-            state: Arc::new(RwLock::new(state)),
+            picker,
+            state,
+            user_tag_popup: None,
+            share_popup: None,
+            batch_popup: None,
+            opener: Opener::new(config),
+            hooks: config.hooks.clone(),
+            sub_accents: SubAccents::new(config),
         }
     }
 
+    /// Show the posts of `sub`, another sub already shown is left like `Back` would
     pub fn load(&mut self, sub: String) {
-        {
-            let state = self.state.read().unwrap();
-            if state.sub == sub || state.loading {
-                return;
-            }
+        if self.state.sub == sub {
+            return;
+        }
+        if !self.state.sub.is_empty() {
+            self.reset();
+        }
+        if self.state.last_sub.as_ref() != Some(&sub) {
+            self.state.previous_sub = self.state.last_sub.replace(sub.clone());
         }
-        self.state.write().unwrap().sub = sub.clone();
+        self.state.sub = sub.clone();
+        self.state.loading = true;
+        self.state.items.clear();
+        self.state.error = None;
+        self.load_about(sub.clone());
 
-        self.state.write().unwrap().load_handle = Some(tokio::spawn({
-            let state = self.state.clone();
+        self.state.load_handle = Some(tokio::spawn({
             let reddit_api = self.reddit_api.clone();
             let app_event_sender = self.app_event_sender.clone();
             async move {
-                {
-                    let mut state = state.write().unwrap();
-                    state.loading = true;
-                    state.items.clear();
-                }
-                app_event_sender.send(AppEvent::Draw).await.unwrap();
-
-                let res = { reddit_api.get_posts(&sub).await };
+                let items = reddit_api
+                    .get_posts(&sub, None)
+                    .await
+                    .map_err(|err| err.in_sub(&sub))
+                    .and_then(|res| {
+                        let posts = res.into_posts();
+                        posts.ok_or_else(|| NgoredError::NoSuchSub(sub.clone()))
+                    })
+                    .map(|posts| posts.into_iter().map(Post::from).collect());
+                app_event_sender
+                    .send(AppEvent::Postlist(PostlistEvent::PostsLoaded {
+                        sub,
+                        items,
+                    }))
+                    .await
+                    .unwrap();
+            }
+        }));
+    }
 
-                {
-                    let mut state = state.write().unwrap();
-                    state.items = res
-                        .as_listing()
-                        .children
-                        .into_iter()
-                        .map(|i| Post::from(i.as_post()))
-                        .collect();
-                    state.loading = false;
-                    state.list_state.select(Some(0));
+    /// The header is left out when the sub's about page can't be loaded, the posts matter more
+    fn load_about(&mut self, sub: String) {
+        self.state.about_handle = Some(tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            let picker = self.picker.clone();
+            let image_options = self.state.image_options.clone();
+            let hooks = self.hooks.clone();
+            async move {
+                // A sub that doesn't exist answers with a search listing
+                let Some(about) = reddit_api
+                    .get_subreddit_about(&sub)
+                    .await
+                    .ok()
+                    .and_then(Data::into_subreddit)
+                else {
+                    return;
+                };
+                let about = SubredditAbout::from(about);
+                let icon_url = about.icon_url.clone();
+                let sub_loaded = sub.clone();
+                app_event_sender
+                    .send(AppEvent::Postlist(PostlistEvent::AboutLoaded {
+                        sub: sub_loaded,
+                        about,
+                    }))
+                    .await
+                    .unwrap();
+                if let Some(url) = icon_url {
+                    let image = ImageRef {
+                        url,
+                        width: 0,
+                        height: 0,
+                        caption: None,
+                        outbound_url: None,
+                    };
+                    let icon = PostDetailComponent::load_image(
+                        &*reddit_api,
+                        &picker,
+                        image,
+                        image_options,
+                        &hooks,
+                    )
+                    .await;
+                    app_event_sender
+                        .send(AppEvent::Postlist(PostlistEvent::AboutIcon { sub, icon }))
+                        .await
+                        .unwrap();
                 }
-                app_event_sender.send(AppEvent::Draw).await.unwrap();
             }
         }));
     }
 
-    fn reset(&self) {
-        let mut state = self.state.write().unwrap();
+    /// Apply the result of a loader task, posts of a sub no longer shown are dropped
+    pub fn handle_loaded(&mut self, event: PostlistEvent) {
+        match event {
+            PostlistEvent::PostsLoaded { sub, items } => {
+                if sub != self.state.sub {
+                    return;
+                }
+                self.state.loading = false;
+                match items {
+                    Ok(items) => {
+                        let selected = self
+                            .state
+                            .positions
+                            .get(&sub.to_lowercase())
+                            .copied()
+                            .filter(|v| *v < items.len())
+                            .or((!items.is_empty()).then_some(0));
+                        self.state.list_state.select(selected);
+                        self.state.items = items;
+                    }
+                    Err(err) => self.state.error = Some(err.to_string()),
+                }
+            }
+            PostlistEvent::AboutLoaded { sub, about } => {
+                if sub == self.state.sub {
+                    self.state.about = Some(about);
+                }
+            }
+            PostlistEvent::AboutIcon { sub, icon } => {
+                // A placeholder doesn't fit in the icon's few cells, go without
+                if sub == self.state.sub && matches!(icon, Media::Image(_)) {
+                    self.state.about_icon = Some(icon);
+                }
+            }
+            PostlistEvent::PostsHidden {
+                sub,
+                post_ids,
+                result,
+            } => {
+                if sub != self.state.sub {
+                    return;
+                }
+                let state = &mut self.state;
+                match result {
+                    Ok(()) => {
+                        state.items.retain(|v| !post_ids.contains(&v.id));
+                        let last = state.items.len().checked_sub(1);
+                        let selected = state.list_state.selected.zip(last);
+                        state
+                            .list_state
+                            .select(selected.map(|(v, last)| v.min(last)));
+                        state.status = Some(Line::from(format!("{} posts hidden", post_ids.len())));
+                    }
+                    Err(err) => {
+                        state.status = Some(Line::from(err.to_string().fg(Color::Red)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Posts from where visual select started to the selected one
+    fn visual_range(state: &PostlistState) -> Option<RangeInclusive<usize>> {
+        let start = state.visual_start?;
+        let selected = state.list_state.selected?;
+        Some(start.min(selected)..=start.max(selected))
+    }
+
+    /// Posts marked one by one or by visual select, in the order they're listed
+    fn marked_posts(state: &PostlistState) -> Vec<&Post> {
+        let visual_range = Self::visual_range(state);
+        state
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(index, post)| {
+                state.marked.contains(&post.id)
+                    || visual_range.as_ref().is_some_and(|v| v.contains(index))
+            })
+            .map(|(_, post)| post)
+            .collect()
+    }
+
+    /// The order `get_posts` fetches in, there's no choosing another one yet
+    fn sort(&self) -> &'static str {
+        if self.state.sub.eq_ignore_ascii_case("friends") {
+            "new"
+        } else {
+            "best"
+        }
+    }
+
+    /// Queue `posts` to read later, returns the status to show
+    fn read_later(&self, posts: &[&Post]) -> Line<'static> {
+        let mut state_store = self.state_store.write().unwrap();
+        let queued = posts
+            .iter()
+            .map(|post| state_store.push_read_later((*post).into()))
+            .collect::<Result<Vec<_>, _>>();
+        let queued = match queued {
+            Ok(queued) => queued.into_iter().filter(|v| *v).count(),
+            Err(err) => return Line::from(err.to_string().fg(Color::Red)),
+        };
+        match (queued, posts.len()) {
+            (0, _) => Line::from("Already queued to read later"),
+            (1, 1) => Line::from("Queued to read later"),
+            (queued, _) => Line::from(format!("{} posts queued to read later", queued)),
+        }
+    }
+
+    /// Open, mark read, hide or queue every marked post, then unmark them
+    fn apply_batch(&mut self, action: Action) {
+        let posts = Self::marked_posts(&self.state);
+        let status = match action {
+            Action::ReadLater => Some(self.read_later(&posts)),
+            Action::OpenInBrowser => posts
+                .iter()
+                .find_map(|post| self.opener.open(&post.permalink()).err())
+                .map(|err| Line::from(format!("Cannot open the browser: {}", err).fg(Color::Red))),
+            Action::MarkRead => {
+                let mut state_store = self.state_store.write().unwrap();
+                let marked = posts
+                    .iter()
+                    .try_for_each(|post| state_store.visit_post(&post.id).map(|_| ()));
+                Some(match marked {
+                    Ok(()) => Line::from(format!("{} posts marked read", posts.len())),
+                    Err(err) => Line::from(err.to_string().fg(Color::Red)),
+                })
+            }
+            Action::Hide => {
+                let post_ids = posts.iter().map(|v| v.id.clone()).collect::<Vec<_>>();
+                tokio::spawn({
+                    let reddit_api = self.reddit_api.clone();
+                    let app_event_sender = self.app_event_sender.clone();
+                    let sub = self.state.sub.clone();
+                    async move {
+                        let fullnames = post_ids
+                            .iter()
+                            .map(|v| format!("t3_{}", v))
+                            .collect::<Vec<_>>();
+                        let result = reddit_api.hide_posts(&fullnames).await;
+                        app_event_sender
+                            .send(AppEvent::Postlist(PostlistEvent::PostsHidden {
+                                sub,
+                                post_ids,
+                                result,
+                            }))
+                            .await
+                            .unwrap();
+                    }
+                });
+                None
+            }
+            _ => return,
+        };
+        let state = &mut self.state;
+        state.status = status;
+        state.marked.clear();
+        state.visual_start = None;
+    }
+
+    /// Share or copy the selected post
+    fn share(&mut self, action: Action) {
+        let state = &mut self.state;
+        if let Some(post) = state.list_state.selected.and_then(|i| state.items.get(i))
+            && let Some(status) = share_post(post, action, &self.opener)
+        {
+            state.status = Some(status);
+        }
+    }
+
+    fn reset(&mut self) {
+        let state = &mut self.state;
+        if let Some(load_handle) = state.load_handle.take() {
+            load_handle.abort();
+        }
+        if let Some(about_handle) = state.about_handle.take() {
+            about_handle.abort();
+        }
+        if let Some(selected) = state.list_state.selected
+            && !state.sub.is_empty()
+        {
+            state.positions.insert(state.sub.to_lowercase(), selected);
+        }
         state.loading = false;
         state.sub = String::default();
         state.items.clear();
+        state.error = None;
         state.list_state = ListState::default();
-        state.load_handle = None;
+        state.status = None;
+        state.marked.clear();
+        state.visual_start = None;
+        state.about = None;
+        state.about_icon = None;
+    }
+
+    /// The text of `post` under the posts, a link post shows where it goes instead
+    fn draw_peek(post: &Post, theme: Theme, wrap_cache: &WrapCache, area: Rect, buf: &mut Buffer) {
+        let block = Block::new()
+            .borders(Borders::TOP)
+            .border_type(BorderType::Rounded)
+            .title(Line::from(post.title.as_str()).bold());
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let lines = if !post.body.is_empty() {
+            theme.body_lines(wrap_cache.wrap(&post.body, inner.width), None, false)
+        } else if let Some(url) = post.outbound_link() {
+            vec![Line::from(format!("🔗 {}", url)).fg(theme.muted())]
+        } else {
+            vec![Line::from("No text").fg(theme.muted()).italic()]
+        };
+        Paragraph::new(lines).render(inner, buf);
     }
 }
 
+#[async_trait]
 impl Component for PostlistComponent {
+    fn key_context(&self) -> Option<KeyContext> {
+        if self.user_tag_popup.is_some() {
+            None
+        } else if self.share_popup.is_some() {
+            Some(KeyContext::Share)
+        } else if self.batch_popup.is_some() {
+            Some(KeyContext::Batch)
+        } else {
+            Some(KeyContext::Postlist)
+        }
+    }
+
+    fn resized(&mut self) {
+        self.state.wrap_cache.clear();
+    }
+
+    fn status_info(&self) -> StatusInfo {
+        let state = &self.state;
+        let state_store = self.state_store.read().unwrap();
+        StatusInfo {
+            sub: Some(state.sub.clone()).filter(|v| !v.is_empty()),
+            sort: Some(self.sort().to_string()),
+            position: state
+                .list_state
+                .selected
+                .filter(|_| !state.items.is_empty())
+                .map(|v| (v + 1, state.items.len())),
+            unread: Some(
+                state
+                    .items
+                    .iter()
+                    .filter(|v| !state_store.last_visits.contains_key(&v.id))
+                    .count(),
+            ),
+        }
+    }
+
     async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
-        match event {
-            Event::Key(KeyEvent {
-                code: KeyCode::Char(char),
-                kind: KeyEventKind::Press,
-                ..
-            }) => match char {
-                'h' => {
-                    if let Some(load_handle) = self.state.write().unwrap().load_handle.take() {
-                        load_handle.abort();
+        if let Some(user_tag_popup) = self.user_tag_popup.as_mut() {
+            match user_tag_popup.handle_event(event) {
+                UserTagPopupEvent::None => {}
+                UserTagPopupEvent::Cancel => self.user_tag_popup = None,
+                UserTagPopupEvent::Submit(user_tag) => {
+                    let saved = self
+                        .state_store
+                        .write()
+                        .unwrap()
+                        .set_user_tag(&user_tag_popup.username, user_tag);
+                    if let Err(err) = saved {
+                        self.state.status = Some(Line::from(err.to_string().fg(Color::Red)));
                     }
+                    self.user_tag_popup = None;
+                }
+            }
+            self.app_event_sender.send(AppEvent::Draw).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        if let Some(batch_popup) = self.batch_popup.as_mut() {
+            match batch_popup.handle_action(action) {
+                BatchPopupEvent::None => {}
+                BatchPopupEvent::Close => self.batch_popup = None,
+                BatchPopupEvent::Apply(action) => {
+                    self.batch_popup = None;
+                    self.apply_batch(action);
+                }
+            }
+            self.app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
+        if let Some(share_popup) = self.share_popup.as_mut() {
+            match share_popup.handle_action(action) {
+                SharePopupEvent::None => {}
+                SharePopupEvent::Close => self.share_popup = None,
+                SharePopupEvent::Share(action) => {
+                    self.share_popup = None;
+                    self.share(action);
+                }
+            }
+            self.app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
+        match action {
+            Action::Back => {
+                self.reset();
+                self.app_event_sender.send(AppEvent::Back).await?;
+            }
+            Action::Refresh => {
+                let sub = self.state.sub.clone();
+                self.reset();
+                self.load(sub);
+            }
+            Action::PreviousSub => {
+                if let Some(previous_sub) = self.state.previous_sub.clone() {
                     self.reset();
-                    self.app_event_sender.send(AppEvent::ClosePostList).await?;
+                    self.load(previous_sub);
+                    self.app_event_sender.send(AppEvent::Draw).await?;
                 }
-                'j' => {
-                    self.state.write().unwrap().list_state.next();
+            }
+            Action::ToggleSubHeader => {
+                self.state.show_header = !self.state.show_header;
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::TogglePeek => {
+                self.state.peek = !self.state.peek;
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::ToggleGroupByDay => {
+                {
+                    let state = &mut self.state;
+                    state.group_by_day = !state.group_by_day;
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
+            Action::Down => {
+                self.state.list_state.next();
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
+            Action::Up => {
+                self.state.list_state.previous();
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
+            Action::PageDown | Action::PageUp | Action::Top | Action::Bottom => {
+                let state = &mut self.state;
+                if let Some(last) = state.items.len().checked_sub(1) {
+                    let selected = state.list_state.selected.unwrap_or(0);
+                    let selected = match action {
+                        Action::PageDown => (selected + state.page_len).min(last),
+                        Action::PageUp => selected.saturating_sub(state.page_len),
+                        Action::Top => 0,
+                        _ => last,
+                    };
+                    state.list_state.select(Some(selected));
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
+            Action::TagUser => {
+                let author = {
+                    let state = &self.state;
+                    state
+                        .list_state
+                        .selected
+                        .and_then(|i| state.items.get(i))
+                        .map(|v| v.author.clone())
+                };
+                if let Some(author) = author {
+                    let user_tag = self.state_store.read().unwrap().user_tag(&author).cloned();
+                    self.user_tag_popup = Some(UserTagPopup::new(author, user_tag.as_ref()));
+                    self.app_event_sender.send(AppEvent::Draw).await?;
+                }
+            }
+            Action::Open => {
+                let state = &self.state;
+                if let Some(post) = state.list_state.selected.and_then(|i| state.items.get(i)) {
+                    self.app_event_sender
+                        .send(AppEvent::OpenPostDetail(post.clone()))
+                        .await?
+                }
+            }
+            Action::Share => {
+                let state = &self.state;
+                let marked = Self::marked_posts(state).len();
+                if marked > 0 {
+                    self.batch_popup = Some(BatchPopup::new(marked, state.theme));
+                    self.app_event_sender.send(AppEvent::Draw).await?
+                } else if let Some(post) =
+                    state.list_state.selected.and_then(|i| state.items.get(i))
+                {
+                    self.share_popup = Some(SharePopup::new(post, state.theme));
                     self.app_event_sender.send(AppEvent::Draw).await?
                 }
-                'k' => {
-                    self.state.write().unwrap().list_state.previous();
+            }
+            Action::ToggleMark => {
+                let state = &mut self.state;
+                if let Some(post) = state.list_state.selected.and_then(|i| state.items.get(i))
+                    && !state.marked.remove(&post.id)
+                {
+                    state.marked.insert(post.id.clone());
+                }
+                state.list_state.next();
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
+            Action::VisualSelect => {
+                let state = &mut self.state;
+                match Self::visual_range(state) {
+                    // The range stays marked once visual select ends
+                    Some(range) => {
+                        let ids = state.items[range].iter().map(|v| v.id.clone());
+                        state.marked.extend(ids);
+                        state.visual_start = None;
+                    }
+                    None => state.visual_start = state.list_state.selected,
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
+            Action::ReadLater => {
+                let state = &self.state;
+                if let Some(post) = state.list_state.selected.and_then(|i| state.items.get(i)) {
+                    let status = self.read_later(&[post]);
+                    self.state.status = Some(status);
                     self.app_event_sender.send(AppEvent::Draw).await?
                 }
-                'l' => {
-                    let state = self.state.read().unwrap();
-                    if let Some(selected_index) = state.list_state.selected {
+            }
+            Action::NextReadLater => {
+                let next = self.state_store.write().unwrap().pop_read_later();
+                match next {
+                    Ok(Some(post)) => {
                         self.app_event_sender
-                            .send(AppEvent::OpenPostDetail(
-                                state.items[selected_index].clone(),
-                            ))
+                            .send(AppEvent::OpenPermalink(post.permalink()))
                             .await?
                     }
+                    Ok(None) => {
+                        self.state.status = Some(Line::from("Nothing to read later"));
+                        self.app_event_sender.send(AppEvent::Draw).await?
+                    }
+                    Err(err) => {
+                        self.state.status = Some(Line::from(err.to_string().fg(Color::Red)));
+                        self.app_event_sender.send(AppEvent::Draw).await?
+                    }
                 }
-                _ => {}
-            },
+            }
+            Action::Cancel => {
+                let state = &mut self.state;
+                state.marked.clear();
+                state.visual_start = None;
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
+            Action::CopyTitle | Action::CopyPermalink | Action::CopyLink => {
+                self.share(action);
+                self.app_event_sender.send(AppEvent::Draw).await?
+            }
             _ => {}
         }
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut ratatui::Frame) {
-        let area = frame.area();
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let buf = frame.buffer_mut();
-        let block = Block::bordered()
+        let group_by_day = self.state.group_by_day;
+        let sort = self.sort();
+        let mut block = Block::bordered()
             .border_type(BorderType::Rounded)
-            .title(format!("r/{}", self.state.read().unwrap().sub.clone()).italic());
-        if self.state.read().unwrap().loading {
+            .border_style(self.sub_accents.border_style(&self.state.sub))
+            .title(format!("r/{} • {}", self.state.sub, sort).italic());
+        if group_by_day {
+            block = block.title(Line::from("by day").italic().right_aligned());
+        }
+        if let Some(status) = self.state.status.clone() {
+            block = block.title_bottom(status.right_aligned());
+        }
+        let marked = Self::marked_posts(&self.state).len();
+        if self.state.visual_start.is_some() {
+            block = block.title_bottom(
+                Line::from(format!(
+                    "-- VISUAL -- {} marked • [v] end • [o] act",
+                    marked
+                ))
+                .left_aligned(),
+            );
+        } else if marked > 0 {
+            block = block.title_bottom(
+                Line::from(format!("{} marked • [o] act • [Esc] unmark", marked)).left_aligned(),
+            );
+        }
+        if self.state.loading || self.state.error.is_some() {
+            let inner = block.inner(area);
             block.render(area, buf);
-            let text = Text::raw("Loading...");
+            let text = match &self.state.error {
+                Some(error) => Text::from_iter(text::wrap(
+                    error,
+                    inner.width,
+                    self.state.wrap_cache.options(),
+                )),
+                None => Text::raw("Loading..."),
+            };
             let [area] = Layout::vertical([Constraint::Length(text.height() as u16)])
                 .flex(Flex::Center)
-                .areas(area);
+                .areas(inner);
             Paragraph::new(text)
                 .alignment(Alignment::Center)
                 .render(area, buf);
         } else {
-            let posts = self.state.read().unwrap().items.clone();
+            let inner = block.inner(area);
+            block.render(area, buf);
+            let state = &mut self.state;
+            let list_area = match state.about.as_ref().filter(|_| state.show_header) {
+                Some(about) => {
+                    let header = SubHeaderWidget::new(
+                        about,
+                        state.about_icon.is_some(),
+                        state.compact_scores,
+                        state.theme,
+                    );
+                    let [header_area, _, list_area] = Layout::vertical([
+                        Constraint::Length(SubHeaderWidget::HEIGHT),
+                        Constraint::Length(1),
+                        Constraint::Fill(1),
+                    ])
+                    .areas(inner);
+                    if let Some(icon) = state.about_icon.as_mut() {
+                        let icon_area = header.icon_area(header_area);
+                        let size = icon.size_for(icon_area);
+                        icon.render(
+                            Rect {
+                                width: size.width,
+                                height: size.height,
+                                ..icon_area
+                            },
+                            buf,
+                            state.theme,
+                        );
+                    }
+                    header.render(header_area, buf);
+                    list_area
+                }
+                None => inner,
+            };
+            let list_area = if state.peek {
+                let [list_area, peek_area] =
+                    Layout::vertical([Constraint::Fill(2), Constraint::Fill(1)]).areas(list_area);
+                let selected = state.list_state.selected.and_then(|i| state.items.get(i));
+                if let Some(post) = selected {
+                    Self::draw_peek(post, state.theme, &state.wrap_cache, peek_area, buf);
+                }
+                list_area
+            } else {
+                list_area
+            };
+            let posts = &state.items;
+            let marked = &state.marked;
+            let visual_range = Self::visual_range(state);
+            let state_store = self.state_store.read().unwrap();
+            let clock = state.clock;
+            let now = clock.now();
+            let post_item = |index: usize, width: u16| {
+                let post = &posts[index];
+                let user_tag = state_store.user_tag(&post.author).cloned();
+                let mut post_item = PostItem::new(
+                    post,
+                    user_tag,
+                    width,
+                    state.compact_scores,
+                    state.theme,
+                    now,
+                    &state.wrap_cache,
+                );
+                post_item.is_marked = marked.contains(&post.id)
+                    || visual_range.as_ref().is_some_and(|v| v.contains(&index));
+                if group_by_day {
+                    let day = clock.day(post.created_at);
+                    let previous_day = index
+                        .checked_sub(1)
+                        .and_then(|i| posts.get(i))
+                        .map(|v| clock.day(v.created_at));
+                    if previous_day != Some(day) {
+                        post_item.set_date_header(day_label(day, clock.day(now)));
+                    }
+                }
+                post_item
+            };
             let builder = ListBuilder::new(|ctx| {
-                let width = ctx.cross_axis_size as usize;
-                let post = posts.get(ctx.index).unwrap();
-                let mut post_item = PostItem::new(post, width);
+                let mut post_item = post_item(ctx.index, ctx.cross_axis_size);
                 if ctx.is_selected {
-                    post_item.set_background(Color::DarkGray);
+                    post_item.set_selected();
                 }
                 let height = post_item.height();
                 (post_item, height as u16)
             });
-            let item_len = { self.state.read().unwrap().items.len() };
-            let list = ListView::new(builder, item_len).block(block);
+            let list = ListView::new(builder, posts.len()).scroll_padding(state.scrolloff);
             // .highlight_style(
             //     Style::default()
             //         .bg(Color::Blue)
             //         .add_modifier(Modifier::BOLD),
             // );
 
-            StatefulWidget::render(list, area, buf, &mut self.state.write().unwrap().list_state);
+            StatefulWidget::render(list, list_area, buf, &mut state.list_state);
+
+            let mut remaining = list_area.height;
+            let page_len = (state.list_state.scroll_offset_index()..posts.len())
+                .map(|i| post_item(i, list_area.width).height() as u16)
+                .take_while(|height| {
+                    let fits = *height <= remaining;
+                    remaining = remaining.saturating_sub(*height);
+                    fits
+                })
+                .count();
+            state.page_len = page_len.max(1);
+        }
+        if let Some(user_tag_popup) = self.user_tag_popup.as_ref() {
+            user_tag_popup.draw(frame, area);
         }
+        if let Some(share_popup) = self.share_popup.as_mut() {
+            share_popup.draw(frame, area);
+        }
+        if let Some(batch_popup) = self.batch_popup.as_mut() {
+            batch_popup.draw(frame, area);
+        }
+    }
+}
+
+fn day_label(day: NaiveDate, today: NaiveDate) -> String {
+    if day == today {
+        "Today".to_string()
+    } else if Some(day) == today.pred_opt() {
+        "Yesterday".to_string()
+    } else {
+        day.format("%a %-d %b").to_string()
     }
 }
 
 pub struct PostItem {
+    pub date_header: Option<String>,
     pub username: String,
+    pub user_tag: Option<UserTag>,
     pub title_lines: Vec<String>,
     pub body_lines: Vec<String>,
-    pub background: Option<Color>,
-    pub score: i64,
+    pub is_selected: bool,
+    /// Marked for a batch action
+    pub is_marked: bool,
+    pub score: String,
     pub num_comments: u64,
     pub created: DateTime<Utc>,
+    /// What `created` is shown relative to
+    pub now: DateTime<Utc>,
+    pub video_badge: Option<String>,
+    pub kind: PostKind,
+    /// Where a link post goes, shown after the title
+    pub domain: Option<String>,
+    /// Where a crosspost comes from, shown under the title
+    pub crosspost_source: Option<String>,
+    pub theme: Theme,
 }
 
 impl PostItem {
-    pub fn new(post: &Post, width: usize) -> Self {
+    pub fn new(
+        post: &Post,
+        user_tag: Option<UserTag>,
+        width: u16,
+        compact_scores: bool,
+        theme: Theme,
+        now: DateTime<Utc>,
+        wrap_cache: &WrapCache,
+    ) -> Self {
         let username = post.author.clone();
-        let title_lines = textwrap::wrap(&post.title, width)
-            .iter()
-            .map(|i| i.to_string())
-            .collect();
-        let mut body_wrap = textwrap::wrap(&post.body, width);
-        if body_wrap.len() > 4 {
-            body_wrap.truncate(4);
-            let mut new_last = body_wrap[3].to_string();
-            if new_last.len() > 3 {
-                let cut = new_last.len() - 3;
-                new_last.replace_range(cut.., "...");
-            } else {
-                new_last = "...".to_string();
-            }
-            body_wrap[3] = Cow::Owned(new_last);
+        // - 2 for the borders
+        let width = width.saturating_sub(2);
+        let mut title_lines = wrap_cache.wrap(&post.title, width);
+        let domain = post
+            .outbound_link()
+            .map(|v| format!("({})", text::domain(v)));
+        // The domain follows the title, on a line of its own when it doesn't fit after it
+        if let Some(domain) = &domain
+            && title_lines
+                .last()
+                .is_some_and(|v| text::width(v) + 1 + text::width(domain) > width)
+        {
+            title_lines.push(String::default());
         }
-        let body_lines = body_wrap.iter().map(|i| i.to_string()).collect();
-        let score = post.score;
+        let mut body_lines = wrap_cache.wrap(&text::hide_spoilers(&post.body), width);
+        if body_lines.len() > 4 {
+            body_lines.truncate(4);
+            body_lines[3] = text::ellipsize(&body_lines[3], width);
+        }
+        let score = post.score_text(compact_scores);
         let num_comments = post.num_comments;
 
         Self {
+            date_header: None,
             username,
+            user_tag,
             title_lines,
             body_lines,
-            background: None,
+            is_selected: false,
+            is_marked: false,
             score,
             num_comments,
             created: post.created_at,
+            now,
+            video_badge: post.video_badge(),
+            kind: post.kind(),
+            domain,
+            crosspost_source: post.crosspost_source(),
+            theme,
         }
     }
 
     pub fn height(&self) -> usize {
-        self.title_lines.len()
+        self.date_header.is_some() as usize
+         + self.title_lines.len()
+         + self.crosspost_source.is_some() as usize
          + 1 //Spacing
          + self.body_lines.len()
          + 2 //block border
     }
 
-    fn set_background(&mut self, background: Color) {
-        self.background = Some(background);
+    fn set_selected(&mut self) {
+        self.is_selected = true;
+    }
+
+    fn set_date_header(&mut self, date_header: String) {
+        self.date_header = Some(date_header);
     }
 }
 
@@ -240,33 +997,84 @@ impl Widget for PostItem {
     where
         Self: Sized,
     {
-        let now = Utc::now();
-        let created = HumanTime::from(self.created - now);
+        let area = if let Some(date_header) = self.date_header {
+            let [header_area, area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+            Block::new()
+                .borders(Borders::TOP)
+                .title(format!(" {} ", date_header).bold())
+                .render(header_area, buf);
+            area
+        } else {
+            area
+        };
+        let created = HumanTime::from(self.created - self.now);
+        let mut title = vec![
+            format!("{} ", self.kind.glyph()).into(),
+            format!("u/{}", self.username).italic(),
+        ];
+        if self.is_marked {
+            title.insert(0, "✔ ".fg(Color::Cyan).bold());
+        }
+        if let Some(user_tag) = &self.user_tag {
+            title.push(" ".into());
+            title.push(user_tag.span());
+        }
+        title.push(format!(" • {}", created).italic());
         let mut block = Block::bordered()
             .border_type(BorderType::Rounded)
-            .title(format!("u/{} • {}", self.username, created).italic())
-            .title_bottom(format!("👍🏻{}", self.score.to_string()))
-            .title_bottom(format!("💬{}", self.num_comments.to_string()));
+            .title(Line::from(title))
+            .title_bottom(format!("👍🏻{}", self.score))
+            .title_bottom(format!("💬{}", self.num_comments));
+        if let Some(video_badge) = self.video_badge {
+            block = block.title(Line::from(video_badge).right_aligned());
+        }
 
-        if let Some(background) = self.background {
-            block = block.bg(background);
+        if self.is_selected {
+            block = block
+                .border_set(self.theme.selected_border_set(border::ROUNDED))
+                .style(self.theme.selected_style());
         }
 
         let [title_area, body_area] = Layout::vertical([
-            Constraint::Length(self.title_lines.len() as u16 + 1),
+            Constraint::Length(
+                self.title_lines.len() as u16 + self.crosspost_source.is_some() as u16 + 1,
+            ),
             Constraint::Fill(1),
         ])
         .areas(block.inner(area));
         block.render(area, buf);
 
-        Paragraph::new(
-            self.title_lines
-                .iter()
-                .map(|i| Line::from(i.clone()))
-                .collect::<Vec<Line>>(),
-        )
-        .add_modifier(Modifier::BOLD)
-        .render(title_area, buf);
+        let mut title_lines = self
+            .title_lines
+            .iter()
+            .map(|i| Line::from(i.clone()))
+            .collect::<Vec<Line>>();
+        if let Some(domain) = self.domain
+            && let Some(last) = title_lines.last_mut()
+        {
+            if last.width() > 0 {
+                last.push_span(" ");
+            }
+            last.push_span(domain.not_bold().fg(self.theme.muted()));
+        }
+        Paragraph::new(title_lines)
+            .add_modifier(Modifier::BOLD)
+            .render(title_area, buf);
+        if let Some(crosspost_source) = self.crosspost_source {
+            let [_, source_area, _] = Layout::vertical([
+                Constraint::Length(self.title_lines.len() as u16),
+                Constraint::Length(1),
+                Constraint::Fill(1),
+            ])
+            .areas(title_area);
+            Paragraph::new(
+                format!("🔁 {}", crosspost_source)
+                    .fg(self.theme.muted())
+                    .italic(),
+            )
+            .render(source_area, buf);
+        }
         Paragraph::new(
             self.body_lines
                 .iter()
@@ -276,3 +1084,119 @@ impl Widget for PostItem {
         .render(body_area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+    use serde_json::json;
+    use tokio::sync::mpsc::{self, Receiver};
+
+    use super::*;
+    use crate::reddit_api::MockRedditApi;
+
+    fn component(reddit_api: Arc<MockRedditApi>) -> (PostlistComponent, Receiver<AppEvent>) {
+        let (sender, receiver) = mpsc::channel(100);
+        let component = PostlistComponent::new(
+            reddit_api,
+            Arc::new(Picker::from_fontsize((8, 12))),
+            Arc::new(RwLock::new(StateStore::in_memory())),
+            &Config::default(),
+            Clock::default(),
+            sender,
+        );
+        (component, receiver)
+    }
+
+    /// Hand the loader results to the component like `App` does, until the posts are in
+    async fn wait_for_posts(component: &mut PostlistComponent, receiver: &mut Receiver<AppEvent>) {
+        while let Some(event) = receiver.recv().await {
+            if let AppEvent::Postlist(event) = event {
+                let posts_loaded = matches!(event, PostlistEvent::PostsLoaded { .. });
+                component.handle_loaded(event);
+                if posts_loaded {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn screen(component: &mut PostlistComponent) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| component.draw(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|v| v.symbol()).collect::<String>() + "\n")
+            .collect()
+    }
+
+    fn post(id: &str, title: &str) -> serde_json::Value {
+        json!({
+            "kind": "t3",
+            "data": {
+                "id": id,
+                "subreddit": "rust",
+                "author": "ferris",
+                "title": title,
+                "selftext": "",
+                "url": format!("https://www.reddit.com/r/rust/comments/{}/", id),
+                "num_comments": 3,
+                "score": 42,
+                "created_utc": 1_700_000_000.0,
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn shows_the_posts_of_the_sub() {
+        let listing = json!({
+            "kind": "Listing",
+            "data": { "children": [post("a1", "Rust 2024 is out"), post("b2", "Weekly questions")] },
+        });
+        let reddit_api =
+            Arc::new(MockRedditApi::default().with("r/rust/best", listing.to_string()));
+        let (mut component, mut receiver) = component(reddit_api.clone());
+
+        component.load("rust".to_string());
+        wait_for_posts(&mut component, &mut receiver).await;
+
+        let screen = screen(&mut component);
+        assert!(screen.contains("r/rust"), "{}", screen);
+        assert!(screen.contains("Rust 2024 is out"), "{}", screen);
+        assert!(screen.contains("Weekly questions"), "{}", screen);
+        assert!(reddit_api.requests().contains(&"r/rust/best".to_string()));
+    }
+
+    #[tokio::test]
+    async fn says_which_sub_failed_to_load() {
+        let reddit_api = Arc::new(MockRedditApi::default());
+        let (mut component, mut receiver) = component(reddit_api);
+
+        component.load("rust".to_string());
+        wait_for_posts(&mut component, &mut receiver).await;
+
+        let screen = screen(&mut component);
+        assert!(screen.contains("Cannot load r/rust"), "{}", screen);
+    }
+
+    #[tokio::test]
+    async fn says_when_the_sub_doesnt_exist() {
+        // Reddit answers with the subs whose name is close
+        let search = json!({
+            "kind": "Listing",
+            "data": {
+                "children": [{ "kind": "t5", "data": { "display_name": "rust" } }]
+            }
+        });
+        let reddit_api =
+            Arc::new(MockRedditApi::default().with("r/rustt/best", search.to_string()));
+        let (mut component, mut receiver) = component(reddit_api);
+
+        component.load("rustt".to_string());
+        wait_for_posts(&mut component, &mut receiver).await;
+
+        let screen = screen(&mut component);
+        assert!(screen.contains("There's no r/rustt"), "{}", screen);
+    }
+}
@@ -0,0 +1,120 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, List, ListState, StatefulWidget, Widget},
+};
+
+use crate::{clipboard, keymap::Action, model::post::Post, opener::Opener, theme::Theme};
+
+pub enum SharePopupEvent {
+    None,
+    Close,
+    /// Handle the action for the post, like its key would outside the popup
+    Share(Action),
+}
+
+/// Menu of the ways to open or copy a post, each entry also has a key of its own
+pub struct SharePopup {
+    entries: Vec<(&'static str, &'static str, Action)>,
+    list_state: ListState,
+    theme: Theme,
+}
+
+impl SharePopup {
+    pub fn new(post: &Post, theme: Theme) -> Self {
+        let mut entries = vec![
+            ("o", "Open in browser", Action::OpenInBrowser),
+            ("O", "Open on old.reddit", Action::OpenOnOldReddit),
+            ("p", "Copy permalink", Action::CopyPermalink),
+            ("m", "Copy as markdown", Action::CopyMarkdown),
+        ];
+        if post.external_url().is_some() {
+            entries.push(("u", "Copy link", Action::CopyLink));
+        }
+        Self {
+            entries,
+            list_state: ListState::default().with_selected(Some(0)),
+            theme,
+        }
+    }
+
+    pub fn handle_action(&mut self, action: Action) -> SharePopupEvent {
+        match action {
+            Action::Back => SharePopupEvent::Close,
+            Action::Down => {
+                self.list_state.select_next();
+                SharePopupEvent::None
+            }
+            Action::Up => {
+                self.list_state.select_previous();
+                SharePopupEvent::None
+            }
+            Action::Open => self
+                .list_state
+                .selected()
+                .and_then(|i| self.entries.get(i))
+                .map_or(SharePopupEvent::None, |(_, _, action)| {
+                    SharePopupEvent::Share(*action)
+                }),
+            action if self.entries.iter().any(|(_, _, v)| *v == action) => {
+                SharePopupEvent::Share(action)
+            }
+            _ => SharePopupEvent::None,
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let [center_vertical] =
+            Layout::vertical([Constraint::Length(self.entries.len() as u16 + 2)])
+                .flex(Flex::Center)
+                .areas(area);
+        let [center] = Layout::horizontal([Constraint::Length(30)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        let buf = frame.buffer_mut();
+        Clear.render(center, buf);
+
+        let items = self
+            .entries
+            .iter()
+            .map(|(key, label, _)| Line::from(vec![format!("[{}] ", key).bold(), (*label).into()]));
+        let list = List::new(items)
+            .highlight_style(self.theme.selected_style())
+            .highlight_symbol(self.theme.highlight_symbol())
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("Share"),
+            );
+        StatefulWidget::render(list, center, buf, &mut self.list_state);
+    }
+}
+
+/// Open or copy `post` for one of the share or `Copy*` actions, returns the status to show
+pub fn share_post(post: &Post, action: Action, opener: &Opener) -> Option<Line<'static>> {
+    let opened = match action {
+        Action::OpenInBrowser => opener.open(&post.permalink()),
+        Action::OpenOnOldReddit => opener.open(&format!(
+            "https://old.reddit.com/r/{}/comments/{}",
+            post.subreddit, post.id
+        )),
+        Action::CopyTitle => return Some(clipboard::copy_with_status(&post.title, "title")),
+        Action::CopyMarkdown => {
+            let title = post.title.replace('[', "\\[").replace(']', "\\]");
+            let markdown = format!("[{}]({})", title, post.permalink());
+            return Some(clipboard::copy_with_status(&markdown, "markdown link"));
+        }
+        Action::CopyLink if let Some(url) = post.external_url() => {
+            return Some(clipboard::copy_with_status(url, "link"));
+        }
+        Action::CopyPermalink | Action::CopyLink => {
+            return Some(clipboard::copy_with_status(&post.permalink(), "permalink"));
+        }
+        _ => return None,
+    };
+    opened
+        .err()
+        .map(|err| Line::from(format!("Cannot open the browser: {}", err).fg(Color::Red)))
+}
@@ -0,0 +1,190 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, List, ListState, Paragraph, StatefulWidget, Widget},
+};
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use crate::{
+    model::subreddit::{sub_name, valid_sub_name},
+    ngored_error::NgoredError,
+    theme::Theme,
+};
+
+/// Most completions listed under the input
+const MAX_COMPLETIONS: usize = 6;
+
+pub enum SubPromptEvent {
+    None,
+    Cancel,
+    /// Name of the sub to look up on reddit, without `r/`, the prompt stays open until
+    /// `checked` gets the result
+    Submit(String),
+}
+
+/// Popup to open any sub by name, completing from the subs it's given
+pub struct SubPrompt {
+    input: Input,
+    subs: Vec<String>,
+    list_state: ListState,
+    /// Why the name typed can't be opened
+    error: Option<String>,
+    /// Name typed being looked up on reddit before it's opened
+    checking: Option<String>,
+    theme: Theme,
+}
+
+impl SubPrompt {
+    pub fn new(subs: Vec<String>, theme: Theme) -> Self {
+        Self {
+            input: Input::default(),
+            subs,
+            list_state: ListState::default(),
+            error: None,
+            checking: None,
+            theme,
+        }
+    }
+
+    /// Subs starting with what's typed first, then the ones containing it
+    fn completions(&self) -> Vec<&str> {
+        let typed = sub_name(self.input.value()).to_lowercase();
+        let (mut starts, contains): (Vec<&str>, Vec<&str>) = self
+            .subs
+            .iter()
+            .map(String::as_str)
+            .filter(|v| v.to_lowercase().contains(&typed))
+            .partition(|v| v.to_lowercase().starts_with(&typed));
+        starts.extend(contains);
+        starts.dedup_by_key(|v| v.to_lowercase());
+        starts.truncate(MAX_COMPLETIONS);
+        starts
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> SubPromptEvent {
+        let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return SubPromptEvent::None;
+        };
+        match code {
+            KeyCode::Esc => SubPromptEvent::Cancel,
+            KeyCode::Enter => {
+                let completions = self.completions();
+                let sub = match self.list_state.selected().and_then(|i| completions.get(i)) {
+                    Some(sub) => sub.to_string(),
+                    None => sub_name(self.input.value()).to_string(),
+                };
+                if sub.is_empty() {
+                    return SubPromptEvent::Cancel;
+                }
+                match valid_sub_name(&sub) {
+                    Ok(name) => {
+                        self.error = None;
+                        self.checking = Some(name.to_string());
+                        SubPromptEvent::Submit(name.to_string())
+                    }
+                    // Stay in the input to fix the name
+                    Err(err) => {
+                        self.error = Some(err.to_string());
+                        SubPromptEvent::None
+                    }
+                }
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                let count = self.completions().len();
+                if count > 0 {
+                    let next = self.list_state.selected().map_or(0, |v| (v + 1) % count);
+                    self.list_state.select(Some(next));
+                }
+                SubPromptEvent::None
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                let count = self.completions().len();
+                if count > 0 {
+                    let previous = self
+                        .list_state
+                        .selected()
+                        .map_or(count - 1, |v| (v + count - 1) % count);
+                    self.list_state.select(Some(previous));
+                }
+                SubPromptEvent::None
+            }
+            _ => {
+                if self.input.handle_event(event).is_some() {
+                    // The completions changed, the old pick would point at another sub
+                    self.list_state.select(None);
+                    self.checking = None;
+                }
+                SubPromptEvent::None
+            }
+        }
+    }
+
+    /// The sub to open once `input` was found on reddit, the error is shown in the prompt
+    /// otherwise. Nothing when something else was typed since
+    pub fn checked(&mut self, input: &str, result: Result<String, NgoredError>) -> Option<String> {
+        if self.checking.as_deref() != Some(input) {
+            return None;
+        }
+        self.checking = None;
+        result
+            .map_err(|err| self.error = Some(err.to_string()))
+            .ok()
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let completions = self
+            .completions()
+            .into_iter()
+            .map(|v| Line::from(format!("r/{}", v)))
+            .collect::<Vec<_>>();
+        let [center_vertical] =
+            Layout::vertical([Constraint::Length(completions.len() as u16 + 3)])
+                .flex(Flex::Center)
+                .areas(area);
+        let [center] = Layout::horizontal([Constraint::Percentage(50)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        let buf = frame.buffer_mut();
+        Clear.render(center, buf);
+
+        let status = if let Some(checking) = self.checking.as_deref() {
+            Line::from(format!("Looking up r/{}…", checking)).fg(self.theme.muted())
+        } else if let Some(error) = self.error.as_deref() {
+            Line::from(error).fg(Color::Red)
+        } else {
+            Line::from("[Tab] complete • [Esc] cancel")
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title("Jump to sub")
+            .title_bottom(status);
+        let inner = block.inner(center);
+        block.render(center, buf);
+        let [input_area, completions_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+        // `r/` goes in front of the input unless it was typed
+        let value = self.input.value();
+        let prefix = if sub_name(value).len() < value.trim().len() {
+            ""
+        } else {
+            "r/"
+        };
+        Paragraph::new(format!("{}{}", prefix, value)).render(input_area, buf);
+        let list = List::new(completions)
+            .highlight_style(self.theme.selected_style())
+            .highlight_symbol(self.theme.highlight_symbol());
+        StatefulWidget::render(list, completions_area, buf, &mut self.list_state);
+
+        let scroll = self.input.visual_scroll(inner.width.max(3) as usize - 3);
+        let x = self.input.visual_cursor().max(scroll) - scroll + prefix.len();
+        frame.set_cursor_position((inner.x + x as u16, inner.y));
+    }
+}
@@ -1,36 +1,391 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
-    layout::{Constraint, Flex, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
     widgets::{Block, BorderType, List, ListState, Paragraph, StatefulWidget, Widget},
 };
-use tokio::sync::mpsc::Sender;
+use tokio::{sync::mpsc::Sender, task::JoinHandle};
 use tui_input::{Input, backend::crossterm::EventHandler};
 
-use crate::{app::AppEvent, component::Component, config::Config, ngored_error::NgoredError};
+use crate::{
+    app::AppEvent,
+    component::{
+        Component, StatusInfo,
+        confirm_popup::{ConfirmPopup, ConfirmPopupEvent},
+    },
+    config::Config,
+    format,
+    keymap::{Action, KeyContext},
+    model::subreddit::{self, SubredditAbout, sub_name},
+    ngored_error::NgoredError,
+    reddit_api::{Data, RedditApi},
+    state_store::StateStore,
+    theme::{SubAccents, Theme},
+};
+
+/// Blank columns between two columns of subs
+const COLUMN_GAP: usize = 2;
+
+/// Most edits `Undo` can take back
+const UNDO_LEN: usize = 10;
+
+/// A removed sub stays in the config this long, so undoing the removal doesn't touch the file
+const UNDO_WINDOW: Duration = Duration::from_secs(10);
+
+/// Subs selected within this time of each other wait this long before their about page is
+/// loaded, so holding a key only loads the one it stops on
+const ABOUT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Results of the sublist's tasks, delivered through `AppEvent::Sublist`
+pub enum SublistEvent {
+    /// Whether the sub typed in `input` exists, with its name as reddit spells it
+    SubChecked {
+        input: String,
+        result: Result<String, NgoredError>,
+    },
+    /// Members and description of `sub`, nothing is sent when its about page can't be loaded
+    AboutLoaded { sub: String, about: SubredditAbout },
+    /// `UNDO_WINDOW` is over since the last removal, it's written to the config
+    UndoWindowClosed,
+}
+
+/// A change to the sublist, kept to be undone
+enum SublistEdit {
+    Added(String),
+    /// The sub and where it was
+    Removed(usize, String),
+}
 
 pub struct SublistComponent {
+    reddit_api: Arc<dyn RedditApi>,
     app_event_sender: Sender<AppEvent>,
+    state_store: Arc<RwLock<StateStore>>,
     subs: Vec<String>,
     list_state: ListState,
     adding: bool,
     sub_input: Input,
+    /// Why the name typed can't be added
+    add_error: Option<String>,
+    /// Name typed being looked up on reddit before it's added
+    checking: Option<String>,
+    /// Ask before `RemoveSub` removes the selected sub
+    confirm: bool,
+    confirm_popup: Option<ConfirmPopup>,
+    /// About pages of the subs selected this session. The ones that failed are left out, they're
+    /// tried again the next time their sub is selected
+    about: HashMap<String, SubredditAbout>,
+    /// Sub whose about page was asked for last, and the task loading it
+    about_load: Option<(String, JoinHandle<()>)>,
+    /// When the last about page was asked for, for `ABOUT_DEBOUNCE`
+    last_about_load: Option<Instant>,
+    /// Edits of this session, the last one is undone first
+    undo: Vec<SublistEdit>,
+    /// Waits out `UNDO_WINDOW` before the removals not written yet go to the config
+    save_handle: Option<JoinHandle<()>>,
+    /// Why the last post to read later couldn't be taken off the queue, until the next key
+    state_error: Option<String>,
+    /// Subs per column at the last draw, 0 when they fit in a single column
+    column_rows: usize,
+    /// Leftmost column in view, when there are more columns than fit
+    first_column: usize,
+    /// Rows of the list in view at the last draw, moved by PageUp/PageDown
+    page_len: u16,
+    scrolloff: u16,
+    /// Show member counts as `12.3k`, off with `full_scores`
+    compact_scores: bool,
+    theme: Theme,
+    sub_accents: SubAccents,
 }
 
 impl SublistComponent {
-    pub fn new(subs: Vec<String>, app_event_sender: Sender<AppEvent>) -> Self {
+    pub fn new(
+        reddit_api: Arc<dyn RedditApi>,
+        state_store: Arc<RwLock<StateStore>>,
+        config: &Config,
+        app_event_sender: Sender<AppEvent>,
+    ) -> Self {
         SublistComponent {
+            reddit_api,
             app_event_sender,
-            subs: subs,
+            state_store,
+            subs: config.subs.clone(),
             list_state: ListState::default().with_selected(Some(0)),
             adding: false,
             sub_input: Input::default(),
+            add_error: None,
+            checking: None,
+            confirm: config.confirm.unwrap_or(true),
+            confirm_popup: None,
+            about: HashMap::default(),
+            about_load: None,
+            last_about_load: None,
+            undo: Vec::default(),
+            save_handle: None,
+            state_error: None,
+            column_rows: 0,
+            first_column: 0,
+            page_len: 1,
+            scrolloff: config.scrolloff.unwrap_or(0),
+            compact_scores: !config.full_scores,
+            theme: Theme::new(config),
+            sub_accents: SubAccents::new(config),
+        }
+    }
+
+    pub fn subs(&self) -> &[String] {
+        &self.subs
+    }
+
+    fn remove_selected_sub(&mut self) {
+        if let Some(selected_index) = self.list_state.selected()
+            && selected_index < self.subs.len()
+        {
+            let sub = self.subs.remove(selected_index);
+            self.push_edit(SublistEdit::Removed(selected_index, sub));
+            self.save_later();
+        }
+    }
+
+    /// Write the subs to the config once `UNDO_WINDOW` is over, starting the wait over
+    fn save_later(&mut self) {
+        if let Some(handle) = self.save_handle.take() {
+            handle.abort();
+        }
+        self.save_handle = Some(tokio::spawn({
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                tokio::time::sleep(UNDO_WINDOW).await;
+                app_event_sender
+                    .send(AppEvent::Sublist(SublistEvent::UndoWindowClosed))
+                    .await
+                    .unwrap();
+            }
+        }));
+    }
+
+    /// Write the subs to the config now, with any removal still waiting for `UNDO_WINDOW`
+    fn save_subs(&mut self) {
+        if let Some(handle) = self.save_handle.take() {
+            handle.abort();
+        }
+        Config::save_subs(self.subs.clone());
+    }
+
+    /// Write a removal still waiting for `UNDO_WINDOW`, when the app quits
+    pub fn save_pending(&mut self) {
+        if self.save_handle.is_some() {
+            self.save_subs();
+        }
+    }
+
+    /// Add `sub` at the end, unless it's already in the list under any casing, then it's only
+    /// selected
+    fn add_sub(&mut self, sub: String) {
+        if let Some(index) = self.subs.iter().position(|v| v.eq_ignore_ascii_case(&sub)) {
+            self.list_state.select(Some(index));
+            return;
+        }
+        self.subs.push(sub.clone());
+        self.save_subs();
+        self.push_edit(SublistEdit::Added(sub));
+        self.list_state.select(Some(self.subs.len() - 1));
+    }
+
+    /// Look `name` up on reddit, each sub of a multi on its own
+    fn check_sub(&mut self, name: String) {
+        self.checking = Some(name.clone());
+        tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            async move {
+                let result = subreddit::look_up(&*reddit_api, &name).await;
+                app_event_sender
+                    .send(AppEvent::Sublist(SublistEvent::SubChecked {
+                        input: name,
+                        result,
+                    }))
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
+    /// Load the about page of the selected sub unless it's known or already asked for. Called
+    /// whenever the selection may have changed, the load of a sub that isn't selected anymore is
+    /// aborted
+    pub fn load_selected_about(&mut self) {
+        // The selection is past the end after `select_last` until the list is rendered
+        let sub = self
+            .list_state
+            .selected()
+            .and_then(|v| self.subs.get(v.min(self.subs.len().saturating_sub(1))))
+            .cloned();
+        if let Some((loading, about_handle)) = self.about_load.take() {
+            if sub.as_ref() == Some(&loading) {
+                self.about_load = Some((loading, about_handle));
+                return;
+            }
+            about_handle.abort();
+        }
+        let Some(sub) = sub.filter(|v| !self.about.contains_key(v)) else {
+            return;
+        };
+        let debounce = self
+            .last_about_load
+            .is_some_and(|v| v.elapsed() < ABOUT_DEBOUNCE);
+        self.last_about_load = Some(Instant::now());
+        let about_handle = tokio::spawn({
+            let reddit_api = self.reddit_api.clone();
+            let app_event_sender = self.app_event_sender.clone();
+            let sub = sub.clone();
+            async move {
+                // Aborted by the next selection if it comes before the wait is over
+                if debounce {
+                    tokio::time::sleep(ABOUT_DEBOUNCE).await;
+                }
+                if let Ok(Data::Subreddit(about)) = reddit_api.get_subreddit_about(&sub).await {
+                    let about = SubredditAbout::from(about);
+                    app_event_sender
+                        .send(AppEvent::Sublist(SublistEvent::AboutLoaded { sub, about }))
+                        .await
+                        .unwrap();
+                }
+            }
+        });
+        self.about_load = Some((sub, about_handle));
+    }
+
+    /// What's known of `sub` from its about page, shown after its name: members and the first
+    /// line of its description
+    fn about_line(&self, sub: &str) -> Option<String> {
+        let about = self.about.get(sub)?;
+        let mut parts = Vec::new();
+        if let Some(subscribers) = about.subscribers {
+            parts.push(format!(
+                "{} members",
+                format::score(subscribers as i64, self.compact_scores)
+            ));
+        }
+        if let Some(description) = about.description.lines().find(|v| !v.trim().is_empty()) {
+            parts.push(description.trim().to_string());
+        }
+        (!parts.is_empty()).then(|| parts.join(" • "))
+    }
+
+    pub fn handle_loaded(&mut self, event: SublistEvent) {
+        match event {
+            SublistEvent::AboutLoaded { sub, about } => {
+                self.about.insert(sub, about);
+            }
+            SublistEvent::UndoWindowClosed => self.save_pending(),
+            SublistEvent::SubChecked { input, result } => {
+                // Closed or typed over while it was looked up
+                if !self.adding || self.checking.as_ref() != Some(&input) {
+                    return;
+                }
+                self.checking = None;
+                match result {
+                    Ok(sub) => {
+                        self.adding = false;
+                        self.add_error = None;
+                        self.sub_input.reset();
+                        self.add_sub(sub);
+                        self.load_selected_about();
+                    }
+                    Err(err) => self.add_error = Some(err.to_string()),
+                }
+            }
+        }
+    }
+
+    async fn open_selected(&self) -> Result<(), NgoredError> {
+        if let Some(sub) = self.list_state.selected().and_then(|v| self.subs.get(v)) {
+            self.app_event_sender
+                .send(AppEvent::OpenPostList(sub.clone()))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Lay the subs out top to bottom in columns when they don't fit in `rows` and the columns
+    /// of at least two fit side by side in `width`. Rows per column and the columns' width, none
+    /// for a single column
+    fn columns(&self, rows: usize, width: usize) -> Option<(usize, usize)> {
+        if rows == 0 || self.subs.len() <= rows {
+            return None;
+        }
+        let column_width = self.subs.iter().map(|v| v.chars().count()).max()?
+            + self.theme.highlight_symbol().len()
+            + COLUMN_GAP;
+        (width / column_width >= 2).then_some((rows, column_width))
+    }
+
+    fn push_edit(&mut self, edit: SublistEdit) {
+        if self.undo.len() == UNDO_LEN {
+            self.undo.remove(0);
+        }
+        self.undo.push(edit);
+    }
+
+    /// Take back the last edit, a removed sub goes back where it was and is selected
+    fn undo(&mut self) {
+        match self.undo.pop() {
+            Some(SublistEdit::Added(sub)) => {
+                self.subs.retain(|v| *v != sub);
+                self.list_state
+                    .select((!self.subs.is_empty()).then_some(self.subs.len() - 1));
+            }
+            Some(SublistEdit::Removed(index, sub)) => {
+                let index = index.min(self.subs.len());
+                self.subs.insert(index, sub);
+                self.list_state.select(Some(index));
+            }
+            None => return,
         }
+        self.save_subs();
     }
 }
 
+#[async_trait]
 impl Component for SublistComponent {
+    fn key_context(&self) -> Option<KeyContext> {
+        (!self.adding && self.confirm_popup.is_none()).then_some(KeyContext::Sublist)
+    }
+
+    fn status_info(&self) -> StatusInfo {
+        StatusInfo {
+            // The selection is past the end after `select_last` until the next draw
+            position: self
+                .list_state
+                .selected()
+                .filter(|_| !self.subs.is_empty())
+                .map(|v| (v.min(self.subs.len() - 1) + 1, self.subs.len())),
+            ..StatusInfo::default()
+        }
+    }
+
     async fn handle_event(&mut self, event: &Event) -> Result<(), NgoredError> {
+        if let Some(confirm_popup) = self.confirm_popup.as_mut() {
+            match confirm_popup.handle_event(event) {
+                ConfirmPopupEvent::None => {}
+                ConfirmPopupEvent::Cancel => self.confirm_popup = None,
+                ConfirmPopupEvent::Confirm => {
+                    self.confirm_popup = None;
+                    self.remove_selected_sub();
+                    self.load_selected_about();
+                }
+            }
+            self.app_event_sender.send(AppEvent::Draw).await?;
+            return Ok(());
+        }
         if self.adding {
             match event {
                 Event::Key(KeyEvent {
@@ -40,6 +395,8 @@ impl Component for SublistComponent {
                 }) => {
                     self.adding = false;
                     self.sub_input.reset();
+                    self.add_error = None;
+                    self.checking = None;
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
                 Event::Key(KeyEvent {
@@ -47,82 +404,239 @@ impl Component for SublistComponent {
                     kind: KeyEventKind::Press,
                     ..
                 }) => {
-                    self.adding = false;
-                    let new_sub = self.sub_input.value_and_reset();
-                    if !new_sub.is_empty() && !self.subs.contains(&new_sub) {
-                        self.subs.push(new_sub);
-                        Config::new(self.subs.clone()).save();
-                        if self.list_state.selected().is_none() {
-                            self.list_state.select(Some(0));
+                    match subreddit::valid_sub_name(self.sub_input.value()) {
+                        // Already listed, no need to ask reddit
+                        Ok(new_sub)
+                            if self.subs.iter().any(|v| v.eq_ignore_ascii_case(new_sub)) =>
+                        {
+                            let new_sub = new_sub.to_string();
+                            self.adding = false;
+                            self.add_error = None;
+                            self.sub_input.reset();
+                            self.add_sub(new_sub);
+                            self.load_selected_about();
                         }
+                        Ok(new_sub) => {
+                            self.add_error = None;
+                            self.check_sub(new_sub.to_string());
+                        }
+                        // Nothing typed is giving up on adding
+                        Err(_) if sub_name(self.sub_input.value()).is_empty() => {
+                            self.adding = false;
+                            self.add_error = None;
+                        }
+                        // Stay in the input to fix the name
+                        Err(err) => self.add_error = Some(err.to_string()),
                     }
                     self.app_event_sender.send(AppEvent::Draw).await?;
                 }
                 _ => {
                     if self.sub_input.handle_event(event).is_some() {
+                        self.checking = None;
                         self.app_event_sender.send(AppEvent::Draw).await?;
                     }
                 }
             }
-        } else {
-            match event {
-                Event::Key(KeyEvent {
-                    code,
-                    kind: KeyEventKind::Press,
-                    ..
-                }) => match code {
-                    KeyCode::Char('j') => {
-                        self.list_state.select_next();
-                        self.app_event_sender.send(AppEvent::Draw).await?;
-                    }
-                    KeyCode::Char('k') => {
-                        self.list_state.select_previous();
-                        self.app_event_sender.send(AppEvent::Draw).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_action(&mut self, action: Action) -> Result<(), NgoredError> {
+        self.state_error = None;
+        match action {
+            Action::Down => {
+                self.list_state.select_next();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Up => {
+                self.list_state.select_previous();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PageDown => {
+                self.list_state.scroll_down_by(self.page_len);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PageUp => {
+                self.list_state.scroll_up_by(self.page_len);
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Top => {
+                self.list_state.select_first();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Bottom => {
+                self.list_state.select_last();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::AddSub => {
+                self.adding = true;
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::RemoveSub => {
+                let selected = self.list_state.selected().and_then(|i| self.subs.get(i));
+                match selected {
+                    Some(sub) if self.confirm => {
+                        let question = format!("Remove r/{} from the sublist?", sub);
+                        self.confirm_popup = Some(ConfirmPopup::new(question));
                     }
-                    KeyCode::Char('a') => {
-                        self.adding = true;
-                        self.app_event_sender.send(AppEvent::Draw).await?;
+                    _ => self.remove_selected_sub(),
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::Undo => {
+                self.undo();
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::OpenModQueue => {
+                self.app_event_sender.send(AppEvent::OpenModQueue).await?;
+            }
+            Action::OpenFriends => {
+                self.app_event_sender
+                    .send(AppEvent::OpenPostList("friends".to_string()))
+                    .await?;
+            }
+            Action::OpenModmail => {
+                self.app_event_sender.send(AppEvent::OpenModmail).await?;
+            }
+            Action::NextReadLater => {
+                let next = self.state_store.write().unwrap().pop_read_later();
+                match next {
+                    Ok(Some(post)) => {
+                        self.app_event_sender
+                            .send(AppEvent::OpenPermalink(post.permalink()))
+                            .await?
                     }
-                    KeyCode::Char('d') => {
-                        if let Some(selected_index) = self.list_state.selected() {
-                            self.subs.remove(selected_index);
-                            Config::new(self.subs.clone()).save();
-                        }
-                        self.app_event_sender.send(AppEvent::Draw).await?;
+                    Ok(None) => {}
+                    Err(err) => {
+                        self.state_error = Some(err.to_string());
+                        self.app_event_sender.send(AppEvent::Draw).await?
                     }
-                    KeyCode::Char('l') => {
-                        if let Some(selected_index) = self.list_state.selected() {
-                            if let Some(sub) = self.subs.get(selected_index) {
-                                self.app_event_sender
-                                    .send(AppEvent::OpenPostList(sub.clone()))
-                                    .await?;
-                            }
-                        }
+                }
+            }
+            Action::Open => self.open_selected().await?,
+            Action::NextColumn if self.column_rows == 0 => self.open_selected().await?,
+            Action::NextColumn => {
+                if let Some(selected) = self.list_state.selected() {
+                    let last = self.subs.len().saturating_sub(1);
+                    // The last column can be shorter, land on its last sub
+                    if selected / self.column_rows < last / self.column_rows {
+                        self.list_state
+                            .select(Some((selected + self.column_rows).min(last)));
                     }
-                    _ => {}
-                },
-                _ => {}
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
+            }
+            Action::PreviousColumn => {
+                if let Some(selected) = self.list_state.selected()
+                    && self.column_rows > 0
+                    && selected >= self.column_rows
+                {
+                    self.list_state.select(Some(selected - self.column_rows));
+                }
+                self.app_event_sender.send(AppEvent::Draw).await?;
             }
+            _ => {}
         }
+        self.load_selected_about();
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut ratatui::Frame) {
-        let area = frame.area();
+    fn draw(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let buf = frame.buffer_mut();
-        let selected_style = Style::new()
-            .bg(Color::DarkGray)
-            .add_modifier(Modifier::BOLD);
-        let list = List::new(self.subs.clone())
-            .highlight_style(selected_style)
-            .block(
-                Block::bordered()
-                    .border_type(BorderType::Rounded)
-                    .title("Sublist"),
+        let mut block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title("Sublist");
+        match self.undo.last() {
+            Some(SublistEdit::Added(sub)) => {
+                block = block.title_bottom(format!("Added r/{} • [u] undo", sub));
+            }
+            Some(SublistEdit::Removed(_, sub)) => {
+                block = block.title_bottom(format!("Removed r/{} • [u] undo", sub));
+            }
+            None => {}
+        }
+        if let Some(state_error) = self.state_error.as_deref() {
+            block = block.title_bottom(Line::from(state_error).fg(Color::Red).right_aligned());
+        }
+        let read_later = self.state_store.read().unwrap().read_later.len();
+        if read_later > 0 {
+            block = block.title(
+                Line::from(format!("{} to read later • [n] next", read_later)).right_aligned(),
             );
-        StatefulWidget::render(list, area, buf, &mut self.list_state);
+        }
+        // The selection is past the end after `select_last` until the list is rendered
+        let selected = self
+            .list_state
+            .selected()
+            .map(|v| v.min(self.subs.len().saturating_sub(1)));
+        let inner = block.inner(area);
+        let columns = self.columns(inner.height as usize, inner.width as usize);
+        self.column_rows = columns.map_or(0, |(rows, _)| rows);
+        let items = self.subs.iter().enumerate().map(|(index, sub)| {
+            let mut line = match self.sub_accents.accent(sub) {
+                Some(accent) => Line::from(sub.as_str().fg(accent)),
+                None => Line::from(sub.as_str()),
+            };
+            // A column has no room for it
+            if selected == Some(index)
+                && columns.is_none()
+                && let Some(about) = self.about_line(sub)
+            {
+                line.push_span(format!("  {}", about).fg(self.theme.muted()));
+            }
+            line
+        });
+        let items: Vec<_> = items.collect();
+        match columns {
+            None => {
+                let list = List::new(items)
+                    .highlight_style(self.theme.selected_style())
+                    .highlight_symbol(self.theme.highlight_symbol())
+                    .scroll_padding(self.scrolloff as usize)
+                    .block(block);
+                StatefulWidget::render(list, area, buf, &mut self.list_state);
+            }
+            Some((rows, column_width)) => {
+                self.list_state.select(selected);
+                block.render(area, buf);
+                let in_view = (inner.width as usize / column_width).max(1);
+                // Scroll the columns just enough to show the selected one
+                let column = selected.unwrap_or_default() / rows;
+                self.first_column = self
+                    .first_column
+                    .min(column)
+                    .max((column + 1).saturating_sub(in_view));
+                for (index, column) in items
+                    .chunks(rows)
+                    .enumerate()
+                    .skip(self.first_column)
+                    .take(in_view)
+                {
+                    let column_area = Rect {
+                        x: inner.x + ((index - self.first_column) * column_width) as u16,
+                        width: (column_width - COLUMN_GAP) as u16,
+                        ..inner
+                    };
+                    let mut state = ListState::default()
+                        .with_selected(selected.filter(|v| v / rows == index).map(|v| v % rows));
+                    let list = List::new(column.to_vec())
+                        .highlight_style(self.theme.selected_style())
+                        .highlight_symbol(self.theme.highlight_symbol());
+                    StatefulWidget::render(list, column_area, buf, &mut state);
+                }
+            }
+        }
+        // - 2 for the borders
+        self.page_len = area.height.saturating_sub(2).max(1);
         if self.adding {
-            let popup_block = Block::bordered().title("Add New Sub");
+            let mut popup_block = Block::bordered().title("Add New Sub");
+            if let Some(checking) = self.checking.as_deref() {
+                popup_block = popup_block.title_bottom(
+                    Line::from(format!("Looking up r/{}…", checking)).fg(self.theme.muted()),
+                );
+            } else if let Some(add_error) = self.add_error.as_deref() {
+                popup_block = popup_block.title_bottom(Line::from(add_error).fg(Color::Red));
+            }
 
             let [center_vertical] = Layout::vertical([Constraint::Length(3)])
                 .flex(Flex::Center)
@@ -139,5 +653,57 @@ impl Component for SublistComponent {
             let x = self.sub_input.visual_cursor().max(scroll) - scroll + 1;
             frame.set_cursor_position((center.x + x as u16, center.y + 1));
         }
+        if let Some(confirm_popup) = self.confirm_popup.as_ref() {
+            confirm_popup.draw(frame, area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc::{self, Receiver};
+
+    use super::*;
+    use crate::reddit_api::MockRedditApi;
+
+    fn component(
+        reddit_api: Arc<MockRedditApi>,
+        subs: &[&str],
+    ) -> (SublistComponent, Receiver<AppEvent>) {
+        let config = Config {
+            subs: subs.iter().map(|v| v.to_string()).collect(),
+            ..Config::default()
+        };
+        let (sender, receiver) = mpsc::channel(100);
+        let component = SublistComponent::new(
+            reddit_api,
+            Arc::new(RwLock::new(StateStore::in_memory())),
+            &config,
+            sender,
+        );
+        (component, receiver)
+    }
+
+    #[test]
+    fn adding_a_listed_sub_in_another_casing_selects_it() {
+        let reddit_api = Arc::new(MockRedditApi::default());
+        let (mut component, _receiver) = component(reddit_api, &["rust", "news", "AskReddit"]);
+        component.add_sub("askreddit".to_string());
+        assert_eq!(component.subs(), ["rust", "news", "AskReddit"]);
+        assert_eq!(component.list_state.selected(), Some(2));
+        assert!(component.undo.is_empty());
+    }
+
+    #[tokio::test]
+    async fn about_pages_load_where_the_selection_stops_and_failures_are_retried() {
+        let reddit_api = Arc::new(MockRedditApi::default());
+        let (mut component, _receiver) = component(reddit_api.clone(), &["rust", "news"]);
+        component.load_selected_about();
+        tokio::task::yield_now().await;
+        // Passing over news in a hurry doesn't load it, rust failed and is asked again
+        component.handle_action(Action::Down).await.unwrap();
+        component.handle_action(Action::Up).await.unwrap();
+        tokio::time::sleep(ABOUT_DEBOUNCE * 2).await;
+        assert_eq!(reddit_api.requests(), ["r/rust/about", "r/rust/about"]);
     }
 }
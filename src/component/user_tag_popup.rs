@@ -0,0 +1,111 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Widget},
+};
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use crate::state_store::UserTag;
+
+pub enum UserTagPopupEvent {
+    None,
+    Cancel,
+    /// `None` removes the tag
+    Submit(Option<UserTag>),
+}
+
+/// Popup editing the local tag attached to a username
+pub struct UserTagPopup {
+    pub username: String,
+    input: Input,
+    color_index: usize,
+}
+
+impl UserTagPopup {
+    pub fn new(username: String, user_tag: Option<&UserTag>) -> Self {
+        let (input, color_index) = match user_tag {
+            Some(user_tag) => (
+                Input::new(user_tag.text.clone()),
+                UserTag::COLORS
+                    .iter()
+                    .position(|v| *v == user_tag.color)
+                    .unwrap_or_default(),
+            ),
+            None => (Input::default(), 0),
+        };
+        Self {
+            username,
+            input,
+            color_index,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> UserTagPopupEvent {
+        match event {
+            Event::Key(KeyEvent {
+                code: KeyCode::Esc,
+                kind: KeyEventKind::Press,
+                ..
+            }) => UserTagPopupEvent::Cancel,
+            Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                let text = self.input.value().trim();
+                UserTagPopupEvent::Submit((!text.is_empty()).then(|| UserTag {
+                    text: text.to_string(),
+                    color: UserTag::COLORS[self.color_index].to_string(),
+                }))
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Tab,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.color_index = (self.color_index + 1) % UserTag::COLORS.len();
+                UserTagPopupEvent::None
+            }
+            _ => {
+                self.input.handle_event(event);
+                UserTagPopupEvent::None
+            }
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        let [center_vertical] = Layout::vertical([Constraint::Length(3)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [center] = Layout::horizontal([Constraint::Percentage(75)])
+            .flex(Flex::Center)
+            .areas(center_vertical);
+        let buf = frame.buffer_mut();
+        Clear.render(center, buf);
+
+        let color = UserTag::COLORS[self.color_index];
+        let popup_block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(format!("Tag u/{}", self.username))
+            .title(
+                Line::from(vec![
+                    format!(" {} ", color)
+                        .fg(Color::Black)
+                        .bg(UserTag::parse_color(color)),
+                    " [Tab] color".into(),
+                ])
+                .right_aligned(),
+            )
+            .title_bottom("empty to remove");
+        Paragraph::new(self.input.value())
+            .block(popup_block)
+            .render(center, buf);
+
+        let scroll = self.input.visual_scroll(center.width.max(3) as usize - 3);
+        let x = self.input.visual_cursor().max(scroll) - scroll + 1;
+        frame.set_cursor_position((center.x + x as u16, center.y + 1));
+    }
+}
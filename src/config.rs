@@ -1,29 +1,228 @@
 use std::{
+    collections::HashMap,
     fs::{self, create_dir_all},
     path::PathBuf,
 };
 
+use ratatui_image::{FilterType, Resize};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+use crate::{keymap::KeyOverrides, ngored_error::NgoredError};
+
+#[derive(Serialize, Deserialize, Default)]
 pub struct Config {
     pub subs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<Account>,
+    /// Replies nested deeper than this are collapsed behind a "[+] continue" placeholder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_comment_depth: Option<usize>,
+    /// Only show top level comments of contest mode threads like reddit does
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub collapse_contest_replies: bool,
+    /// Show raw scores instead of `12.3k`/`1.2M`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub full_scores: bool,
+    /// Override the auto-detected image protocol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_protocol: Option<ImageProtocol>,
+    /// Start without downloading images, for metered or slow connections
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text_only: bool,
+    /// Coalesce redraws from background updates instead of redrawing on every change
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reduce_motion: bool,
+    /// Render the post detail as plain linear text for terminal screen readers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub screen_reader: bool,
+    /// Lines scrolled by `j`/`k` in the post detail, live threads and modmail, defaults to 1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scroll_step: Option<u16>,
+    /// Lines kept in view above and below the selected sub, post or comment, defaults to 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scrolloff: Option<u16>,
+    /// Indent wrapped lines of list items and quotes under their text, defaults to true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hanging_indent: Option<bool>,
+    /// Break words at their hyphens to fill lines, defaults to true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hyphenate: Option<bool>,
+    /// Widest the post detail gets in columns, it's centered on wider terminals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_content_width: Option<u16>,
+    /// Percent of the post's height images fit in, defaults to 50
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_height: Option<u16>,
+    /// Seconds each gallery image is shown during a slideshow, defaults to 5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slideshow_interval: Option<u64>,
+    /// Command links are opened with instead of the system's default, split into words like a
+    /// shell would. `{url}` is replaced with the link or it's appended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeName>,
+    /// How the selected item stands out, defaults to a background highlight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection_indicator: Option<SelectionIndicator>,
+    /// How images are fitted to the available space, defaults to `scale`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_resize: Option<ImageResize>,
+    /// Resampling filter used when resizing images, defaults to `nearest`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_filter: Option<ImageFilter>,
+    /// Dither images on sixel terminals to make up for their 256 colors, defaults to true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_dithering: Option<bool>,
+    /// Commands for links of some sites or file types, the first one matching opens the link
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub url_handlers: Vec<UrlHandler>,
+    /// Ask before removing a sub or quitting, defaults to true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<bool>,
+    /// Looks of some subs so they're told apart at a glance, keyed by the sub's name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sub_themes: HashMap<String, SubTheme>,
+    /// Format of a status line at the bottom, none is shown without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_line: Option<String>,
+    /// Shell commands run when things happen in the app
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    /// Keys bound on top of the defaults, under `global` or the screen they work in
+    #[serde(default, skip_serializing_if = "KeyOverrides::is_empty")]
+    pub keys: KeyOverrides,
 }
 
-impl Config {
-    pub fn new(subs: Vec<String>) -> Self {
-        Self { subs }
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocol {
+    Kitty,
+    Sixel,
+    Iterm2,
+    Halfblocks,
+    /// Don't load images at all
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    /// Selection and secondary text readable on any color scheme
+    HighContrast,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectionIndicator {
+    Background,
+    Bold,
+    /// A `>` marker in front of the selected item
+    Gutter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageResize {
+    /// Shrink to fit, never upscale
+    Fit,
+    /// Shrink or enlarge to fit
+    Scale,
+    /// Cut off what doesn't fit
+    Crop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImageFilter {
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
+/// Credentials of a reddit "script" app, needed for anything that acts as the
+/// logged in user (moderation, friends, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub username: String,
+    pub password: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// Opens the links matching any of its domains or extensions with its own command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlHandler {
+    /// Domains handled, their subdomains included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+    /// Extensions of the link's path handled, like `jpg` or `mp4`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+    /// Run like `browser_command`, `{url}` is replaced with the link or it's appended
+    pub command: String,
+}
+
+/// Tweaks to the theme for a single sub
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubTheme {
+    /// Color of the borders around the sub's posts and its name in the sublist, a name like
+    /// `red` or a `#rrggbb` hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<String>,
+}
+
+/// Shell commands run on events, with what happened passed in `NGORED_*` environment variables
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Hooks {
+    /// A post is opened, gets `NGORED_POST_ID`, `NGORED_SUBREDDIT`, `NGORED_TITLE`,
+    /// `NGORED_AUTHOR`, `NGORED_PERMALINK` and `NGORED_URL`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_opened: Option<String>,
+    /// An image finished downloading, gets `NGORED_MEDIA_URL` and `NGORED_MEDIA_BYTES`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_downloaded: Option<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        self.post_opened.is_none() && self.media_downloaded.is_none()
     }
-    pub fn load() -> Self {
+}
+
+impl UrlHandler {
+    pub fn matches(&self, url: &reqwest::Url) -> bool {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        let path = url.path().to_lowercase();
+        self.domains.iter().any(|domain| {
+            let domain = domain.to_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        }) || self
+            .extensions
+            .iter()
+            .any(|extension| path.ends_with(&format!(".{}", extension.to_lowercase())))
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self, NgoredError> {
         let path = Self::path();
-        let data = fs::read_to_string(path);
-        if let Ok(data) = data {
-            toml::from_str(&data).expect("Invalid config file")
-        } else {
-            Self {
-                subs: Vec::default(),
-            }
-        }
+        let Ok(data) = fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        toml::from_str(&data).map_err(|source| NgoredError::Config { path, source })
+    }
+    pub fn save_subs(subs: Vec<String>) {
+        // Don't overwrite a file that was broken by hand since startup
+        let Ok(mut config) = Self::load() else {
+            return;
+        };
+        config.subs = subs;
+        config.save();
     }
     pub fn save(&self) {
         let path = Self::path();
@@ -34,6 +233,24 @@ impl Config {
         fs::write(path, data).expect("Cannot save config");
     }
 
+    /// Smooth filters are left out when dithering is off, the colors they blend would be
+    /// dithered again
+    pub fn image_resize(&self) -> Resize {
+        let filter = self.image_filter.map(|v| match v {
+            _ if self.image_dithering == Some(false) => FilterType::Nearest,
+            ImageFilter::Nearest => FilterType::Nearest,
+            ImageFilter::Triangle => FilterType::Triangle,
+            ImageFilter::CatmullRom => FilterType::CatmullRom,
+            ImageFilter::Gaussian => FilterType::Gaussian,
+            ImageFilter::Lanczos3 => FilterType::Lanczos3,
+        });
+        match self.image_resize {
+            Some(ImageResize::Fit) => Resize::Fit(filter),
+            Some(ImageResize::Scale) | None => Resize::Scale(filter),
+            Some(ImageResize::Crop) => Resize::Crop(None),
+        }
+    }
+
     fn path() -> PathBuf {
         let home = dirs::home_dir().expect("Could not find home directory");
         home.join(".config").join("ngored").join("config.toml")
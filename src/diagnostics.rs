@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Counters shown by the diagnostics overlay, updated where the work happens
+static HTTP_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static PROTOCOL_HITS: AtomicU64 = AtomicU64::new(0);
+static PROTOCOL_MISSES: AtomicU64 = AtomicU64::new(0);
+static PROTOCOLS: AtomicUsize = AtomicUsize::new(0);
+static PROTOCOL_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a request as in flight until it's dropped, so cancelled requests are counted out too
+pub struct HttpRequestGuard;
+
+impl HttpRequestGuard {
+    pub fn new() -> Self {
+        HTTP_IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
+        Self
+    }
+}
+
+impl Drop for HttpRequestGuard {
+    fn drop(&mut self) {
+        HTTP_IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// An image drawn with the protocol it already had, or one that had to be rebuilt
+pub fn protocol_lookup(hit: bool) {
+    let counter = if hit {
+        &PROTOCOL_HITS
+    } else {
+        &PROTOCOL_MISSES
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// `bytes` is the size of the decoded image the protocol was built from
+pub fn protocol_created(bytes: usize) {
+    PROTOCOLS.fetch_add(1, Ordering::Relaxed);
+    PROTOCOL_BYTES.fetch_add(bytes, Ordering::Relaxed);
+}
+
+pub fn protocol_released(bytes: usize) {
+    PROTOCOLS.fetch_sub(1, Ordering::Relaxed);
+    PROTOCOL_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+pub struct Counters {
+    pub http_in_flight: usize,
+    /// `None` until an image has been drawn
+    pub protocol_hit_rate: Option<f64>,
+    pub protocols: usize,
+    pub protocol_bytes: usize,
+}
+
+pub fn counters() -> Counters {
+    let hits = PROTOCOL_HITS.load(Ordering::Relaxed);
+    let misses = PROTOCOL_MISSES.load(Ordering::Relaxed);
+    Counters {
+        http_in_flight: HTTP_IN_FLIGHT.load(Ordering::Relaxed),
+        protocol_hit_rate: (hits + misses > 0).then(|| hits as f64 / (hits + misses) as f64),
+        protocols: PROTOCOLS.load(Ordering::Relaxed),
+        protocol_bytes: PROTOCOL_BYTES.load(Ordering::Relaxed),
+    }
+}
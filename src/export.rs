@@ -0,0 +1,199 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::{
+    config::Config,
+    model::post::Post,
+    ngored_error::NgoredError,
+    reddit_api::{FixtureMode, HttpRedditApi, RedditApi},
+};
+
+/// Fetch the listing of `sub` the post list shows and write it as an Atom feed to `output`, or to
+/// stdout without one
+pub async fn export_rss(
+    sub: String,
+    output: Option<PathBuf>,
+    fixture_mode: Option<FixtureMode>,
+) -> Result<(), NgoredError> {
+    let posts = fetch_posts(&sub, None, fixture_mode).await?;
+    let feed = atom_feed(&sub, &posts);
+    match output {
+        Some(path) => fs::write(path, feed)?,
+        None => print!("{}", feed),
+    }
+    Ok(())
+}
+
+/// Print the posts of `sub`, sorted by `sort` or as the post list shows them, one JSON object a line
+pub async fn dump(
+    sub: String,
+    sort: Option<String>,
+    fixture_mode: Option<FixtureMode>,
+) -> Result<(), NgoredError> {
+    let posts = fetch_posts(&sub, sort.as_deref(), fixture_mode).await?;
+    let mut stdout = io::stdout().lock();
+    for post in posts {
+        let line = serde_json::to_string(&post).expect("posts are always serializable");
+        match writeln!(stdout, "{}", line) {
+            // The reader, like `head`, has all it wanted
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => break,
+            result => result?,
+        }
+    }
+    Ok(())
+}
+
+async fn fetch_posts(
+    sub: &str,
+    sort: Option<&str>,
+    fixture_mode: Option<FixtureMode>,
+) -> Result<Vec<Post>, NgoredError> {
+    let config = Config::load()?;
+    let reddit_api: Arc<dyn RedditApi> = Arc::new(HttpRedditApi::new(config.account, fixture_mode));
+    Ok(reddit_api
+        .get_posts(sub, sort)
+        .await
+        .map_err(|err| err.in_sub(sub))?
+        .into_posts()
+        .ok_or_else(|| NgoredError::NoSuchSub(sub.to_string()))?
+        .into_iter()
+        .map(Post::from)
+        .collect())
+}
+
+fn atom_feed(sub: &str, posts: &[Post]) -> String {
+    let sub_url = format!("https://www.reddit.com/r/{}/", sub);
+    // The feed changed when its newest post did, not when it was exported
+    let updated = posts.iter().map(updated_at).max().unwrap_or_else(Utc::now);
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str(&format!("  <id>{}</id>\n", escape(&sub_url)));
+    feed.push_str(&format!("  <title>r/{}</title>\n", escape(sub)));
+    feed.push_str(&format!("  <link href=\"{}\"/>\n", escape(&sub_url)));
+    feed.push_str(&format!("  <updated>{}</updated>\n", timestamp(updated)));
+    feed.push_str("  <generator>ngored</generator>\n");
+    for post in posts {
+        let permalink = escape(&post.permalink());
+        feed.push_str("  <entry>\n");
+        feed.push_str(&format!("    <id>{}</id>\n", permalink));
+        feed.push_str(&format!("    <title>{}</title>\n", escape(&post.title)));
+        feed.push_str(&format!("    <link href=\"{}\"/>\n", permalink));
+        if let Some(url) = post.external_url() {
+            feed.push_str(&format!(
+                "    <link rel=\"related\" href=\"{}\"/>\n",
+                escape(url)
+            ));
+        }
+        feed.push_str(&format!(
+            "    <author><name>u/{}</name></author>\n",
+            escape(&post.author)
+        ));
+        feed.push_str(&format!(
+            "    <published>{}</published>\n",
+            timestamp(post.created_at)
+        ));
+        feed.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            timestamp(updated_at(post))
+        ));
+        if !post.body.is_empty() {
+            feed.push_str(&format!(
+                "    <content type=\"text\">{}</content>\n",
+                escape(&post.body)
+            ));
+        }
+        feed.push_str("  </entry>\n");
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+fn updated_at(post: &Post) -> DateTime<Utc> {
+    post.edited_at.unwrap_or(post.created_at)
+}
+
+/// RFC 3339, as Atom dates have to be
+fn timestamp(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// `text` made safe for XML content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if is_xml_char(c) => escaped.push(c),
+            // Not even escaped, a parser rejects the whole feed over one
+            _ => {}
+        }
+    }
+    escaped
+}
+
+/// Characters XML 1.0 documents can hold: no control characters but tab and newlines, and no
+/// U+FFFE or U+FFFF
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | ' '..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_replaces_markup_characters() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn escape_drops_characters_xml_cannot_hold() {
+        assert_eq!(escape("a\u{0}b\u{1b}[31mc\u{fffe}\u{ffff}d"), "ab[31mcd");
+        assert_eq!(escape("tab\tline\r\nend 🦀"), "tab\tline\r\nend 🦀");
+    }
+
+    #[test]
+    fn atom_feed_has_an_entry_per_post() {
+        let created_at = DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z")
+            .unwrap()
+            .to_utc();
+        let post = Post {
+            id: "1cabcde".to_string(),
+            subreddit: "rust".to_string(),
+            author: "ferris".to_string(),
+            title: "Rust & C\u{8}".to_string(),
+            body: "1 < 2".to_string(),
+            url: "https://example.com/?a=1&b=2".to_string(),
+            created_at,
+            edited_at: Some(created_at + chrono::Duration::hours(1)),
+            ..Post::default()
+        };
+        let feed = atom_feed("rust", &[post]);
+        assert!(feed.contains("  <updated>2024-05-01T11:00:00Z</updated>\n"));
+        assert!(feed.contains(
+            "  <entry>\n    <id>https://www.reddit.com/r/rust/comments/1cabcde</id>\n    \
+             <title>Rust &amp; C</title>\n"
+        ));
+        assert!(
+            feed.contains(
+                "    <link rel=\"related\" href=\"https://example.com/?a=1&amp;b=2\"/>\n"
+            )
+        );
+        assert!(feed.contains("    <published>2024-05-01T10:00:00Z</published>\n"));
+        assert!(feed.contains("    <content type=\"text\">1 &lt; 2</content>\n"));
+        assert!(feed.ends_with("  </entry>\n</feed>\n"));
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+
+/// What relative dates, days and the status line's clock are shown against. Handed to the
+/// components by `App`, tests fix it so screens don't change with the date or the timezone
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Clock {
+    #[default]
+    System,
+    /// Always this time, in its offset's timezone
+    #[cfg_attr(not(test), allow(dead_code))]
+    Fixed(DateTime<FixedOffset>),
+}
+
+impl Clock {
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(now) => now.to_utc(),
+        }
+    }
+
+    /// The time on the user's wall clock
+    pub fn local_now(&self) -> DateTime<FixedOffset> {
+        match self {
+            Clock::System => Local::now().fixed_offset(),
+            Clock::Fixed(now) => *now,
+        }
+    }
+
+    /// Day `time` falls on in the user's timezone
+    pub fn day(&self, time: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Clock::System => time.with_timezone(&Local).date_naive(),
+            Clock::Fixed(now) => time.with_timezone(&now.timezone()).date_naive(),
+        }
+    }
+}
+
+/// Format a score as `12.3k`/`1.2M` so it doesn't blow out narrow layouts, `compact` is off when
+/// the config asks for `full_scores`
+pub fn score(score: i64, compact: bool) -> String {
+    if !compact || score.abs() < 1_000 {
+        return score.to_string();
+    }
+    // 999_950 would round up to "1000.0k"
+    let (value, suffix) = if score.abs() < 999_950 {
+        (score as f64 / 1_000.0, "k")
+    } else {
+        (score as f64 / 1_000_000.0, "M")
+    };
+    let value = format!("{:.1}", value);
+    format!("{}{}", value.trim_end_matches(".0"), suffix)
+}
+
+/// Format a video length as `1:05`, or `1:02:05` past an hour
+pub fn duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_compacts_thousands_and_millions() {
+        assert_eq!(score(999, true), "999");
+        assert_eq!(score(1_000, true), "1k");
+        assert_eq!(score(1_234, true), "1.2k");
+        assert_eq!(score(-12_345, true), "-12.3k");
+        assert_eq!(score(2_500_000, true), "2.5M");
+    }
+
+    #[test]
+    fn score_switches_to_millions_before_rounding_to_1000k() {
+        assert_eq!(score(999_949, true), "999.9k");
+        assert_eq!(score(999_950, true), "1M");
+        assert_eq!(score(-999_950, true), "-1M");
+    }
+
+    #[test]
+    fn score_is_left_alone_when_not_compact() {
+        assert_eq!(score(1_234_567, false), "1234567");
+    }
+}
@@ -0,0 +1,62 @@
+use std::process::Stdio;
+
+use log::debug;
+use tokio::process::Command;
+
+use crate::{config::Hooks, model::post::Post};
+
+/// Run the `post_opened` hook for `post`. Posts opened from a permalink only know their id and
+/// sub, the rest is empty
+pub fn post_opened(hooks: &Hooks, post: &Post) {
+    if let Some(command) = hooks.post_opened.as_deref() {
+        run(
+            "post_opened",
+            command,
+            &[
+                ("NGORED_POST_ID", &post.id),
+                ("NGORED_SUBREDDIT", &post.subreddit),
+                ("NGORED_TITLE", &post.title),
+                ("NGORED_AUTHOR", &post.author),
+                ("NGORED_PERMALINK", &post.permalink()),
+                ("NGORED_URL", &post.url),
+            ],
+        );
+    }
+}
+
+/// Run the `media_downloaded` hook for the `bytes` downloaded from `url`
+pub fn media_downloaded(hooks: &Hooks, url: &str, bytes: usize) {
+    if let Some(command) = hooks.media_downloaded.as_deref() {
+        run(
+            "media_downloaded",
+            command,
+            &[
+                ("NGORED_MEDIA_URL", url),
+                ("NGORED_MEDIA_BYTES", &bytes.to_string()),
+            ],
+        );
+    }
+}
+
+/// Run `command` with `sh` in the background, its output is discarded so it can't draw over the
+/// UI. A hook failing to start is only logged, it mustn't get in the way of reading
+fn run(event: &str, command: &str, env: &[(&str, &str)]) {
+    let spawned = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("NGORED_EVENT", event)
+        .envs(env.iter().copied())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        // Waited on so the hook doesn't linger as a zombie once it exits
+        Ok(mut child) => {
+            tokio::spawn(async move {
+                let _ = child.wait().await;
+            });
+        }
+        Err(err) => debug!("Cannot run the {} hook: {}", event, err),
+    }
+}
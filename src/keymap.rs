@@ -0,0 +1,696 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// What a key does, components react to actions instead of interpreting keys themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    /// Show the keys of the current screen
+    Help,
+    #[cfg(debug_assertions)]
+    ToggleDebug,
+    ToggleDiagnostics,
+    /// Prompt for any sub to open, from any screen
+    JumpToSub,
+    Back,
+    Cancel,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    /// First item, or the top of a scrolling view
+    Top,
+    /// Last item, or the bottom of a scrolling view
+    Bottom,
+    /// Open the selected item
+    Open,
+    /// Same row of the next column of a sublist laid out in columns, opens the sub when it's
+    /// a single column
+    NextColumn,
+    PreviousColumn,
+    OpenInBrowser,
+    OpenOnOldReddit,
+    /// Menu of the ways to open or copy a post
+    Share,
+    Refresh,
+    TagUser,
+    AddSub,
+    RemoveSub,
+    /// Take back the last edit of the sublist
+    Undo,
+    OpenFriends,
+    OpenModQueue,
+    OpenModmail,
+    ToggleGroupByDay,
+    /// Show or hide the strip with the sub's title and icon above the posts
+    ToggleSubHeader,
+    /// Switch back to the sub opened before the current one
+    PreviousSub,
+    /// Show or hide the selected post's text under the posts
+    TogglePeek,
+    /// Mark or unmark the selected post for a batch action
+    ToggleMark,
+    /// Mark every post the selection moves over, until pressed again
+    VisualSelect,
+    /// Record the posts as visited, like opening them would
+    MarkRead,
+    /// Hide the posts from the account's listings
+    Hide,
+    /// Put the posts at the end of the read later queue
+    ReadLater,
+    /// Open the oldest post of the read later queue, taking it out of the queue
+    NextReadLater,
+    OpenImageExternally,
+    ToggleTextOnly,
+    ShowDuplicates,
+    Search,
+    NextSearchMatch,
+    PreviousSearchMatch,
+    ShowFullThread,
+    /// Hide or show the replies of the selected comment
+    ToggleCollapse,
+    NextSameAuthorComment,
+    NextComment,
+    PreviousComment,
+    /// Focus the next link of the post or its comments
+    NextLink,
+    PreviousLink,
+    /// Label the links in view to open one by typing its label
+    LinkHints,
+    /// Label the links in view to copy one by typing its label
+    CopyLinkHints,
+    ToggleFriend,
+    Approve,
+    Remove,
+    Spam,
+    ToggleLock,
+    OpenLiveThread,
+    OpenCrosspostParent,
+    PlayVideo,
+    NextImage,
+    PreviousImage,
+    /// Tile every image of the gallery to pick one
+    GalleryGrid,
+    /// Turn the image in view a quarter clockwise
+    RotateImage,
+    /// Switch images between fitting half the view's height and filling its width
+    ToggleImageFit,
+    /// Give images more of the view's height
+    GrowImage,
+    ShrinkImage,
+    /// Undo rotations, the fit and the height
+    ResetImage,
+    /// Start or stop advancing through the gallery on a timer
+    ToggleSlideshow,
+    PauseSlideshow,
+    /// Show or hide the spoilers of the selected comment, or of the post
+    RevealSpoilers,
+    NextTab,
+    PreviousTab,
+    CopyComment,
+    CopyTitle,
+    CopyPermalink,
+    /// The outbound link of a link post, the permalink of a self post
+    CopyLink,
+    CopyMarkdown,
+}
+
+/// Where a key is pressed, the same key can mean different actions in different screens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyContext {
+    #[cfg(debug_assertions)]
+    Debug,
+    Sublist,
+    Postlist,
+    PostDetail,
+    Duplicates,
+    Share,
+    /// Menu of the batch actions on the marked posts
+    Batch,
+    GalleryGrid,
+    LiveThread,
+    ModQueue,
+    Modmail,
+    ModmailConversation,
+}
+
+impl Action {
+    /// The action's name as words, like `Toggle group by day`
+    pub fn label(&self) -> String {
+        words(&format!("{:?}", self))
+    }
+}
+
+impl KeyContext {
+    /// The screens a user gets to, in the order the help lists them
+    pub const SCREENS: [KeyContext; 11] = [
+        KeyContext::Sublist,
+        KeyContext::Postlist,
+        KeyContext::PostDetail,
+        KeyContext::Duplicates,
+        KeyContext::Share,
+        KeyContext::Batch,
+        KeyContext::GalleryGrid,
+        KeyContext::LiveThread,
+        KeyContext::ModQueue,
+        KeyContext::Modmail,
+        KeyContext::ModmailConversation,
+    ];
+
+    pub fn label(&self) -> String {
+        words(&format!("{:?}", self))
+    }
+}
+
+/// A key of the `[keys]` config, written like `J`, `ctrl-d`, `space` or `f5`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key {
+    code: KeyCode,
+    ctrl: bool,
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let (name, ctrl) = match value.strip_prefix("ctrl-") {
+            Some(name) => (name, true),
+            None => (value.as_str(), false),
+        };
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "shift-tab" => KeyCode::BackTab,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|v| v.parse().ok()) {
+                    Some(number @ 1..=12) => KeyCode::F(number),
+                    _ => return Err(format!("Unknown key {:?}", value)),
+                },
+            },
+        };
+        // Control only goes with characters, like the bindings that come with the app
+        if ctrl && !matches!(code, KeyCode::Char(_)) {
+            return Err(format!("Unknown key {:?}", value));
+        }
+        Ok(Key { code, ctrl })
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        let name = match key.code {
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::BackTab => "shift-tab".to_string(),
+            KeyCode::F(number) => format!("f{}", number),
+            code => code.to_string().to_lowercase().replace(' ', ""),
+        };
+        if key.ctrl {
+            format!("ctrl-{}", name)
+        } else {
+            name
+        }
+    }
+}
+
+/// Keys bound on top of the ones that come with the app, for every screen or for one. They
+/// shadow the app's bindings of the same key in the same place
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyOverrides {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub global: HashMap<Key, Action>,
+    #[serde(flatten)]
+    pub screens: HashMap<KeyContext, HashMap<Key, Action>>,
+}
+
+impl KeyOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.screens.values().all(HashMap::is_empty)
+    }
+
+    fn screen(&self, context: KeyContext, code: KeyCode, ctrl: bool) -> Option<Action> {
+        self.screens
+            .get(&context)?
+            .get(&Key { code, ctrl })
+            .copied()
+    }
+
+    fn global(&self, code: KeyCode, ctrl: bool) -> Option<Action> {
+        self.global.get(&Key { code, ctrl }).copied()
+    }
+
+    fn keys(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.global
+            .keys()
+            .chain(self.screens.values().flat_map(HashMap::keys))
+            .map(|v| v.code)
+    }
+}
+
+/// `CamelCase` as `Camel case`
+fn words(name: &str) -> String {
+    let mut words = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            words.push(' ');
+            words.push(c.to_ascii_lowercase());
+        } else {
+            words.push(c);
+        }
+    }
+    words
+}
+
+/// Keys worth looking up, every binding is made of them
+fn listed_keys() -> Vec<KeyCode> {
+    let mut keys = (' '..='~').map(KeyCode::Char).collect::<Vec<_>>();
+    keys.extend([
+        KeyCode::Enter,
+        KeyCode::Esc,
+        KeyCode::Tab,
+        KeyCode::BackTab,
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::Up,
+        KeyCode::Down,
+        KeyCode::Home,
+        KeyCode::End,
+        KeyCode::PageUp,
+        KeyCode::PageDown,
+    ]);
+    keys.extend((1..=12).map(KeyCode::F));
+    keys
+}
+
+fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::BackTab => "Shift-Tab".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        code => code.to_string(),
+    }
+}
+
+/// Keeps the first key of a sequence like `yc` until the next key press completes it
+#[derive(Debug, Default)]
+pub struct Keymap {
+    pending: Option<(KeyContext, KeyCode)>,
+    overrides: KeyOverrides,
+}
+
+impl Keymap {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            pending: None,
+            overrides: config.keys.clone(),
+        }
+    }
+
+    /// What each key does in `context`, looked up in the same tables key presses are: the
+    /// screen's own bindings, then the global ones it doesn't shadow. Each list has the keys of
+    /// an action, in the order actions are declared
+    pub fn bindings(&self, context: KeyContext) -> [Vec<(Action, Vec<String>)>; 2] {
+        let mut screen = Vec::new();
+        let mut global = Vec::new();
+        let mut keys = listed_keys();
+        keys.extend(
+            self.overrides
+                .keys()
+                .filter(|v| !keys.contains(v))
+                .collect::<Vec<_>>(),
+        );
+        for &code in &keys {
+            if self.is_prefix(context, code) {
+                for &next in &keys {
+                    if let Some(action) = sequence_action(context, code, next) {
+                        screen.push((action, format!("{}{}", key_label(code), key_label(next))));
+                    }
+                }
+            } else if let Some(action) = self.screen_action(context, code, false) {
+                screen.push((action, key_label(code)));
+            } else if let Some(action) = self.global_action(code, false) {
+                global.push((action, key_label(code)));
+            }
+            if let KeyCode::Char(c) = code {
+                if let Some(action) = self.screen_action(context, code, true) {
+                    screen.push((action, format!("Ctrl-{}", c)));
+                } else if let Some(action) = self.global_action(code, true) {
+                    global.push((action, format!("Ctrl-{}", c)));
+                }
+            }
+        }
+        [screen, global].map(|bindings| {
+            let mut grouped: Vec<(Action, Vec<String>)> = Vec::new();
+            for (action, key) in bindings {
+                match grouped.iter_mut().find(|(v, _)| *v == action) {
+                    Some((_, keys)) => keys.push(key),
+                    None => grouped.push((action, vec![key])),
+                }
+            }
+            grouped.sort_by_key(|(action, _)| *action);
+            grouped
+        })
+    }
+
+    /// The first key of each action in `context`, for the hints that say which key to press
+    pub fn hint_keys(&self, context: KeyContext) -> HashMap<Action, String> {
+        // The screen's keys go in last, so they win over a global binding of the same action
+        self.bindings(context)
+            .into_iter()
+            .rev()
+            .flatten()
+            .filter_map(|(action, keys)| Some((action, keys.into_iter().next()?)))
+            .collect()
+    }
+
+    /// Translate a key press into the action it's bound to. A key that starts a sequence returns
+    /// no action, and a key that doesn't complete the pending one cancels it
+    pub fn action(&mut self, context: KeyContext, key: &KeyEvent) -> Option<Action> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        if let Some((pending_context, prefix)) = self.pending.take() {
+            return if pending_context == context {
+                sequence_action(context, prefix, key.code)
+            } else {
+                None
+            };
+        }
+        // Control keys are bound on their own, ctrl-d mustn't fall through to `d`
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if !ctrl && self.is_prefix(context, key.code) {
+            self.pending = Some((context, key.code));
+            return None;
+        }
+        // Bindings of the context come before the global ones
+        self.screen_action(context, key.code, ctrl)
+            .or_else(|| self.global_action(key.code, ctrl))
+    }
+
+    /// A key bound in the config doesn't start the app's sequences anymore
+    fn is_prefix(&self, context: KeyContext, code: KeyCode) -> bool {
+        self.overrides.screen(context, code, false).is_none() && is_prefix(context, code)
+    }
+
+    fn screen_action(&self, context: KeyContext, code: KeyCode, ctrl: bool) -> Option<Action> {
+        self.overrides.screen(context, code, ctrl).or_else(|| {
+            if ctrl {
+                control_action(context, code)
+            } else {
+                context_action(context, code)
+            }
+        })
+    }
+
+    fn global_action(&self, code: KeyCode, ctrl: bool) -> Option<Action> {
+        self.overrides
+            .global(code, ctrl)
+            .or_else(|| if ctrl { None } else { global_action(code) })
+    }
+}
+
+fn is_prefix(context: KeyContext, code: KeyCode) -> bool {
+    matches!(
+        (context, code),
+        (
+            KeyContext::Postlist | KeyContext::PostDetail,
+            KeyCode::Char('y')
+        )
+    )
+}
+
+fn sequence_action(context: KeyContext, prefix: KeyCode, code: KeyCode) -> Option<Action> {
+    use KeyCode::Char;
+
+    match (context, prefix, code) {
+        (KeyContext::Postlist | KeyContext::PostDetail, Char('y'), Char('t')) => {
+            Some(Action::CopyTitle)
+        }
+        (KeyContext::Postlist | KeyContext::PostDetail, Char('y'), Char('p')) => {
+            Some(Action::CopyPermalink)
+        }
+        (KeyContext::Postlist | KeyContext::PostDetail, Char('y'), Char('u')) => {
+            Some(Action::CopyLink)
+        }
+        (KeyContext::PostDetail, Char('y'), Char('c')) => Some(Action::CopyComment),
+        (KeyContext::PostDetail, Char('y'), Char('f')) => Some(Action::CopyLinkHints),
+        _ => None,
+    }
+}
+
+fn global_action(code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Char('q') => Some(Action::Quit),
+        #[cfg(debug_assertions)]
+        KeyCode::Char('`') => Some(Action::ToggleDebug),
+        KeyCode::F(12) => Some(Action::ToggleDiagnostics),
+        KeyCode::Char(':') => Some(Action::JumpToSub),
+        KeyCode::Char('?') => Some(Action::Help),
+        _ => None,
+    }
+}
+
+fn control_action(context: KeyContext, code: KeyCode) -> Option<Action> {
+    use KeyCode::Char;
+
+    let scrolls = matches!(
+        context,
+        KeyContext::PostDetail | KeyContext::LiveThread | KeyContext::ModmailConversation
+    );
+    match code {
+        Char('d') if scrolls => Some(Action::HalfPageDown),
+        Char('u') if scrolls => Some(Action::HalfPageUp),
+        // Like vim's alternate file, terminals send ctrl-^ as ctrl-6 too
+        Char('^' | '6') if context == KeyContext::Postlist => Some(Action::PreviousSub),
+        _ => None,
+    }
+}
+
+fn context_action(context: KeyContext, code: KeyCode) -> Option<Action> {
+    use Action::*;
+    use KeyCode::Char;
+
+    let action = match (context, code) {
+        (_, Char('j') | KeyCode::Down) => Down,
+        (_, Char('k') | KeyCode::Up) => Up,
+        (_, KeyCode::PageDown) => PageDown,
+        (_, KeyCode::PageUp) => PageUp,
+        (_, KeyCode::Home) => Top,
+        (_, KeyCode::End) => Bottom,
+
+        #[cfg(debug_assertions)]
+        (KeyContext::Debug, KeyCode::Esc) => Cancel,
+
+        (KeyContext::Sublist, Char('l') | KeyCode::Right) => NextColumn,
+        (KeyContext::Sublist, Char('h') | KeyCode::Left) => PreviousColumn,
+        (KeyContext::Sublist, KeyCode::Enter) => Open,
+        (KeyContext::Sublist, Char('a')) => AddSub,
+        (KeyContext::Sublist, Char('d')) => RemoveSub,
+        (KeyContext::Sublist, Char('u')) => Undo,
+        (KeyContext::Sublist, Char('f')) => OpenFriends,
+        (KeyContext::Sublist, Char('m')) => OpenModQueue,
+        (KeyContext::Sublist, Char('M')) => OpenModmail,
+        (KeyContext::Sublist, Char('n')) => NextReadLater,
+
+        (KeyContext::Postlist, Char('h') | KeyCode::Left) => Back,
+        (KeyContext::Postlist, Char('l') | KeyCode::Right | KeyCode::Enter) => Open,
+        (KeyContext::Postlist, Char('r')) => Refresh,
+        (KeyContext::Postlist, Char('g')) => ToggleGroupByDay,
+        (KeyContext::Postlist, Char('b')) => ToggleSubHeader,
+        (KeyContext::Postlist, Char('t')) => TagUser,
+        (KeyContext::Postlist, Char('o')) => Share,
+        (KeyContext::Postlist, Char('p')) => TogglePeek,
+        (KeyContext::Postlist, Char(' ')) => ToggleMark,
+        (KeyContext::Postlist, Char('v')) => VisualSelect,
+        (KeyContext::Postlist, Char('a')) => ReadLater,
+        (KeyContext::Postlist, Char('n')) => NextReadLater,
+        (KeyContext::Postlist, KeyCode::Esc) => Cancel,
+
+        (KeyContext::PostDetail, Char('h') | KeyCode::Left) => Back,
+        (KeyContext::PostDetail, KeyCode::Esc) => Cancel,
+        (KeyContext::PostDetail, Char('J')) => PageDown,
+        (KeyContext::PostDetail, Char('K')) => PageUp,
+        (KeyContext::PostDetail, Char('o')) => Share,
+        (KeyContext::PostDetail, Char('O')) => OpenImageExternally,
+        (KeyContext::PostDetail, Char('r')) => Refresh,
+        (KeyContext::PostDetail, Char('t')) => TagUser,
+        (KeyContext::PostDetail, Char('i')) => ToggleTextOnly,
+        (KeyContext::PostDetail, Char('d')) => ShowDuplicates,
+        (KeyContext::PostDetail, Char('/')) => Search,
+        (KeyContext::PostDetail, Char('n')) => NextSearchMatch,
+        (KeyContext::PostDetail, Char('N')) => PreviousSearchMatch,
+        (KeyContext::PostDetail, Char('T')) => ShowFullThread,
+        (KeyContext::PostDetail, Char('c')) => ToggleCollapse,
+        (KeyContext::PostDetail, Char('a')) => NextSameAuthorComment,
+        (KeyContext::PostDetail, Char('}')) => NextComment,
+        (KeyContext::PostDetail, Char('{')) => PreviousComment,
+        // Opens the focused link, or else expands the selected comment
+        (KeyContext::PostDetail, KeyCode::Enter) => Open,
+        (KeyContext::PostDetail, KeyCode::Tab) => NextLink,
+        (KeyContext::PostDetail, KeyCode::BackTab) => PreviousLink,
+        (KeyContext::PostDetail, Char('f')) => LinkHints,
+        (KeyContext::PostDetail, Char('F')) => ToggleFriend,
+        (KeyContext::PostDetail, Char('A')) => Approve,
+        (KeyContext::PostDetail, Char('R')) => Remove,
+        (KeyContext::PostDetail, Char('L')) => ToggleLock,
+        (KeyContext::PostDetail, Char('l')) => OpenLiveThread,
+        (KeyContext::PostDetail, Char('p')) => OpenCrosspostParent,
+        (KeyContext::PostDetail, Char('v')) => PlayVideo,
+        (KeyContext::PostDetail, Char(']')) => NextImage,
+        (KeyContext::PostDetail, Char('[')) => PreviousImage,
+        (KeyContext::PostDetail, Char('g')) => GalleryGrid,
+        (KeyContext::PostDetail, Char('z')) => RotateImage,
+        (KeyContext::PostDetail, Char('w')) => ToggleImageFit,
+        (KeyContext::PostDetail, Char('=')) => ResetImage,
+        (KeyContext::PostDetail, Char('+')) => GrowImage,
+        (KeyContext::PostDetail, Char('-')) => ShrinkImage,
+        (KeyContext::PostDetail, Char('s')) => ToggleSlideshow,
+        (KeyContext::PostDetail, Char(' ')) => PauseSlideshow,
+        (KeyContext::PostDetail, Char('x')) => RevealSpoilers,
+
+        (KeyContext::Duplicates, KeyCode::Esc | Char('d') | Char('h') | KeyCode::Left) => Back,
+        (KeyContext::Duplicates, Char('l') | KeyCode::Right | KeyCode::Enter) => Open,
+
+        (KeyContext::Share, KeyCode::Esc | Char('h') | KeyCode::Left) => Back,
+        (KeyContext::Share, Char('l') | KeyCode::Right | KeyCode::Enter) => Open,
+        (KeyContext::Share, Char('o')) => OpenInBrowser,
+        (KeyContext::Share, Char('O')) => OpenOnOldReddit,
+        (KeyContext::Share, Char('p')) => CopyPermalink,
+        (KeyContext::Share, Char('m')) => CopyMarkdown,
+        (KeyContext::Share, Char('u')) => CopyLink,
+
+        (KeyContext::Batch, KeyCode::Esc | Char('h') | KeyCode::Left) => Back,
+        (KeyContext::Batch, Char('l') | KeyCode::Right | KeyCode::Enter) => Open,
+        (KeyContext::Batch, Char('o')) => OpenInBrowser,
+        (KeyContext::Batch, Char('r')) => MarkRead,
+        (KeyContext::Batch, Char('x')) => Hide,
+        (KeyContext::Batch, Char('a')) => ReadLater,
+
+        (KeyContext::GalleryGrid, KeyCode::Esc | Char('g')) => Back,
+        (KeyContext::GalleryGrid, Char('h') | KeyCode::Left) => PreviousImage,
+        (KeyContext::GalleryGrid, Char('l') | KeyCode::Right) => NextImage,
+        (KeyContext::GalleryGrid, KeyCode::Enter) => Open,
+
+        (KeyContext::LiveThread, Char('h') | KeyCode::Left) => Back,
+        (KeyContext::LiveThread, Char('J')) => PageDown,
+        (KeyContext::LiveThread, Char('K')) => PageUp,
+        (KeyContext::LiveThread, Char('o')) => OpenInBrowser,
+
+        (KeyContext::ModQueue, Char('h') | KeyCode::Left) => Back,
+        (KeyContext::ModQueue, Char('l') | KeyCode::Right | KeyCode::Enter) => Open,
+        (KeyContext::ModQueue, Char('o')) => OpenInBrowser,
+        (KeyContext::ModQueue, KeyCode::Tab | Char(']')) => NextTab,
+        (KeyContext::ModQueue, KeyCode::BackTab | Char('[')) => PreviousTab,
+        (KeyContext::ModQueue, Char('a')) => Approve,
+        (KeyContext::ModQueue, Char('r')) => Remove,
+        (KeyContext::ModQueue, Char('s')) => Spam,
+
+        (KeyContext::Modmail, Char('h') | KeyCode::Left) => Back,
+        (KeyContext::Modmail, Char('l') | KeyCode::Right | KeyCode::Enter) => Open,
+        (KeyContext::Modmail, Char('o')) => OpenInBrowser,
+
+        (KeyContext::ModmailConversation, Char('h') | KeyCode::Left) => Back,
+        (KeyContext::ModmailConversation, Char('J')) => PageDown,
+        (KeyContext::ModmailConversation, Char('K')) => PageUp,
+
+        _ => return None,
+    };
+    Some(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(toml: &str) -> Keymap {
+        Keymap::new(&toml::from_str(&format!("subs = []\n{}", toml)).unwrap())
+    }
+
+    fn press(keymap: &mut Keymap, context: KeyContext, code: KeyCode) -> Option<Action> {
+        keymap.action(context, &KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn keys_are_read_like_they_are_written() {
+        for name in ["J", "ctrl-d", "space", "shift-tab", "pageup", "esc", "f5"] {
+            let key = Key::try_from(name.to_string()).unwrap();
+            assert_eq!(String::from(key), name);
+        }
+        assert!(Key::try_from("ctrl-enter".to_string()).is_err());
+        assert!(Key::try_from("f13".to_string()).is_err());
+    }
+
+    #[test]
+    fn config_keys_shadow_the_defaults() {
+        let mut keymap = keymap(
+            "[keys.global]\nQ = \"quit\"\n[keys.post_detail]\ny = \"next_comment\"\nq = \"back\"",
+        );
+        let context = KeyContext::PostDetail;
+        assert_eq!(
+            press(&mut keymap, context, KeyCode::Char('Q')),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            press(&mut keymap, context, KeyCode::Char('q')),
+            Some(Action::Back)
+        );
+        assert_eq!(
+            press(&mut keymap, KeyContext::Sublist, KeyCode::Char('q')),
+            Some(Action::Quit)
+        );
+        // `y` doesn't wait for the rest of `yc` anymore
+        assert_eq!(
+            press(&mut keymap, context, KeyCode::Char('y')),
+            Some(Action::NextComment)
+        );
+        assert_eq!(
+            press(&mut keymap, KeyContext::Postlist, KeyCode::Char('y')),
+            None
+        );
+    }
+
+    #[test]
+    fn bindings_list_config_keys() {
+        let keymap = keymap("[keys.sublist]\n\"ctrl-r\" = \"refresh\"");
+        let [screen, _] = keymap.bindings(KeyContext::Sublist);
+        assert!(screen.contains(&(Action::Refresh, vec!["Ctrl-r".to_string()])));
+    }
+
+    #[test]
+    fn hints_name_rebound_keys() {
+        let keymap = keymap(
+            "[keys.post_detail]
+v = \"next_comment\"
+P = \"play_video\"",
+        );
+        let hint_keys = keymap.hint_keys(KeyContext::PostDetail);
+        assert_eq!(hint_keys[&Action::PlayVideo], "P");
+        assert_eq!(hint_keys[&Action::ShowFullThread], "T");
+        assert_eq!(hint_keys[&Action::Quit], "q");
+    }
+}
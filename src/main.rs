@@ -1,15 +1,29 @@
-use crate::{app::App, ngored_error::NgoredError};
+use std::path::PathBuf;
+
+use crate::{app::App, ngored_error::NgoredError, reddit_api::FixtureMode};
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
 static ALLOC: dhat::Alloc = dhat::Alloc;
 
 mod app;
+mod clipboard;
 mod component;
 mod config;
+mod diagnostics;
+mod export;
+mod format;
+mod hooks;
+mod keymap;
 mod model;
 mod ngored_error;
+mod opener;
 mod reddit_api;
+#[cfg(test)]
+mod snapshot;
+mod state_store;
+mod text;
+mod theme;
 mod widget;
 
 #[tokio::main]
@@ -24,8 +38,43 @@ async fn main() -> Result<(), NgoredError> {
         debug!("App started")
     }
 
+    // --record saves reddit responses, --replay serves them back without network
+    let fixture_mode = std::env::args().find_map(|v| match v.as_str() {
+        "--record" => Some(FixtureMode::Record),
+        "--replay" => Some(FixtureMode::Replay),
+        _ => None,
+    });
+
+    // export-rss <sub> [-o <file>] writes the sub's listing as an Atom feed instead of starting the UI
+    let mut args = std::env::args().skip_while(|v| v != "export-rss");
+    if args.next().is_some() {
+        let sub = args
+            .next()
+            .filter(|v| !v.starts_with('-'))
+            .ok_or(NgoredError::Usage("ngored export-rss <sub> [-o <file>]"))?;
+        let output = args
+            .skip_while(|v| v != "-o" && v != "--output")
+            .nth(1)
+            .map(PathBuf::from);
+        return export::export_rss(sub, output, fixture_mode).await;
+    }
+
+    // dump <sub> [--sort <sort>] prints the sub's posts as JSON lines instead of starting the UI
+    let mut args = std::env::args().skip_while(|v| v != "dump");
+    if args.next().is_some() {
+        let sub = args
+            .next()
+            .filter(|v| !v.starts_with('-'))
+            .ok_or(NgoredError::Usage("ngored dump <sub> [--sort <sort>]"))?;
+        let sort = args.skip_while(|v| v != "--sort").nth(1);
+        return export::dump(sub, sort, fixture_mode).await;
+    }
+
     let mut terminal = ratatui::init();
-    let app_result = App::new().run(&mut terminal).await;
+    let app_result = match App::new(fixture_mode) {
+        Ok(mut app) => app.run(&mut terminal).await,
+        Err(err) => Err(err),
+    };
 
     ratatui::restore();
 
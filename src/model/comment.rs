@@ -1,29 +1,60 @@
+use std::collections::{HashMap, HashSet};
+
 use chrono::{DateTime, Utc};
 
-use crate::reddit_api::CommentData;
+use crate::{
+    format,
+    reddit_api::{CommentData, EmoteData},
+};
 
 #[derive(Clone)]
 pub struct Comment {
+    pub id: String,
     pub body: String,
     pub author: String,
     pub score: i64,
+    /// Score is hidden by the sub for new comments, `score` is meaningless until revealed
+    pub score_hidden: bool,
     pub replies: Vec<Comment>,
     pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub locked: bool,
+    pub approved: bool,
+    pub removed: bool,
+}
+
+/// A row of a flattened comment tree
+pub enum CommentRow<'a> {
+    Comment(usize, &'a Comment),
+    /// Replies of `parent_id` hidden by the depth limit, or because it's `collapsed`
+    Continue {
+        depth: usize,
+        parent_id: String,
+        count: usize,
+        collapsed: bool,
+    },
 }
 
 impl From<CommentData> for Comment {
     fn from(value: CommentData) -> Self {
         Self {
-            body: value.body,
+            id: value.id,
+            body: replace_emotes(&value.body, &value.media_metadata),
             author: value.author,
             score: value.score,
+            score_hidden: value.score_hidden,
             created_at: DateTime::<Utc>::from_timestamp_secs(value.created_utc as i64).unwrap(),
+            edited_at: value
+                .edited
+                .and_then(|v| DateTime::<Utc>::from_timestamp_secs(v as i64)),
+            locked: value.locked,
+            approved: value.approved,
+            removed: value.removed,
             replies: value.replies.map_or(Vec::new(), |replies| {
                 replies
-                    .as_listing()
-                    .children
+                    .into_children()
                     .into_iter()
-                    .filter_map(|comment_data| comment_data.as_comment_opt().map(|v| v.into()))
+                    .filter_map(|comment_data| comment_data.into_comment().map(|v| v.into()))
                     .collect()
             }),
         }
@@ -31,18 +62,134 @@ impl From<CommentData> for Comment {
 }
 
 impl Comment {
-    /// Flatten this comment tree into (depth, Comment)
-    pub fn flatten(&self, depth: usize) -> Vec<(usize, Comment)> {
+    pub fn score_text(&self, compact: bool) -> String {
+        if self.score_hidden {
+            "•".to_string()
+        } else {
+            format::score(self.score, compact)
+        }
+    }
+
+    pub fn fullname(&self) -> String {
+        format!("t1_{}", self.id)
+    }
+
+    /// Find a comment by id anywhere in the given comment trees
+    pub fn find_mut<'a>(comments: &'a mut [Comment], id: &str) -> Option<&'a mut Comment> {
+        for comment in comments {
+            if comment.id == id {
+                return Some(comment);
+            }
+            if let Some(reply) = Self::find_mut(&mut comment.replies, id) {
+                return Some(reply);
+            }
+        }
+        None
+    }
+
+    /// Case-insensitive match against the body or author, `query` must already be lowercased
+    pub fn matches(&self, query: &str) -> bool {
+        self.body.to_lowercase().contains(query) || self.author.to_lowercase().contains(query)
+    }
+
+    /// Number of comments in this tree, including itself
+    pub fn tree_size(&self) -> usize {
+        1 + self.replies.iter().map(|v| v.tree_size()).sum::<usize>()
+    }
+
+    /// Flatten this comment tree into rows, replies more than `max_depth` levels below the last
+    /// expanded comment, and those of `collapsed` comments, are replaced by a
+    /// `CommentRow::Continue`
+    pub fn flatten(
+        &self,
+        max_depth: Option<usize>,
+        expanded: &HashSet<String>,
+        collapsed: &HashSet<String>,
+    ) -> Vec<CommentRow<'_>> {
         let mut out = Vec::new();
+        self.flatten_into(0, max_depth, max_depth, expanded, collapsed, &mut out);
+        out
+    }
 
+    fn flatten_into<'a>(
+        &'a self,
+        depth: usize,
+        remaining_depth: Option<usize>,
+        max_depth: Option<usize>,
+        expanded: &HashSet<String>,
+        collapsed: &HashSet<String>,
+        out: &mut Vec<CommentRow<'a>>,
+    ) {
         // push self
-        out.push((depth, self.clone()));
+        out.push(CommentRow::Comment(depth, self));
+        if self.replies.is_empty() {
+            return;
+        }
+        if collapsed.contains(&self.id) {
+            out.push(CommentRow::Continue {
+                depth: depth + 1,
+                parent_id: self.id.clone(),
+                count: self.tree_size() - 1,
+                collapsed: true,
+            });
+            return;
+        }
+
+        let remaining_depth = match remaining_depth {
+            Some(0) if expanded.contains(&self.id) => max_depth,
+            Some(0) => {
+                out.push(CommentRow::Continue {
+                    depth: depth + 1,
+                    parent_id: self.id.clone(),
+                    count: self.tree_size() - 1,
+                    collapsed: false,
+                });
+                return;
+            }
+            remaining_depth => remaining_depth,
+        };
 
         // recursively flatten replies
         for reply in &self.replies {
-            out.extend(reply.flatten(depth + 1));
+            reply.flatten_into(
+                depth + 1,
+                remaining_depth.map(|v| v.saturating_sub(1)),
+                max_depth,
+                expanded,
+                collapsed,
+                out,
+            );
         }
+    }
+}
 
-        out
+/// Replace emote markup (`![img](emote|t5_xxx|123)` or `:emote|t5_xxx|123:`) with a readable
+/// `:name:`
+fn replace_emotes(body: &str, emotes: &HashMap<String, EmoteData>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    loop {
+        let markdown = rest.find("![img](emote|").map(|i| (i, "![img](", ')'));
+        let colon = rest.find(":emote|").map(|i| (i, ":", ':'));
+        let Some((start, open, close)) = [markdown, colon].into_iter().flatten().min() else {
+            break;
+        };
+        let id_start = start + open.len();
+        let Some(id_len) = rest[id_start..].find(close) else {
+            break;
+        };
+        let id = &rest[id_start..id_start + id_len];
+        let name = emotes
+            .get(id)
+            .and_then(|v| v.t.as_deref())
+            .or(id.rsplit('|').next())
+            .unwrap_or(id);
+        out.push_str(&rest[..start]);
+        out.push(':');
+        out.push_str(name);
+        out.push(':');
+        rest = &rest[id_start + id_len + 1..];
     }
+    out.push_str(rest);
+    out
 }
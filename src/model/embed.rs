@@ -0,0 +1,34 @@
+use serde::Serialize;
+
+use crate::reddit_api::Oembed;
+
+/// A video or post from another site the post links to, as described by that site
+#[derive(Debug, Clone, Serialize)]
+pub struct Embed {
+    pub title: String,
+    /// Channel or account that posted it
+    pub author: Option<String>,
+    /// Site name, like "YouTube"
+    pub provider: Option<String>,
+}
+
+impl Embed {
+    /// `None` when the site gave nothing worth showing
+    pub fn from_oembed(value: Oembed) -> Option<Self> {
+        let title = value.title.filter(|v| !v.trim().is_empty())?;
+        Some(Self {
+            title,
+            author: value.author_name.filter(|v| !v.trim().is_empty()),
+            provider: value.provider_name.filter(|v| !v.trim().is_empty()),
+        })
+    }
+
+    /// "channel • provider", whichever of them is known
+    pub fn byline(&self) -> Option<String> {
+        let parts: Vec<&str> = [self.author.as_deref(), self.provider.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" • "))
+    }
+}
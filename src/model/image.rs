@@ -0,0 +1,33 @@
+use serde::Serialize;
+
+/// A remote image along with its size, known before it is downloaded
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageRef {
+    pub url: String,
+    /// 0 when the size isn't known, like for direct image links without a preview
+    pub width: u32,
+    pub height: u32,
+    pub caption: Option<String>,
+    /// Link the poster attached to a gallery image
+    pub outbound_url: Option<String>,
+}
+
+impl ImageRef {
+    /// The image `url` links to directly, for hosts known to serve the file itself
+    pub fn from_direct_link(url: &str) -> Option<Self> {
+        const HOSTS: [&str; 3] = ["i.redd.it", "i.imgur.com", "imgur.com"];
+        const EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "webp", "gif"];
+
+        let parsed = reqwest::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_lowercase();
+        let (_, extension) = parsed.path().rsplit_once('.')?;
+        (HOSTS.contains(&host.as_str()) && EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            .then(|| ImageRef {
+                url: url.to_string(),
+                width: 0,
+                height: 0,
+                caption: None,
+                outbound_url: None,
+            })
+    }
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+
+use crate::reddit_api::{LiveUpdateData, LiveUpdateEventData};
+
+#[derive(Debug, Default, Clone)]
+pub struct LiveThread {
+    pub title: String,
+    pub description: String,
+    pub is_live: bool,
+    pub viewer_count: Option<u64>,
+}
+
+impl From<LiveUpdateEventData> for LiveThread {
+    fn from(value: LiveUpdateEventData) -> Self {
+        Self {
+            title: value.title,
+            description: value.description,
+            is_live: value.state == "live",
+            viewer_count: value.viewer_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LiveUpdate {
+    pub id: String,
+    pub author: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub stricken: bool,
+}
+
+impl From<LiveUpdateData> for LiveUpdate {
+    fn from(value: LiveUpdateData) -> Self {
+        Self {
+            id: value.id,
+            author: value.author.unwrap_or_else(|| "[deleted]".to_string()),
+            body: value.body,
+            created_at: DateTime::<Utc>::from_timestamp_secs(value.created_utc as i64).unwrap(),
+            stricken: value.stricken,
+        }
+    }
+}
@@ -1,2 +1,9 @@
 pub mod comment;
+pub mod embed;
+pub mod image;
+pub mod live_thread;
+pub mod mod_item;
+pub mod modmail;
+pub mod poll;
 pub mod post;
+pub mod subreddit;
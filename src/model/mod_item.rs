@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::reddit_api::{CommentData, PostData};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModItemKind {
+    Post,
+    Comment,
+}
+
+/// A post or comment as it appears in a moderation listing
+#[derive(Debug, Clone)]
+pub struct ModItem {
+    pub fullname: String,
+    pub kind: ModItemKind,
+    pub subreddit: String,
+    pub author: String,
+    pub title: String,
+    pub body: String,
+    pub score: i64,
+    pub created_at: DateTime<Utc>,
+    pub permalink: String,
+    pub report_reasons: Vec<String>,
+}
+
+impl From<PostData> for ModItem {
+    fn from(value: PostData) -> Self {
+        Self {
+            fullname: format!("t3_{}", value.id),
+            kind: ModItemKind::Post,
+            subreddit: value.subreddit,
+            author: value.author,
+            title: value.title,
+            body: value.selftext,
+            score: value.score,
+            created_at: DateTime::<Utc>::from_timestamp_secs(value.created_utc as i64).unwrap(),
+            permalink: value.permalink,
+            report_reasons: report_reasons(&value.user_reports, &value.mod_reports),
+        }
+    }
+}
+
+impl From<CommentData> for ModItem {
+    fn from(value: CommentData) -> Self {
+        Self {
+            fullname: format!("t1_{}", value.id),
+            kind: ModItemKind::Comment,
+            subreddit: value.subreddit,
+            author: value.author,
+            title: value.link_title.unwrap_or_default(),
+            body: value.body,
+            score: value.score,
+            created_at: DateTime::<Utc>::from_timestamp_secs(value.created_utc as i64).unwrap(),
+            permalink: value.permalink,
+            report_reasons: report_reasons(&value.user_reports, &value.mod_reports),
+        }
+    }
+}
+
+/// Reports come as `[reason, count, ...]` (user) or `[reason, moderator]` (mod) arrays
+fn report_reasons(user_reports: &[Value], mod_reports: &[Value]) -> Vec<String> {
+    user_reports
+        .iter()
+        .chain(mod_reports)
+        .filter_map(|v| v.get(0).and_then(|v| v.as_str()).map(String::from))
+        .collect()
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+
+use crate::reddit_api::{ModmailConversationData, ModmailMessageData};
+
+#[derive(Debug, Clone)]
+pub struct ModmailConversation {
+    pub id: String,
+    pub subject: String,
+    pub subreddit: String,
+    pub participant: Option<String>,
+    pub last_updated: DateTime<Utc>,
+    pub num_messages: u64,
+}
+
+impl From<ModmailConversationData> for ModmailConversation {
+    fn from(value: ModmailConversationData) -> Self {
+        Self {
+            id: value.id,
+            subject: value.subject,
+            subreddit: value.owner.display_name,
+            participant: value.participant.map(|v| v.name).filter(|v| !v.is_empty()),
+            last_updated: value.last_updated,
+            num_messages: value.num_messages,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub author: String,
+    pub is_mod: bool,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ModmailMessageData> for Message {
+    fn from(value: ModmailMessageData) -> Self {
+        Self {
+            author: value.author.name,
+            is_mod: value.author.is_mod,
+            body: value.body_markdown,
+            created_at: value.date,
+        }
+    }
+}
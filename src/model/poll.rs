@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::reddit_api::PollData;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Poll {
+    pub options: Vec<PollOption>,
+    pub total_vote_count: u64,
+    pub voting_end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PollOption {
+    pub text: String,
+    /// Only known once the poll has ended (or the user has voted)
+    pub vote_count: Option<u64>,
+}
+
+impl From<PollData> for Poll {
+    fn from(value: PollData) -> Self {
+        Self {
+            options: value
+                .options
+                .into_iter()
+                .map(|v| PollOption {
+                    text: v.text,
+                    vote_count: v.vote_count,
+                })
+                .collect(),
+            total_vote_count: value.total_vote_count,
+            voting_end_at: value
+                .voting_end_timestamp
+                .and_then(DateTime::<Utc>::from_timestamp_millis),
+        }
+    }
+}
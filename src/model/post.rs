@@ -1,26 +1,53 @@
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 
-use crate::reddit_api::PostData;
+use crate::{
+    format,
+    model::{embed::Embed, image::ImageRef, poll::Poll},
+    reddit_api::PostData,
+};
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct Post {
     pub author: String,
     pub body: String,
     pub crosspost_parent: Vec<Post>,
     pub id: String,
     pub num_comments: u64,
-    pub preview_image_urls: Option<Vec<String>>,
+    pub preview_images: Option<Vec<ImageRef>>,
     pub score: i64,
+    /// Score is hidden by the sub for new posts, `score` is meaningless until revealed
+    pub score_hidden: bool,
+    /// Comment scores are hidden and their order randomized
+    pub contest_mode: bool,
     pub subreddit: String,
     pub title: String,
     pub url: String,
-    pub galleries: Option<Vec<String>>,
+    pub galleries: Option<Vec<ImageRef>>,
     pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
     pub video_url: Option<String>,
+    /// Hosted on reddit, even when `video_url` is missing
+    pub is_video: bool,
+    /// Seconds, when reddit knows it
+    pub video_duration: Option<u64>,
+    pub post_hint: Option<String>,
+    /// Title and channel of a linked YouTube video, tweet and the like
+    pub embed: Option<Embed>,
+    pub poll: Option<Poll>,
+    pub locked: bool,
+    pub approved: bool,
+    pub removed: bool,
 }
 
 impl From<PostData> for Post {
-    fn from(value: PostData) -> Self {
+    fn from(mut value: PostData) -> Self {
+        // Direct image links have no preview at times, the link itself is the image then
+        let direct_image = value
+            .media_metadata
+            .is_none()
+            .then(|| ImageRef::from_direct_link(&value.url))
+            .flatten();
         Post {
             id: value.id,
             subreddit: value.subreddit,
@@ -30,24 +57,81 @@ impl From<PostData> for Post {
             url: value.url,
             num_comments: value.num_comments,
             score: value.score,
+            score_hidden: value.hide_score,
+            contest_mode: value.contest_mode,
             crosspost_parent: value
                 .crosspost_parent_list
                 .into_iter()
                 .map(Post::from)
                 .collect(),
-            preview_image_urls: value.preview.and_then(|i| {
-                i.images
-                    .first()
-                    .map(|i| i.resolutions.iter().map(|i| i.url.clone()).collect())
-            }),
-            galleries: value.media_metadata.map(|v| {
-                v.items
+            preview_images: value
+                .preview
+                .and_then(|i| {
+                    i.images.first().map(|i| {
+                        i.resolutions
+                            .iter()
+                            .map(|i| ImageRef {
+                                url: i.url.clone(),
+                                width: i.width as u32,
+                                height: i.height as u32,
+                                caption: None,
+                                outbound_url: None,
+                            })
+                            .collect()
+                    })
+                })
+                .or_else(|| direct_image.map(|v| vec![v])),
+            galleries: value.media_metadata.map(|v| match value.gallery_data {
+                // Gallery items keep the poster's order, the metadata alone is unordered
+                Some(gallery_data) => gallery_data
+                    .items
+                    .into_iter()
+                    .filter_map(|item| {
+                        let media = v.items.values().find(|v| v.id == item.media_id)?;
+                        media.p.last().map(|v| ImageRef {
+                            url: v.u.clone(),
+                            width: v.x,
+                            height: v.y,
+                            caption: item.caption,
+                            outbound_url: item.outbound_url,
+                        })
+                    })
+                    .collect(),
+                None => v
+                    .items
                     .values()
-                    .filter_map(|v| v.p.last().map(|v| v.u.clone()))
-                    .collect()
+                    .filter_map(|item| {
+                        item.p.last().map(|v| ImageRef {
+                            url: v.u.clone(),
+                            width: v.x,
+                            height: v.y,
+                            caption: None,
+                            outbound_url: None,
+                        })
+                    })
+                    .collect(),
             }),
             created_at: DateTime::<Utc>::from_timestamp_secs(value.created_utc as i64).unwrap(),
+            edited_at: value
+                .edited
+                .and_then(|v| DateTime::<Utc>::from_timestamp_secs(v as i64)),
+            embed: value
+                .media
+                .as_mut()
+                .and_then(|v| v.oembed.take())
+                .and_then(|v| Embed::from_oembed(*v)),
+            video_duration: value
+                .media
+                .as_ref()
+                .and_then(|v| v.reddit_video.as_ref())
+                .and_then(|v| v.duration),
             video_url: value.media.and_then(|v| v.reddit_video.map(|v| v.hls_url)),
+            is_video: value.is_video,
+            post_hint: value.post_hint,
+            poll: value.poll_data.map(Poll::from),
+            locked: value.locked,
+            approved: value.approved,
+            removed: value.removed,
             // galleries: value.gallery_data.map(|v| {
             //     v.items
             //         .iter()
@@ -70,3 +154,166 @@ impl From<PostData> for Post {
         }
     }
 }
+
+/// What a post mostly is, for skimming a feed by type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostKind {
+    Text,
+    Link,
+    Image,
+    Video,
+    Gallery,
+    Crosspost,
+}
+
+impl PostKind {
+    /// Emoji that are two cells wide without a variation selector, so the titles line up
+    pub fn glyph(self) -> &'static str {
+        match self {
+            PostKind::Text => "📝",
+            PostKind::Link => "🔗",
+            PostKind::Image => "📷",
+            PostKind::Video => "🎬",
+            PostKind::Gallery => "📚",
+            PostKind::Crosspost => "🔁",
+        }
+    }
+}
+
+impl Post {
+    /// From the media fields first, `post_hint` is missing on many posts
+    pub fn kind(&self) -> PostKind {
+        let hint = self.post_hint.as_deref();
+        if !self.crosspost_parent.is_empty() {
+            PostKind::Crosspost
+        } else if self.galleries.is_some() {
+            PostKind::Gallery
+        } else if self.is_video
+            || self.video_url.is_some()
+            || matches!(hint, Some("hosted:video" | "rich:video"))
+        {
+            PostKind::Video
+        } else if hint == Some("image") || ImageRef::from_direct_link(&self.url).is_some() {
+            PostKind::Image
+        } else if self.external_url().is_some() {
+            PostKind::Link
+        } else {
+            PostKind::Text
+        }
+    }
+
+    pub fn score_text(&self, compact: bool) -> String {
+        if self.score_hidden {
+            "•".to_string()
+        } else {
+            format::score(self.score, compact)
+        }
+    }
+
+    pub fn fullname(&self) -> String {
+        format!("t3_{}", self.id)
+    }
+
+    /// Parse a post or comment permalink (`/r/{sub}/comments/{post_id}/{slug}/{comment_id}/`)
+    /// into a post stub and the comment id
+    pub fn from_permalink(permalink: &str) -> Option<(Self, Option<String>)> {
+        let path = permalink
+            .split_once("reddit.com")
+            .map_or(permalink, |(_, path)| path);
+        let mut segments = path.split('/').filter(|v| !v.is_empty());
+        let (Some("r"), Some(subreddit), Some("comments"), Some(id), _, comment_id) = (
+            segments.next(),
+            segments.next(),
+            segments.next(),
+            segments.next(),
+            segments.next(),
+            segments.next(),
+        ) else {
+            return None;
+        };
+        let post = Post {
+            id: id.to_string(),
+            subreddit: subreddit.to_string(),
+            ..Default::default()
+        };
+        Some((post, comment_id.map(String::from)))
+    }
+
+    /// Only the id and sub are known, like for a post opened from its permalink
+    pub fn is_stub(&self) -> bool {
+        self.title.is_empty()
+    }
+
+    pub fn permalink(&self) -> String {
+        format!(
+            "https://www.reddit.com/r/{}/comments/{}",
+            self.subreddit, self.id
+        )
+    }
+
+    /// Where a link post points to, `None` for self posts whose url is their own permalink
+    pub fn external_url(&self) -> Option<&str> {
+        let own_path = format!("/comments/{}", self.id);
+        Some(self.url.as_str()).filter(|url| !url.is_empty() && !url.contains(&own_path))
+    }
+
+    /// Where a post linking to another site goes, `None` for self posts and for images, videos,
+    /// galleries and crossposts which are shown in the post instead
+    pub fn outbound_link(&self) -> Option<&str> {
+        self.external_url().filter(|url| {
+            ImageRef::from_direct_link(url).is_none()
+                && self.video_url.is_none()
+                && self.galleries.is_none()
+                && self.crosspost_parent.is_empty()
+                && self.live_thread_id().is_none()
+        })
+    }
+
+    /// `crossposted from r/sub by u/user` for crossposts, `None` for other posts
+    pub fn crosspost_source(&self) -> Option<String> {
+        self.crosspost_parent
+            .first()
+            .map(|v| format!("crossposted from r/{} by u/{}", v.subreddit, v.author))
+    }
+
+    /// `▶` with the length of a video post, `None` for other posts
+    pub fn video_badge(&self) -> Option<String> {
+        if !self.is_video && self.video_url.is_none() {
+            return None;
+        }
+        Some(match self.video_duration {
+            Some(duration) => format!("▶ {}", format::duration(duration)),
+            None => "▶".to_string(),
+        })
+    }
+
+    /// Id of the reddit live thread this post links to, if any
+    pub fn live_thread_id(&self) -> Option<&str> {
+        let (_, path) = self.url.split_once("reddit.com/live/")?;
+        path.split(['/', '?', '#']).next().filter(|v| !v.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::text::Span;
+
+    use super::*;
+    use crate::text;
+
+    #[test]
+    fn glyphs_are_as_wide_as_each_other() {
+        let kinds = [
+            PostKind::Text,
+            PostKind::Link,
+            PostKind::Image,
+            PostKind::Video,
+            PostKind::Gallery,
+            PostKind::Crosspost,
+        ];
+        for kind in kinds {
+            assert_eq!(text::width(kind.glyph()), 2, "{:?}", kind);
+            assert_eq!(Span::raw(kind.glyph()).width(), 2, "{:?}", kind);
+        }
+    }
+}
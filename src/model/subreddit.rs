@@ -0,0 +1,111 @@
+use reqwest::StatusCode;
+
+use crate::{
+    ngored_error::NgoredError,
+    reddit_api::{Data, RedditApi, SubredditData},
+};
+
+/// `text` without the `r/` people tend to type
+pub fn sub_name(text: &str) -> &str {
+    let text = text.trim();
+    let text = text.strip_prefix('/').unwrap_or(text);
+    text.strip_prefix("r/").unwrap_or(text)
+}
+
+/// The sub name in `text` if reddit could have a sub by that name: 2 to 21 letters, digits or
+/// `_`, or several of those joined with `+` for a multi
+pub fn valid_sub_name(text: &str) -> Result<&str, NgoredError> {
+    let name = sub_name(text);
+    let valid = name.split('+').all(|part| {
+        (2..=21).contains(&part.len())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    });
+    if valid {
+        Ok(name)
+    } else {
+        Err(NgoredError::InvalidSubName(name.to_string()))
+    }
+}
+
+/// Look `name` up on reddit, each sub of a multi on its own, and return it as reddit spells it
+pub async fn look_up(reddit_api: &dyn RedditApi, name: &str) -> Result<String, NgoredError> {
+    let mut names = Vec::new();
+    for part in name.split('+') {
+        names.push(canonical_name(reddit_api, part).await?);
+    }
+    Ok(names.join("+"))
+}
+
+/// `sub` as reddit spells it. Reddit answers a sub that doesn't exist with a search for it and a
+/// banned one with a 404. Private subs can't be looked at but exist, they keep the name as typed
+async fn canonical_name(reddit_api: &dyn RedditApi, sub: &str) -> Result<String, NgoredError> {
+    match reddit_api.get_subreddit_about(sub).await {
+        Ok(Data::Subreddit(about)) => Ok(about.display_name),
+        Ok(_) => Err(NgoredError::NoSuchSub(sub.to_string())),
+        Err(NgoredError::Http { source, .. }) if source.status() == Some(StatusCode::NOT_FOUND) => {
+            Err(NgoredError::NoSuchSub(sub.to_string()))
+        }
+        Err(NgoredError::Http { source, .. }) if source.status() == Some(StatusCode::FORBIDDEN) => {
+            Ok(sub.to_string())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// How a sub describes itself on its about page
+#[derive(Debug, Clone)]
+pub struct SubredditAbout {
+    pub title: String,
+    pub description: String,
+    pub subscribers: Option<u64>,
+    pub active_users: Option<u64>,
+    pub icon_url: Option<String>,
+    /// Accent color picked by the sub, as `#rrggbb`
+    pub color: Option<String>,
+}
+
+impl From<SubredditData> for SubredditAbout {
+    fn from(value: SubredditData) -> Self {
+        // Reddit sends empty strings for what a sub didn't set
+        let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+        Self {
+            title: non_empty(value.title).unwrap_or(value.display_name),
+            description: value.public_description.unwrap_or_default(),
+            subscribers: value.subscribers,
+            active_users: value.active_user_count,
+            icon_url: non_empty(value.community_icon).or(non_empty(value.icon_img)),
+            color: non_empty(value.key_color).or(non_empty(value.primary_color)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_sub_name_drops_the_prefix() {
+        for text in ["rust", "r/rust", "/r/rust", "  r/rust "] {
+            assert_eq!(valid_sub_name(text).unwrap(), "rust");
+        }
+        assert_eq!(valid_sub_name("r/rust+news").unwrap(), "rust+news");
+    }
+
+    #[test]
+    fn valid_sub_name_rejects_what_reddit_wouldnt_name_a_sub() {
+        let too_long = "a".repeat(22);
+        for text in [
+            "r",
+            "r/a",
+            &too_long,
+            "rust-lang",
+            "rust news",
+            "rust+",
+            "+rust",
+            "",
+        ] {
+            assert!(valid_sub_name(text).is_err(), "{:?} passed", text);
+        }
+        assert!(valid_sub_name(&"a".repeat(21)).is_ok());
+    }
+}
@@ -1,11 +1,96 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
 use tui_logger::TuiLoggerError;
 
+/// Errors carry what was being loaded so the message shown in the UI says what to retry or fix
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum NgoredError {
+    #[error("Logger: {0}")]
     Logger(String),
-    IO(String),
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error("App event: {0}")]
     Send(String),
+    #[error("Request to {url} failed: {source}")]
+    Http {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("Unexpected response from {url}: {source}")]
+    Json {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("No fixture for {url}, record it first with --record")]
+    MissingFixture {
+        url: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Cannot decode image {url}: {source}")]
+    Image {
+        url: String,
+        #[source]
+        source: image::ImageError,
+    },
+    #[error("Invalid config {}: {source}", path.display())]
+    Config {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("Invalid state file {}: {source}", path.display())]
+    State {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("Cannot save state to {}: {reason}", path.display())]
+    SaveState { path: PathBuf, reason: String },
+    #[error("Cannot run \"{command}\": {reason}")]
+    Command { command: String, reason: String },
+    #[error("Moderation needs an [account] section in config.toml")]
+    NoAccount,
+    #[error("Cannot load r/{sub}: {source}")]
+    Sub {
+        sub: String,
+        #[source]
+        source: Box<NgoredError>,
+    },
+    #[error("Cannot load post {post_id}: {source}")]
+    Post {
+        post_id: String,
+        #[source]
+        source: Box<NgoredError>,
+    },
+    #[error("live/{0} isn't a live thread")]
+    NotLiveThread(String),
+    #[error("\"{0}\" isn't a sub name, those are 2 to 21 letters, digits or _")]
+    InvalidSubName(String),
+    #[error("There's no r/{0}, or it was banned")]
+    NoSuchSub(String),
+    #[error("Usage: {0}")]
+    Usage(&'static str),
+}
+
+impl NgoredError {
+    pub fn in_sub(self, sub: &str) -> Self {
+        NgoredError::Sub {
+            sub: sub.to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    pub fn in_post(self, post_id: &str) -> Self {
+        NgoredError::Post {
+            post_id: post_id.to_string(),
+            source: Box::new(self),
+        }
+    }
 }
 
 impl From<TuiLoggerError> for NgoredError {
@@ -17,12 +102,6 @@ impl From<TuiLoggerError> for NgoredError {
     }
 }
 
-impl From<std::io::Error> for NgoredError {
-    fn from(value: std::io::Error) -> Self {
-        NgoredError::IO(value.to_string())
-    }
-}
-
 impl<T> From<tokio::sync::mpsc::error::SendError<T>> for NgoredError {
     fn from(value: tokio::sync::mpsc::error::SendError<T>) -> Self {
         NgoredError::Send(value.to_string())
@@ -0,0 +1,109 @@
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+use crate::{
+    config::{Config, UrlHandler},
+    ngored_error::NgoredError,
+};
+
+/// Opens links the way the config asks, held by each component that opens any
+#[derive(Debug, Clone, Default)]
+pub struct Opener {
+    /// `browser_command` from the config, `None` opens urls with the system's default handler
+    browser_command: Option<String>,
+    /// `url_handlers` from the config, tried before the browser
+    url_handlers: Vec<UrlHandler>,
+}
+
+impl Opener {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            browser_command: config
+                .browser_command
+                .clone()
+                .filter(|v| !v.trim().is_empty()),
+            url_handlers: config
+                .url_handlers
+                .iter()
+                .filter(|v| !v.command.trim().is_empty())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Open `url` with the first url handler matching it, else the configured browser command,
+    /// else the system's default handler
+    pub fn open(&self, url: &str) -> Result<(), NgoredError> {
+        let handler = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|parsed| self.url_handlers.iter().find(|v| v.matches(&parsed)));
+        if let Some(handler) = handler {
+            return run(&handler.command, url);
+        }
+        let Some(command) = &self.browser_command else {
+            open::that(url)?;
+            return Ok(());
+        };
+        run(command, url)
+    }
+}
+
+/// Run `command` split into words like a shell would, quotes and backslashes included. `{url}` in
+/// it is replaced with the url, which is appended when there's no placeholder. Output is discarded
+/// so it can't draw over the UI, and the process is waited on in the background once it's done
+fn run(command: &str, url: &str) -> Result<(), NgoredError> {
+    let args = args(command, url).map_err(|reason| NgoredError::Command {
+        command: command.to_string(),
+        reason,
+    })?;
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    tokio::spawn(async move {
+        let _ = child.wait().await;
+    });
+    Ok(())
+}
+
+/// Words of `command` with the url put in, the program first
+fn args(command: &str, url: &str) -> Result<Vec<String>, String> {
+    let mut args = shell_words::split(command).map_err(|err| err.to_string())?;
+    if args.is_empty() {
+        return Err("nothing to run".to_string());
+    }
+    if command.contains("{url}") {
+        args.iter_mut().for_each(|v| *v = v.replace("{url}", url));
+    } else {
+        args.push(url.to_string());
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://example.com/a b";
+
+    #[test]
+    fn args_keep_quoted_words_together() {
+        assert_eq!(
+            args("mpv --title='ngored: {url}' {url}", URL).unwrap(),
+            ["mpv", "--title=ngored: https://example.com/a b", URL]
+        );
+        assert_eq!(
+            args(r#""/opt/My Browser/browser" --new-tab"#, URL).unwrap(),
+            ["/opt/My Browser/browser", "--new-tab", URL]
+        );
+    }
+
+    #[test]
+    fn args_fail_on_unclosed_quotes() {
+        assert!(args("w3m 'unclosed", URL).is_err());
+        assert!(args("   ", URL).is_err());
+    }
+}
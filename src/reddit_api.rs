@@ -1,48 +1,83 @@
 use std::collections::HashMap;
 
-use reqwest::Client;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 
-#[derive(Debug, Clone)]
-pub struct RedditApi {
-    pub client: Client,
+use crate::ngored_error::NgoredError;
+
+mod http;
+#[cfg(test)]
+mod mock;
+
+pub use http::{FixtureMode, HttpRedditApi};
+#[cfg(test)]
+pub use mock::MockRedditApi;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ModAction {
+    Approve,
+    Remove,
+    Spam,
+    Lock,
+    Unlock,
 }
 
-impl RedditApi {
-    pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.6 Safari/605.1.15")
-    .build().unwrap();
+/// Backend the UI loads reddit data from, `HttpRedditApi` talks to reddit itself
+#[async_trait]
+pub trait RedditApi: Send + Sync {
+    fn has_account(&self) -> bool;
 
-        Self { client }
-    }
+    async fn get_mod_queue(&self, sub: &str, location: &str) -> Result<Data, NgoredError>;
 
-    pub async fn get_posts(&self, sub: &str) -> Data {
-        self.client
-            .get(format!("https://www.reddit.com/r/{}/best.json", sub))
-            .query(&[("raw_json", "1")])
-            .send()
-            .await
-            .unwrap()
-            .json()
-            .await
-            .unwrap()
-    }
+    async fn get_moderated_subs(&self) -> Result<Data, NgoredError>;
 
-    pub async fn get_post_comment(&self, sub: &str, post_id: &str) -> Data {
-        let res: Vec<serde_json::Value> = self
-            .client
-            .get(format!("https://www.reddit.com/r/{}/{}.json", sub, post_id))
-            .query(&[("raw_json", "1")])
-            .send()
-            .await
-            .unwrap()
-            .json()
-            .await
-            .unwrap();
-        serde_json::from_value(res[1].clone()).unwrap()
-    }
+    async fn get_friends(&self) -> Result<Vec<String>, NgoredError>;
+
+    async fn set_friend(&self, username: &str, is_friend: bool) -> Result<(), NgoredError>;
+
+    /// Hide posts by fullname from the account's listings
+    async fn hide_posts(&self, fullnames: &[String]) -> Result<(), NgoredError>;
+
+    async fn get_modmail_conversations(&self) -> Result<ModmailConversationsData, NgoredError>;
+
+    async fn get_modmail_conversation(
+        &self,
+        conversation_id: &str,
+    ) -> Result<ModmailConversationDetailData, NgoredError>;
+
+    async fn moderate(&self, fullname: &str, action: ModAction) -> Result<(), NgoredError>;
+
+    /// `sort` is one of reddit's listings like `top` or `new`, `None` for the one the post list
+    /// shows
+    async fn get_posts(&self, sub: &str, sort: Option<&str>) -> Result<Data, NgoredError>;
+
+    /// The sub's title, description, icon and colors
+    async fn get_subreddit_about(&self, sub: &str) -> Result<Data, NgoredError>;
+
+    async fn get_post_comment(&self, sub: &str, post_id: &str) -> Result<Data, NgoredError>;
+
+    /// The post listing along with the comments, for a post only known by its id
+    async fn get_post(&self, sub: &str, post_id: &str) -> Result<(Data, Data), NgoredError>;
+
+    /// Post and comments narrowed down to one comment with `context` levels of its parents
+    async fn get_comment_context(
+        &self,
+        sub: &str,
+        post_id: &str,
+        comment_id: &str,
+        context: u32,
+    ) -> Result<(Data, Data), NgoredError>;
+
+    async fn get_duplicates(&self, post_id: &str) -> Result<Data, NgoredError>;
+
+    async fn get_live_thread_about(&self, thread_id: &str) -> Result<Data, NgoredError>;
+
+    async fn get_live_thread_updates(&self, thread_id: &str) -> Result<Data, NgoredError>;
+
+    /// Raw bytes of an image, the error is shown in place of the image
+    async fn get_media(&self, url: &str) -> Result<Vec<u8>, NgoredError>;
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,47 +86,75 @@ pub enum Data {
     #[serde(rename = "t1")]
     Comment(CommentData),
     #[serde(rename = "t3")]
-    Post(PostData),
+    Post(Box<PostData>),
+    #[serde(rename = "t5")]
+    Subreddit(SubredditData),
     Listing(ListingData),
     #[serde(rename = "more")]
+    #[allow(dead_code)]
     More(MoreData),
+    LiveUpdate(LiveUpdateData),
+    LiveUpdateEvent(LiveUpdateEventData),
 }
 
 impl Data {
-    fn variant_str(&self) -> &'static str {
-        match self {
-            Data::Comment(..) => "Comment",
-            Data::Post(..) => "Post",
-            Data::Listing(..) => "Listing",
-            Data::More(..) => "More",
-        }
-    }
-
-    pub fn as_post(self) -> PostData {
+    pub fn into_post(self) -> Option<PostData> {
         if let Data::Post(data) = self {
-            data
+            Some(*data)
         } else {
-            panic!("{} is not Post", self.variant_str())
+            None
         }
     }
 
-    pub fn as_listing(self) -> ListingData {
+    pub fn into_listing(self) -> Option<ListingData> {
         if let Data::Listing(data) = self {
-            data
+            Some(data)
         } else {
-            panic!("{} is not Listing", self.variant_str())
+            None
         }
     }
 
-    pub fn as_comment(self) -> CommentData {
+    /// What a listing holds, nothing for anything else
+    pub fn into_children(self) -> Vec<Data> {
+        self.into_listing().map(|v| v.children).unwrap_or_default()
+    }
+
+    /// The posts of a listing, none when it isn't one or holds something else, like the listing
+    /// of subs reddit answers the posts of a sub that doesn't exist with
+    pub fn into_posts(self) -> Option<Vec<PostData>> {
+        self.into_listing()?
+            .children
+            .into_iter()
+            .map(Data::into_post)
+            .collect()
+    }
+
+    pub fn into_comment(self) -> Option<CommentData> {
         if let Data::Comment(data) = self {
-            data
+            Some(data)
         } else {
-            panic!("{} is not Comment", self.variant_str())
+            None
         }
     }
-    pub fn as_comment_opt(self) -> Option<CommentData> {
-        if let Data::Comment(data) = self {
+
+    pub fn into_subreddit(self) -> Option<SubredditData> {
+        if let Data::Subreddit(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
+    pub fn into_live_update(self) -> Option<LiveUpdateData> {
+        if let Data::LiveUpdate(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
+    pub fn into_live_update_event(self) -> Option<LiveUpdateEventData> {
+        if let Data::LiveUpdateEvent(data) = self {
             Some(data)
         } else {
             None
@@ -134,6 +197,16 @@ where
     }
 }
 
+fn deserialize_edited<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // edited == false or edited == <utc timestamp>
+    let val: Value = Deserialize::deserialize(deserializer)?;
+    Ok(val.as_f64())
+}
+
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct MoreData {
     pub count: u64,
@@ -155,6 +228,12 @@ pub struct PostData {
     pub url: String,
     pub num_comments: u64,
     pub score: i64,
+    #[serde(default)]
+    pub hide_score: bool,
+    #[serde(default)]
+    pub contest_mode: bool,
+    #[serde(default, deserialize_with = "deserialize_edited")]
+    pub edited: Option<f64>,
     #[serde(default = "Vec::default")]
     pub crosspost_parent_list: Vec<PostData>,
     pub preview: Option<Preview>,
@@ -162,15 +241,70 @@ pub struct PostData {
     pub gallery_data: Option<GalleryData>,
     pub created_utc: f64,
     pub media: Option<Media>,
+    #[serde(default)]
+    pub is_video: bool,
+    /// What reddit guessed the post is: "image", "link", "hosted:video", "rich:video"...
+    pub post_hint: Option<String>,
+    pub poll_data: Option<PollData>,
+    #[serde(default)]
+    pub permalink: String,
+    #[serde(default)]
+    pub user_reports: Vec<Value>,
+    #[serde(default)]
+    pub mod_reports: Vec<Value>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub approved: bool,
+    #[serde(default)]
+    pub removed: bool,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct SubredditData {
+    pub display_name: String,
+    pub title: Option<String>,
+    pub public_description: Option<String>,
+    pub subscribers: Option<u64>,
+    pub active_user_count: Option<u64>,
+    pub community_icon: Option<String>,
+    pub icon_img: Option<String>,
+    pub key_color: Option<String>,
+    pub primary_color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PollData {
+    pub options: Vec<PollOptionData>,
+    pub total_vote_count: u64,
+    pub voting_end_timestamp: Option<i64>, // milliseconds
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PollOptionData {
+    pub text: String,
+    pub vote_count: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Media {
     pub reddit_video: Option<RedditVideo>,
+    pub oembed: Option<Box<Oembed>>,
+}
+
+/// What the linked site says about an embed, like a YouTube video or a tweet
+#[derive(Debug, Deserialize)]
+pub struct Oembed {
+    pub title: Option<String>,
+    pub author_name: Option<String>,
+    pub provider_name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct RedditVideo {
     pub hls_url: String,
+    /// Seconds
+    pub duration: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -179,6 +313,7 @@ pub struct MediaMetadata {
     pub items: HashMap<String, MediaItem>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct MediaItem {
     pub status: String,
@@ -203,18 +338,117 @@ pub struct GalleryData {
 #[derive(Debug, Deserialize)]
 pub struct GalleryItem {
     pub media_id: String,
+    pub caption: Option<String>,
+    /// Link the poster attached to the image
+    pub outbound_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CommentData {
+    pub id: String,
+    #[serde(default)]
+    pub subreddit: String,
+    #[serde(default)]
+    pub link_title: Option<String>,
+    #[serde(default)]
+    pub permalink: String,
+    #[serde(default)]
+    pub user_reports: Vec<Value>,
+    #[serde(default)]
+    pub mod_reports: Vec<Value>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub approved: bool,
+    #[serde(default)]
+    pub removed: bool,
     pub body: String,
     pub author: String,
     pub score: i64,
+    #[serde(default)]
+    pub score_hidden: bool,
     pub created_utc: f64,
+    #[serde(default, deserialize_with = "deserialize_edited")]
+    pub edited: Option<f64>,
+    /// Emotes used in the body, keyed by their `emote|...` id
+    #[serde(default)]
+    pub media_metadata: HashMap<String, EmoteData>,
     #[serde(default, deserialize_with = "deserialize_replies")]
     pub replies: Option<Box<Data>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct EmoteData {
+    /// Emote name, e.g. "joy"
+    pub t: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LiveUpdateData {
+    pub id: String,
+    pub body: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    pub created_utc: f64,
+    #[serde(default)]
+    pub stricken: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LiveUpdateEventData {
+    pub title: String,
+    pub description: String,
+    pub state: String,
+    pub viewer_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailConversationsData {
+    pub conversations: HashMap<String, ModmailConversationData>,
+    pub conversation_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailConversationData {
+    pub id: String,
+    pub subject: String,
+    pub owner: ModmailOwnerData,
+    #[serde(default)]
+    pub participant: Option<ModmailAuthorData>,
+    pub last_updated: DateTime<Utc>,
+    pub num_messages: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailOwnerData {
+    pub display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailAuthorData {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub is_mod: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModmailConversationDetailData {
+    pub messages: HashMap<String, ModmailMessageData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModmailMessageData {
+    pub body_markdown: String,
+    pub author: ModmailAuthorData,
+    pub date: DateTime<Utc>,
+}
+
 // impl<'de> Deserialize<'de> for ListingData<CommentData> {
 //     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
 //     where
@@ -0,0 +1,414 @@
+use std::{
+    fs::{self, create_dir_all},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{
+    Deserialize,
+    de::{DeserializeOwned, IgnoredAny},
+};
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::{
+    config::Account,
+    diagnostics::HttpRequestGuard,
+    ngored_error::NgoredError,
+    reddit_api::{
+        Data, ModAction, ModmailConversationDetailData, ModmailConversationsData, RedditApi,
+    },
+};
+
+const OAUTH_URL: &str = "https://oauth.reddit.com";
+const ACCESS_TOKEN_URL: &str = "https://www.reddit.com/api/v1/access_token";
+
+#[derive(Debug)]
+pub struct HttpRedditApi {
+    client: Client,
+    account: Option<Account>,
+    access_token: Mutex<Option<AccessToken>>,
+    fixture_mode: Option<FixtureMode>,
+}
+
+/// Save every response to disk, or answer every request from what was saved, to work on the UI
+/// offline with the same data each run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FixtureMode {
+    Record,
+    Replay,
+}
+
+#[derive(Debug)]
+struct AccessToken {
+    token: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccessTokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl HttpRedditApi {
+    pub fn new(account: Option<Account>, fixture_mode: Option<FixtureMode>) -> Self {
+        let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.6 Safari/605.1.15")
+    .build().unwrap();
+
+        Self {
+            client,
+            account,
+            access_token: Mutex::new(None),
+            fixture_mode,
+        }
+    }
+
+    async fn access_token(&self) -> Result<String, NgoredError> {
+        // Replayed requests never reach reddit, and the token must not end up in a fixture
+        if self.fixture_mode == Some(FixtureMode::Replay) {
+            return Ok(String::default());
+        }
+        let mut access_token = self.access_token.lock().await;
+        if let Some(access_token) = access_token.as_ref()
+            && access_token.expires_at > Instant::now()
+        {
+            return Ok(access_token.token.clone());
+        }
+
+        let account = self.account.as_ref().ok_or(NgoredError::NoAccount)?;
+        let res: AccessTokenResponse = async {
+            self.client
+                .post(ACCESS_TOKEN_URL)
+                .basic_auth(&account.client_id, Some(&account.client_secret))
+                .form(&[
+                    ("grant_type", "password"),
+                    ("username", &account.username),
+                    ("password", &account.password),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        }
+        .await
+        .map_err(Self::http_error)?;
+        // Renew a minute early so requests in flight don't race the expiry
+        *access_token = Some(AccessToken {
+            token: res.access_token.clone(),
+            expires_at: Instant::now() + Duration::from_secs(res.expires_in.saturating_sub(60)),
+        });
+        Ok(res.access_token)
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, NgoredError> {
+        let (url, body) = self.get_body(request).await?;
+        serde_json::from_slice(&body).map_err(|source| NgoredError::Json {
+            url: url.to_string(),
+            source,
+        })
+    }
+
+    /// Body of a GET request with the URL it was sent to, recorded or replayed in fixture mode
+    async fn get_body(&self, request: RequestBuilder) -> Result<(Url, Vec<u8>), NgoredError> {
+        let request = request.build().map_err(Self::http_error)?;
+        let url = request.url().clone();
+        let fixture = Self::fixture_path(&url);
+        if self.fixture_mode == Some(FixtureMode::Replay) {
+            let body = fs::read(&fixture).map_err(|source| NgoredError::MissingFixture {
+                url: url.to_string(),
+                source,
+            })?;
+            return Ok((url, body));
+        }
+        let _in_flight = HttpRequestGuard::new();
+        let body = async {
+            let bytes = self
+                .client
+                .execute(request)
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?;
+            Ok(bytes.to_vec())
+        }
+        .await
+        .map_err(Self::http_error)?;
+        if self.fixture_mode == Some(FixtureMode::Record) {
+            if let Some(parent) = fixture.parent() {
+                create_dir_all(parent)?;
+            }
+            fs::write(&fixture, &body)?;
+        }
+        Ok((url, body))
+    }
+
+    /// Requests changing something on reddit, skipped when replaying
+    async fn execute(&self, request: RequestBuilder) -> Result<(), NgoredError> {
+        if self.fixture_mode == Some(FixtureMode::Replay) {
+            return Ok(());
+        }
+        let _in_flight = HttpRequestGuard::new();
+        request
+            .send()
+            .await
+            .and_then(|v| v.error_for_status())
+            .map_err(Self::http_error)?;
+        Ok(())
+    }
+
+    fn http_error(source: reqwest::Error) -> NgoredError {
+        NgoredError::Http {
+            url: source.url().map(|v| v.to_string()).unwrap_or_default(),
+            source,
+        }
+    }
+
+    /// One file per URL, named after the URL so fixtures can be found and edited by hand
+    fn fixture_path(url: &Url) -> PathBuf {
+        let name: String = url
+            .as_str()
+            .trim_start_matches("https://")
+            .chars()
+            .map(|v| {
+                if v.is_ascii_alphanumeric() || v == '.' {
+                    v
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let home = dirs::home_dir().expect("Could not find home directory");
+        home.join(".local")
+            .join("share")
+            .join("ngored")
+            .join("fixtures")
+            .join(name)
+    }
+}
+
+#[async_trait]
+impl RedditApi for HttpRedditApi {
+    fn has_account(&self) -> bool {
+        self.account.is_some()
+    }
+
+    async fn get_mod_queue(&self, sub: &str, location: &str) -> Result<Data, NgoredError> {
+        let access_token = self.access_token().await?;
+        let request = self
+            .client
+            .get(format!("{}/r/{}/about/{}", OAUTH_URL, sub, location))
+            .bearer_auth(access_token)
+            .query(&[("raw_json", "1")]);
+        self.get_json(request).await
+    }
+
+    async fn get_moderated_subs(&self) -> Result<Data, NgoredError> {
+        let access_token = self.access_token().await?;
+        let request = self
+            .client
+            .get(format!("{}/subreddits/mine/moderator", OAUTH_URL))
+            .bearer_auth(access_token)
+            .query(&[("limit", "100")]);
+        self.get_json(request).await
+    }
+
+    async fn get_friends(&self) -> Result<Vec<String>, NgoredError> {
+        let access_token = self.access_token().await?;
+        let request = self
+            .client
+            .get(format!("{}/api/v1/me/friends", OAUTH_URL))
+            .bearer_auth(access_token);
+        let res: Value = self.get_json(request).await?;
+        // { kind: "UserList", data: { children: [{ name, ... }] } }
+        let friends = res["data"]["children"]
+            .as_array()
+            .map(|v| {
+                v.iter()
+                    .filter_map(|v| v["name"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(friends)
+    }
+
+    async fn set_friend(&self, username: &str, is_friend: bool) -> Result<(), NgoredError> {
+        let access_token = self.access_token().await?;
+        let url = format!("{}/api/v1/me/friends/{}", OAUTH_URL, username);
+        let request = if is_friend {
+            self.client
+                .put(url)
+                .json(&serde_json::json!({ "name": username }))
+        } else {
+            self.client.delete(url)
+        };
+        self.execute(request.bearer_auth(access_token)).await
+    }
+
+    async fn hide_posts(&self, fullnames: &[String]) -> Result<(), NgoredError> {
+        let access_token = self.access_token().await?;
+        let request = self
+            .client
+            .post(format!("{}/api/hide", OAUTH_URL))
+            .form(&[("id", fullnames.join(","))]);
+        self.execute(request.bearer_auth(access_token)).await
+    }
+
+    async fn get_modmail_conversations(&self) -> Result<ModmailConversationsData, NgoredError> {
+        let access_token = self.access_token().await?;
+        let request = self
+            .client
+            .get(format!("{}/api/mod/conversations", OAUTH_URL))
+            .bearer_auth(access_token)
+            .query(&[("state", "all"), ("sort", "recent"), ("raw_json", "1")]);
+        self.get_json(request).await
+    }
+
+    async fn get_modmail_conversation(
+        &self,
+        conversation_id: &str,
+    ) -> Result<ModmailConversationDetailData, NgoredError> {
+        let access_token = self.access_token().await?;
+        let request = self
+            .client
+            .get(format!(
+                "{}/api/mod/conversations/{}",
+                OAUTH_URL, conversation_id
+            ))
+            .bearer_auth(access_token)
+            .query(&[("raw_json", "1")]);
+        self.get_json(request).await
+    }
+
+    async fn moderate(&self, fullname: &str, action: ModAction) -> Result<(), NgoredError> {
+        let access_token = self.access_token().await?;
+        let request = match action {
+            ModAction::Approve => self
+                .client
+                .post(format!("{}/api/approve", OAUTH_URL))
+                .form(&[("id", fullname)]),
+            ModAction::Remove => self
+                .client
+                .post(format!("{}/api/remove", OAUTH_URL))
+                .form(&[("id", fullname), ("spam", "false")]),
+            ModAction::Spam => self
+                .client
+                .post(format!("{}/api/remove", OAUTH_URL))
+                .form(&[("id", fullname), ("spam", "true")]),
+            ModAction::Lock => self
+                .client
+                .post(format!("{}/api/lock", OAUTH_URL))
+                .form(&[("id", fullname)]),
+            ModAction::Unlock => self
+                .client
+                .post(format!("{}/api/unlock", OAUTH_URL))
+                .form(&[("id", fullname)]),
+        };
+        self.execute(request.bearer_auth(access_token)).await
+    }
+
+    async fn get_posts(&self, sub: &str, sort: Option<&str>) -> Result<Data, NgoredError> {
+        // r/friends only exists for the logged in user
+        let request = if sub.eq_ignore_ascii_case("friends") && self.has_account() {
+            self.client
+                .get(format!("{}/r/friends/{}", OAUTH_URL, sort.unwrap_or("new")))
+                .bearer_auth(self.access_token().await?)
+        } else {
+            self.client.get(format!(
+                "https://www.reddit.com/r/{}/{}.json",
+                sub,
+                sort.unwrap_or("best")
+            ))
+        };
+        self.get_json(request.query(&[("raw_json", "1")])).await
+    }
+
+    async fn get_subreddit_about(&self, sub: &str) -> Result<Data, NgoredError> {
+        let request = self
+            .client
+            .get(format!("https://www.reddit.com/r/{}/about.json", sub))
+            .query(&[("raw_json", "1")]);
+        self.get_json(request).await
+    }
+
+    async fn get_post_comment(&self, sub: &str, post_id: &str) -> Result<Data, NgoredError> {
+        let request = self
+            .client
+            .get(format!("https://www.reddit.com/r/{}/{}.json", sub, post_id))
+            .query(&[("raw_json", "1")]);
+        // [post listing, comments listing]
+        let (_, comments): (IgnoredAny, Data) = self.get_json(request).await?;
+        Ok(comments)
+    }
+
+    async fn get_post(&self, sub: &str, post_id: &str) -> Result<(Data, Data), NgoredError> {
+        let request = self
+            .client
+            .get(format!("https://www.reddit.com/r/{}/{}.json", sub, post_id))
+            .query(&[("raw_json", "1")]);
+        self.get_json(request).await
+    }
+
+    /// Post and comments narrowed down to one comment with `context` levels of its parents
+    async fn get_comment_context(
+        &self,
+        sub: &str,
+        post_id: &str,
+        comment_id: &str,
+        context: u32,
+    ) -> Result<(Data, Data), NgoredError> {
+        let request = self
+            .client
+            .get(format!(
+                "https://www.reddit.com/r/{}/comments/{}/_/{}.json",
+                sub, post_id, comment_id
+            ))
+            .query(&[("raw_json", "1")])
+            .query(&[("context", context)]);
+        self.get_json(request).await
+    }
+
+    async fn get_duplicates(&self, post_id: &str) -> Result<Data, NgoredError> {
+        let request = self
+            .client
+            .get(format!(
+                "https://www.reddit.com/duplicates/{}.json",
+                post_id
+            ))
+            .query(&[("raw_json", "1")]);
+        // [original post listing, duplicates listing]
+        let (_, duplicates): (IgnoredAny, Data) = self.get_json(request).await?;
+        Ok(duplicates)
+    }
+
+    async fn get_live_thread_about(&self, thread_id: &str) -> Result<Data, NgoredError> {
+        let request = self
+            .client
+            .get(format!(
+                "https://www.reddit.com/live/{}/about.json",
+                thread_id
+            ))
+            .query(&[("raw_json", "1")]);
+        self.get_json(request).await
+    }
+
+    async fn get_live_thread_updates(&self, thread_id: &str) -> Result<Data, NgoredError> {
+        let request = self
+            .client
+            .get(format!("https://www.reddit.com/live/{}.json", thread_id))
+            .query(&[("raw_json", "1")]);
+        self.get_json(request).await
+    }
+
+    async fn get_media(&self, url: &str) -> Result<Vec<u8>, NgoredError> {
+        let (_, body) = self.get_body(self.client.get(url)).await?;
+        Ok(body)
+    }
+}
@@ -0,0 +1,141 @@
+use std::{collections::HashMap, io, sync::Mutex};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, de::IgnoredAny};
+
+use crate::{
+    ngored_error::NgoredError,
+    reddit_api::{
+        Data, ModAction, ModmailConversationDetailData, ModmailConversationsData, RedditApi,
+    },
+};
+
+/// Backend for tests, answering from bodies set up by path like `r/rust/best`. The path is the one
+/// of reddit's URL the request would go to, without `.json` and the query. Requests without an
+/// answer fail, and every request is kept to be checked
+#[derive(Default)]
+pub struct MockRedditApi {
+    answers: HashMap<String, Vec<u8>>,
+    requests: Mutex<Vec<String>>,
+}
+
+impl MockRedditApi {
+    /// Answer requests to `path` with `body`, reddit's JSON for most of them
+    pub fn with(mut self, path: &str, body: impl Into<Vec<u8>>) -> Self {
+        self.answers.insert(path.to_string(), body.into());
+        self
+    }
+
+    /// Paths requested so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn body(&self, path: String) -> Result<&[u8], NgoredError> {
+        self.requests.lock().unwrap().push(path.clone());
+        self.answers.get(&path).map(Vec::as_slice).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("No answer for {}", path)).into()
+        })
+    }
+
+    fn json<T: DeserializeOwned>(&self, path: String) -> Result<T, NgoredError> {
+        let body = self.body(path.clone())?;
+        serde_json::from_slice(body).map_err(|source| NgoredError::Json { url: path, source })
+    }
+
+    /// Requests changing something on reddit only succeed
+    fn execute(&self, path: String) -> Result<(), NgoredError> {
+        self.requests.lock().unwrap().push(path);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RedditApi for MockRedditApi {
+    /// Logged out, components skip the requests that need an account
+    fn has_account(&self) -> bool {
+        false
+    }
+
+    async fn get_mod_queue(&self, sub: &str, location: &str) -> Result<Data, NgoredError> {
+        self.json(format!("r/{}/about/{}", sub, location))
+    }
+
+    async fn get_moderated_subs(&self) -> Result<Data, NgoredError> {
+        self.json("subreddits/mine/moderator".to_string())
+    }
+
+    /// Answered with a plain array of names
+    async fn get_friends(&self) -> Result<Vec<String>, NgoredError> {
+        self.json("api/v1/me/friends".to_string())
+    }
+
+    async fn set_friend(&self, username: &str, is_friend: bool) -> Result<(), NgoredError> {
+        let method = if is_friend { "PUT" } else { "DELETE" };
+        self.execute(format!("{} api/v1/me/friends/{}", method, username))
+    }
+
+    async fn hide_posts(&self, fullnames: &[String]) -> Result<(), NgoredError> {
+        self.execute(format!("POST api/hide {}", fullnames.join(",")))
+    }
+
+    async fn get_modmail_conversations(&self) -> Result<ModmailConversationsData, NgoredError> {
+        self.json("api/mod/conversations".to_string())
+    }
+
+    async fn get_modmail_conversation(
+        &self,
+        conversation_id: &str,
+    ) -> Result<ModmailConversationDetailData, NgoredError> {
+        self.json(format!("api/mod/conversations/{}", conversation_id))
+    }
+
+    async fn moderate(&self, fullname: &str, action: ModAction) -> Result<(), NgoredError> {
+        self.execute(format!("POST {:?} {}", action, fullname))
+    }
+
+    async fn get_posts(&self, sub: &str, sort: Option<&str>) -> Result<Data, NgoredError> {
+        self.json(format!("r/{}/{}", sub, sort.unwrap_or("best")))
+    }
+
+    async fn get_subreddit_about(&self, sub: &str) -> Result<Data, NgoredError> {
+        self.json(format!("r/{}/about", sub))
+    }
+
+    async fn get_post_comment(&self, sub: &str, post_id: &str) -> Result<Data, NgoredError> {
+        let (_, comments): (IgnoredAny, Data) = self.json(format!("r/{}/{}", sub, post_id))?;
+        Ok(comments)
+    }
+
+    async fn get_post(&self, sub: &str, post_id: &str) -> Result<(Data, Data), NgoredError> {
+        self.json(format!("r/{}/{}", sub, post_id))
+    }
+
+    async fn get_comment_context(
+        &self,
+        sub: &str,
+        post_id: &str,
+        comment_id: &str,
+        _context: u32,
+    ) -> Result<(Data, Data), NgoredError> {
+        self.json(format!("r/{}/comments/{}/_/{}", sub, post_id, comment_id))
+    }
+
+    async fn get_duplicates(&self, post_id: &str) -> Result<Data, NgoredError> {
+        let (_, duplicates): (IgnoredAny, Data) = self.json(format!("duplicates/{}", post_id))?;
+        Ok(duplicates)
+    }
+
+    async fn get_live_thread_about(&self, thread_id: &str) -> Result<Data, NgoredError> {
+        self.json(format!("live/{}/about", thread_id))
+    }
+
+    async fn get_live_thread_updates(&self, thread_id: &str) -> Result<Data, NgoredError> {
+        self.json(format!("live/{}", thread_id))
+    }
+
+    /// Answered from the full URL of the image
+    async fn get_media(&self, url: &str) -> Result<Vec<u8>, NgoredError> {
+        self.body(url.to_string()).map(<[u8]>::to_vec)
+    }
+}
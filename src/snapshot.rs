@@ -0,0 +1,117 @@
+//! Screens of the app driven through a sub, its post list and a post against the fixtures in
+//! `tests/snapshots/fixtures`, compared with the screens saved next to them
+
+use std::{fs, path::PathBuf, sync::Arc, time::Duration};
+
+use chrono::DateTime;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{Terminal, backend::TestBackend, buffer::Buffer, text::Span};
+use ratatui_image::picker::{Picker, ProtocolType};
+
+use crate::{
+    app::App, config::Config, format::Clock, reddit_api::MockRedditApi, state_store::StateStore,
+};
+
+const WIDTH: u16 = 100;
+const HEIGHT: u16 = 40;
+
+/// Relative dates and days are shown against this, in its timezone, so the screens don't age
+const NOW: &str = "2024-05-01T12:00:00+02:00";
+
+/// How long the app has to stay quiet after the keys of a step before its screen is captured
+const IDLE: Duration = Duration::from_millis(300);
+
+/// Keys pressed before each snapshot: the post list of the sub, its first post with the comments,
+/// then the same post scrolled down
+const STEPS: [(&str, &[KeyCode]); 4] = [
+    ("sublist", &[]),
+    ("postlist", &[KeyCode::Char('l')]),
+    ("postdetail", &[KeyCode::Char('l')]),
+    (
+        "postdetail_scrolled",
+        &[KeyCode::Char('J'), KeyCode::Char('J')],
+    ),
+];
+
+/// A changed screen is written next to the saved one as `<step>.new.txt`, move it over the old
+/// one to accept it
+#[tokio::test]
+async fn screens_match_snapshots() {
+    let config = Config {
+        subs: vec!["rust".to_string()],
+        ..Config::default()
+    };
+    let reddit_api = MockRedditApi::default()
+        .with(
+            "r/rust/about",
+            include_str!("../tests/snapshots/fixtures/r_rust_about.json"),
+        )
+        .with(
+            "r/rust/best",
+            include_str!("../tests/snapshots/fixtures/r_rust_best.json"),
+        )
+        .with(
+            "r/rust/1cabcde",
+            include_str!("../tests/snapshots/fixtures/r_rust_1cabcde.json"),
+        );
+    // The terminal isn't queried, halfblocks render the same everywhere
+    let mut picker = Picker::from_fontsize((8, 12));
+    picker.set_protocol_type(ProtocolType::Halfblocks);
+    let clock = Clock::Fixed(DateTime::parse_from_rfc3339(NOW).unwrap());
+    let mut app = App::from_parts(
+        config,
+        Arc::new(reddit_api),
+        StateStore::in_memory(),
+        picker,
+        clock,
+    );
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    let mut changed = Vec::new();
+    for (name, keys) in STEPS {
+        for code in keys {
+            let key = KeyEvent::new(*code, KeyModifiers::NONE);
+            app.handle_event(&Event::Key(key)).await.unwrap();
+        }
+        app.settle(&mut terminal, IDLE).await.unwrap();
+
+        let actual = buffer_text(terminal.backend().buffer());
+        let expected = fs::read_to_string(dir.join(format!("{}.txt", name))).unwrap_or_default();
+        if actual != expected {
+            fs::write(dir.join(format!("{}.new.txt", name)), actual).unwrap();
+            changed.push(name);
+        }
+    }
+    assert!(
+        changed.is_empty(),
+        "Screens changed: {}, see the .new.txt files in {}",
+        changed.join(", "),
+        dir.display()
+    );
+}
+
+/// The symbols of the buffer row by row, styles are left out so the snapshots stay readable
+fn buffer_text(buffer: &Buffer) -> String {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width)
+        .map(|row| {
+            let mut line = String::new();
+            // Cells under a wide glyph are never sent to the backend and keep whatever was drawn
+            // there before, they'd make the text depend on the screens drawn earlier
+            let mut hidden = 0;
+            for cell in row {
+                if hidden > 0 {
+                    hidden -= 1;
+                    continue;
+                }
+                line.push_str(cell.symbol());
+                // Measured like ratatui does, which can disagree with `text::width` on emoji
+                hidden = Span::raw(cell.symbol()).width().saturating_sub(1);
+            }
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
@@ -0,0 +1,202 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, create_dir_all},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use ratatui::{
+    style::{Color, Stylize},
+    text::Span,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{model::post::Post, ngored_error::NgoredError};
+
+/// Local state that isn't configuration, persisted between sessions
+#[derive(Serialize, Deserialize, Default)]
+pub struct StateStore {
+    /// Keyed by lowercased username since reddit usernames are case-insensitive
+    #[serde(default)]
+    pub user_tags: HashMap<String, UserTag>,
+    /// Last time each post was opened, keyed by post id
+    #[serde(default)]
+    pub last_visits: HashMap<String, DateTime<Utc>>,
+    /// Comments collapsed in each thread, keyed by post id
+    #[serde(default)]
+    pub collapsed_comments: HashMap<String, HashSet<String>>,
+    /// Posts put aside to read later, oldest first
+    #[serde(default)]
+    pub read_later: Vec<ReadLaterPost>,
+    /// Never written to disk, so tests don't touch the user's state
+    #[serde(skip)]
+    in_memory: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserTag {
+    pub text: String,
+    pub color: String,
+}
+
+/// What's needed to open a queued post again, the rest is loaded fresh from its permalink
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadLaterPost {
+    pub id: String,
+    pub subreddit: String,
+    pub title: String,
+}
+
+impl From<&Post> for ReadLaterPost {
+    fn from(post: &Post) -> Self {
+        Self {
+            id: post.id.clone(),
+            subreddit: post.subreddit.clone(),
+            title: post.title.clone(),
+        }
+    }
+}
+
+impl ReadLaterPost {
+    pub fn permalink(&self) -> String {
+        format!(
+            "https://www.reddit.com/r/{}/comments/{}",
+            self.subreddit, self.id
+        )
+    }
+}
+
+impl UserTag {
+    pub const COLORS: [&str; 6] = ["red", "green", "yellow", "blue", "magenta", "cyan"];
+
+    pub fn color(&self) -> Color {
+        Self::parse_color(&self.color)
+    }
+
+    pub fn parse_color(color: &str) -> Color {
+        Color::from_str(color).unwrap_or(Color::Blue)
+    }
+
+    pub fn span(&self) -> Span<'static> {
+        format!(" {} ", self.text).fg(Color::Black).bg(self.color())
+    }
+}
+
+impl StateStore {
+    pub fn load() -> Result<Self, NgoredError> {
+        let path = Self::path();
+        let Ok(data) = fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        toml::from_str(&data).map_err(|source| NgoredError::State { path, source })
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            in_memory: true,
+            ..Self::default()
+        }
+    }
+
+    /// A change that fails to save stays in memory and goes out with the next save
+    pub fn save(&self) -> Result<(), NgoredError> {
+        if self.in_memory {
+            return Ok(());
+        }
+        let path = Self::path();
+        let error = |reason: String| NgoredError::SaveState {
+            path: path.clone(),
+            reason,
+        };
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).map_err(|err| error(err.to_string()))?;
+        }
+        let data = toml::to_string_pretty(self).map_err(|err| error(err.to_string()))?;
+        fs::write(&path, data).map_err(|err| error(err.to_string()))
+    }
+
+    pub fn user_tag(&self, username: &str) -> Option<&UserTag> {
+        self.user_tags.get(&username.to_lowercase())
+    }
+
+    pub fn set_user_tag(
+        &mut self,
+        username: &str,
+        user_tag: Option<UserTag>,
+    ) -> Result<(), NgoredError> {
+        let username = username.to_lowercase();
+        match user_tag {
+            Some(user_tag) => self.user_tags.insert(username, user_tag),
+            None => self.user_tags.remove(&username),
+        };
+        self.save()
+    }
+
+    /// Record a visit to the post now and return the previous visit
+    pub fn visit_post(&mut self, post_id: &str) -> Result<Option<DateTime<Utc>>, NgoredError> {
+        let now = Utc::now();
+        // Forget old visits so the state file doesn't grow forever
+        self.last_visits
+            .retain(|_, v| now - *v < TimeDelta::days(30));
+        let last_visit = self.last_visits.insert(post_id.to_string(), now);
+        // Threads not visited in a while are forgotten along with their visit
+        self.collapsed_comments
+            .retain(|k, _| self.last_visits.contains_key(k));
+        self.save()?;
+        Ok(last_visit)
+    }
+
+    pub fn collapsed_comments(&self, post_id: &str) -> HashSet<String> {
+        self.collapsed_comments
+            .get(post_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_collapsed_comments(
+        &mut self,
+        post_id: &str,
+        comment_ids: &HashSet<String>,
+    ) -> Result<(), NgoredError> {
+        if comment_ids.is_empty() {
+            self.collapsed_comments.remove(post_id);
+        } else {
+            self.collapsed_comments
+                .insert(post_id.to_string(), comment_ids.clone());
+        }
+        self.save()
+    }
+
+    /// Queue `post` at the end of the read later queue, `false` when it's already queued
+    pub fn push_read_later(&mut self, post: ReadLaterPost) -> Result<bool, NgoredError> {
+        if self.read_later.iter().any(|v| v.id == post.id) {
+            return Ok(false);
+        }
+        self.read_later.push(post);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Take the oldest post out of the read later queue, it's put back when that can't be saved
+    pub fn pop_read_later(&mut self) -> Result<Option<ReadLaterPost>, NgoredError> {
+        if self.read_later.is_empty() {
+            return Ok(None);
+        }
+        let post = self.read_later.remove(0);
+        if let Err(err) = self.save() {
+            self.read_later.insert(0, post);
+            return Err(err);
+        }
+        Ok(Some(post))
+    }
+
+    fn path() -> PathBuf {
+        let home = dirs::home_dir().expect("Could not find home directory");
+        home.join(".local")
+            .join("share")
+            .join("ngored")
+            .join("state.toml")
+    }
+}
@@ -0,0 +1,386 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use textwrap::core::display_width;
+use unicode_bidi::BidiInfo;
+
+use crate::config::Config;
+
+/// Texts of every post and comment seen go in a `WrapCache`, it starts over past this many
+const WRAP_CACHE_LIMIT: usize = 8192;
+
+/// Wrapping preferences from the config, held by whatever wraps text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WrapOptions {
+    /// Wrapped lines of list items and quotes stay under their text
+    pub hanging_indent: bool,
+    /// Words break at their hyphens to fill lines
+    pub hyphenate: bool,
+}
+
+impl WrapOptions {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            hanging_indent: config.hanging_indent.unwrap_or(true),
+            hyphenate: config.hyphenate.unwrap_or(true),
+        }
+    }
+}
+
+impl Default for WrapOptions {
+    fn default() -> Self {
+        Self {
+            hanging_indent: true,
+            hyphenate: true,
+        }
+    }
+}
+
+/// Width of `text` in terminal cells, use this rather than `len()` for layout since emoji and
+/// CJK take two cells and multi-byte glyphs aren't one cell per byte
+pub fn width(text: &str) -> u16 {
+    display_width(text).try_into().unwrap_or(u16::MAX)
+}
+
+/// Wrap `text` into lines at most `width` terminal cells wide, wide glyphs like CJK and emoji
+/// take two cells. Lines are returned in display order so RTL text reads correctly
+pub fn wrap(text: &str, width: u16, options: WrapOptions) -> Vec<String> {
+    let width = (width as usize).max(1);
+    let splitter = if options.hyphenate {
+        textwrap::WordSplitter::HyphenSplitter
+    } else {
+        textwrap::WordSplitter::NoHyphenation
+    };
+    text.split('\n')
+        .flat_map(|line| {
+            let indent = if options.hanging_indent {
+                continuation_indent(line)
+            } else {
+                String::default()
+            };
+            // Deep indents on narrow widths would leave no room for the text
+            let indent = if display_width(&indent) * 2 > width {
+                String::default()
+            } else {
+                indent
+            };
+            let options = textwrap::Options::new(width)
+                .word_splitter(splitter.clone())
+                .subsequent_indent(&indent);
+            textwrap::wrap(line, options)
+                .into_iter()
+                .map(|v| bidi_reorder(&v))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// What the wrapped lines of `line` start with so they stay under it: the `>` markers of a
+/// quote, or blanks as wide as a list item's bullet or number
+fn continuation_indent(line: &str) -> String {
+    let mut after_quote = line;
+    while let Some(rest) = strip_quote_marker(after_quote) {
+        after_quote = rest;
+    }
+    if after_quote.len() < line.len() {
+        return line[..line.len() - after_quote.len()].to_string();
+    }
+    let content = line.trim_start_matches(' ');
+    let leading = line.len() - content.len();
+    let digits = content.chars().take_while(char::is_ascii_digit).count();
+    let marker = if content.starts_with(['-', '*', '+']) {
+        1
+    } else if digits > 0 && content[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else {
+        return String::default();
+    };
+    let spaces = content[marker..].len() - content[marker..].trim_start_matches(' ').len();
+    if spaces == 0 {
+        return String::default();
+    }
+    " ".repeat(leading + marker + spaces)
+}
+
+/// `line` after its first `>` quote marker and the spaces around it, `>!` starts a spoiler
+/// rather than a quote
+pub fn strip_quote_marker(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches(' ').strip_prefix('>')?;
+    if rest.starts_with('!') {
+        return None;
+    }
+    Some(rest.trim_start_matches(' '))
+}
+
+/// `text` with the content of its `>!spoilers!<` replaced by a placeholder
+pub fn hide_spoilers(text: &str) -> String {
+    let mut hidden = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(">!")
+        && let Some(end) = rest[start + 2..].find("!<")
+    {
+        hidden.push_str(&rest[..start]);
+        hidden.push_str("[spoiler]");
+        rest = &rest[start + 2 + end + 2..];
+    }
+    hidden.push_str(rest);
+    hidden
+}
+
+/// `wrap` with the component's options for text drawn on every frame like titles and bodies,
+/// lines are reused until `clear`. Filled while drawing, which only borrows the component's state
+#[derive(Debug, Default)]
+pub struct WrapCache {
+    options: WrapOptions,
+    /// By width then text
+    lines: RefCell<HashMap<u16, HashMap<String, Vec<String>>>>,
+}
+
+impl WrapCache {
+    pub fn new(options: WrapOptions) -> Self {
+        Self {
+            options,
+            lines: RefCell::default(),
+        }
+    }
+
+    pub fn options(&self) -> WrapOptions {
+        self.options
+    }
+
+    pub fn wrap(&self, text: &str, width: u16) -> Vec<String> {
+        let mut cache = self.lines.borrow_mut();
+        if let Some(lines) = cache.get(&width).and_then(|v| v.get(text)) {
+            return lines.clone();
+        }
+        if cache.values().map(HashMap::len).sum::<usize>() >= WRAP_CACHE_LIMIT {
+            cache.clear();
+        }
+        let lines = wrap(text, width, self.options);
+        cache
+            .entry(width)
+            .or_default()
+            .insert(text.to_string(), lines.clone());
+        lines
+    }
+
+    /// Called on resize since the old widths won't be asked for again
+    pub fn clear(&mut self) {
+        self.lines.get_mut().clear();
+    }
+}
+
+/// Urls in `text`, bare or the target of a markdown link, and the `r/sub` and `u/user` mentioned
+/// outside of them, in the order they appear
+pub fn links(text: &str) -> Vec<&str> {
+    let mut urls = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("http") {
+        let candidate = &rest[start..];
+        let end = if candidate.starts_with("https://") || candidate.starts_with("http://") {
+            let end = url_len(candidate);
+            let offset = text.len() - candidate.len();
+            urls.push((offset, &candidate[..end]));
+            end
+        } else {
+            "http".len()
+        };
+        rest = &candidate[end..];
+    }
+    let mentions = mentions(text)
+        .into_iter()
+        .filter(|(start, _)| {
+            !urls
+                .iter()
+                .any(|(url_start, url)| (*url_start..url_start + url.len()).contains(start))
+        })
+        .collect::<Vec<_>>();
+    let mut links = urls.into_iter().chain(mentions).collect::<Vec<_>>();
+    links.sort_by_key(|(start, _)| *start);
+    links.into_iter().map(|(_, v)| v).collect()
+}
+
+/// Where `r/sub` and `u/user` are mentioned in `text`, with or without a leading `/`, and the
+/// mention without it. The `r` or `u` has to start a word so paths like `reddit.com/r/sub` aren't
+/// taken
+pub fn mentions(text: &str) -> Vec<(usize, &str)> {
+    let mut mentions = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = text[start..].find('/') {
+        let slash = start + offset;
+        start = slash + 1;
+        let (name_chars, name_lens) = match &text[..slash] {
+            // Sub names are 2 to 21 characters, usernames 3 to 20 and can have dashes, a longer
+            // run is something else
+            v if v.ends_with('r') => ("_", 2..=21),
+            v if v.ends_with('u') => ("_-", 3..=20),
+            _ => continue,
+        };
+        let at = slash - 1;
+        let before = text[..at].strip_suffix('/').unwrap_or(&text[..at]);
+        if before
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '/' | '.' | '-' | '_'))
+        {
+            continue;
+        }
+        let name_len = text[start..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && !name_chars.contains(c))
+            .unwrap_or(text.len() - start);
+        if name_lens.contains(&name_len) {
+            mentions.push((at, &text[at..start + name_len]));
+            start += name_len;
+        }
+    }
+    mentions
+}
+
+/// Name of the sub a link from `links` is a mention of
+pub fn mentioned_sub(link: &str) -> Option<&str> {
+    link.strip_prefix("r/")
+}
+
+/// Name of the user a link from `links` is a mention of
+pub fn mentioned_user(link: &str) -> Option<&str> {
+    link.strip_prefix("u/")
+}
+
+/// Length of the url `text` starts with. Parentheses are kept when balanced, like in wikipedia
+/// links, so the one closing a markdown link isn't taken
+fn url_len(text: &str) -> usize {
+    let mut depth = 0;
+    let mut end = text.len();
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ')' | '<' | '>' | '"' | '[' | ']' => {
+                end = index;
+                break;
+            }
+            c if c.is_whitespace() => {
+                end = index;
+                break;
+            }
+            _ => {}
+        }
+    }
+    text[..end]
+        .trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '*'])
+        .len()
+}
+
+/// Reorder a single line from logical to visual order with the unicode bidi algorithm
+fn bidi_reorder(line: &str) -> String {
+    let bidi_info = BidiInfo::new(line, None);
+    if !bidi_info.has_rtl() {
+        return line.to_string();
+    }
+    bidi_info
+        .paragraphs
+        .iter()
+        .map(|paragraph| bidi_info.reorder_line(paragraph, paragraph.range.clone()))
+        .collect()
+}
+
+/// Host of `url` without a leading `www.`, like "github.com"
+pub fn domain(url: &str) -> &str {
+    let host = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    host.strip_prefix("www.").unwrap_or(host)
+}
+
+/// Shorten `line` to end with "..." while staying within `width` cells
+pub fn ellipsize(line: &str, width: u16) -> String {
+    let width = (width as usize).saturating_sub(3);
+    let mut line = line.to_string();
+    while display_width(&line) > width {
+        line.pop();
+    }
+    line.push_str("...");
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mentions_take_r_and_u_with_or_without_a_slash() {
+        assert_eq!(
+            mentions("see r/rust and /r/learnrust, ask u/ferris or /u/big-crab"),
+            [
+                (4, "r/rust"),
+                (16, "r/learnrust"),
+                (33, "u/ferris"),
+                (46, "u/big-crab")
+            ]
+        );
+    }
+
+    #[test]
+    fn mentions_skip_paths_of_urls() {
+        assert_eq!(mentions("https://reddit.com/r/rust"), []);
+        assert_eq!(mentions("www.reddit.com/u/ferris"), []);
+        assert_eq!(mentions("old.reddit.com/r/rust/comments"), []);
+        assert_eq!(mentions("attr/rust and bar/u/ferris"), []);
+    }
+
+    #[test]
+    fn mentions_need_a_name_of_the_right_length() {
+        assert_eq!(
+            mentions("r/a u/ab r/this_name_is_way_too_long_for_a_sub"),
+            []
+        );
+        assert_eq!(mentions("(r/rust)"), [(1, "r/rust")]);
+        assert_eq!(mentions("r/rust."), [(0, "r/rust")]);
+    }
+
+    #[test]
+    fn links_take_mentions_outside_of_urls_once() {
+        assert_eq!(
+            links("r/rust, https://reddit.com/r/rust and [u/ferris](https://example.com)"),
+            [
+                "r/rust",
+                "https://reddit.com/r/rust",
+                "u/ferris",
+                "https://example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_keeps_list_items_under_their_text_with_hanging_indent() {
+        let item = "- a list item long enough to wrap";
+        assert_eq!(
+            wrap(item, 16, WrapOptions::default()),
+            ["- a list item", "  long enough to", "  wrap"]
+        );
+        let flush = WrapOptions {
+            hanging_indent: false,
+            ..WrapOptions::default()
+        };
+        assert_eq!(
+            wrap(item, 16, flush),
+            ["- a list item", "long enough to", "wrap"]
+        );
+    }
+
+    #[test]
+    fn wrap_breaks_at_hyphens_only_when_hyphenating() {
+        let text = "a well-known fact";
+        assert_eq!(
+            wrap(text, 10, WrapOptions::default()),
+            ["a well-", "known fact"]
+        );
+        let whole_words = WrapOptions {
+            hyphenate: false,
+            ..WrapOptions::default()
+        };
+        assert_eq!(wrap(text, 10, whole_words), ["a", "well-known", "fact"]);
+    }
+}
@@ -0,0 +1,331 @@
+use std::{collections::HashMap, str::FromStr};
+
+use ratatui::{
+    style::{Color, Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span},
+};
+
+use crate::{
+    config::{Config, SelectionIndicator, SubTheme, ThemeName},
+    text,
+};
+
+/// Accent colors of the subs that have one in `sub_themes`, keyed by the lowercased sub
+#[derive(Debug, Clone, Default)]
+pub struct SubAccents(HashMap<String, Color>);
+
+impl SubAccents {
+    /// Colors that don't parse are ignored, the sub keeps the default look
+    pub fn new(config: &Config) -> Self {
+        Self(
+            config
+                .sub_themes
+                .iter()
+                .filter_map(|(sub, theme)| Self::accent_of(sub, theme))
+                .collect(),
+        )
+    }
+
+    fn accent_of(sub: &str, theme: &SubTheme) -> Option<(String, Color)> {
+        let accent = Color::from_str(theme.accent.as_deref()?).ok()?;
+        let sub = sub.strip_prefix("r/").unwrap_or(sub);
+        Some((sub.to_lowercase(), accent))
+    }
+
+    /// Accent color configured for `sub`
+    pub fn accent(&self, sub: &str) -> Option<Color> {
+        self.0.get(&sub.to_lowercase()).copied()
+    }
+
+    /// Borders of the screens showing `sub`, in its accent color if it has one
+    pub fn border_style(&self, sub: &str) -> Style {
+        self.accent(sub)
+            .map_or_else(Style::new, |v| Style::new().fg(v))
+    }
+}
+
+/// Display preferences from the config, copied into the components and popups that draw with
+/// them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Theme {
+    name: ThemeName,
+    selection_indicator: Option<SelectionIndicator>,
+}
+
+impl Theme {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            name: config.theme.unwrap_or(ThemeName::Default),
+            selection_indicator: config.selection_indicator,
+        }
+    }
+
+    fn high_contrast(&self) -> bool {
+        self.name == ThemeName::HighContrast
+    }
+
+    /// Style of the selected row in lists and post items
+    pub fn selected_style(&self) -> Style {
+        let style = Style::new().add_modifier(Modifier::BOLD);
+        match (self.selection_indicator, self.high_contrast()) {
+            (None | Some(SelectionIndicator::Background), false) => style.bg(Color::DarkGray),
+            (None | Some(SelectionIndicator::Background), true) => {
+                style.fg(Color::Black).bg(Color::White)
+            }
+            (Some(SelectionIndicator::Bold), true) => style.add_modifier(Modifier::UNDERLINED),
+            (Some(SelectionIndicator::Bold | SelectionIndicator::Gutter), _) => style,
+        }
+    }
+
+    /// Style of the selected comment, green text unless an indicator is configured
+    pub fn selected_comment_style(&self) -> Style {
+        match (self.selection_indicator, self.high_contrast()) {
+            (None, false) => Style::new().fg(Color::Green),
+            _ => self.selected_style(),
+        }
+    }
+
+    /// Marker in front of the selected row of lists
+    pub fn highlight_symbol(&self) -> &'static str {
+        match self.selection_indicator {
+            Some(SelectionIndicator::Gutter) => "> ",
+            _ => "",
+        }
+    }
+
+    /// Border of selected bordered items, the left edge becomes the `>` gutter
+    pub fn selected_border_set(&self, set: border::Set) -> border::Set {
+        match self.selection_indicator {
+            Some(SelectionIndicator::Gutter) => border::Set {
+                vertical_left: ">",
+                ..set
+            },
+            _ => set,
+        }
+    }
+
+    /// Secondary text like placeholders and empty bars
+    pub fn muted(&self) -> Color {
+        if self.high_contrast() {
+            Color::Gray
+        } else {
+            Color::DarkGray
+        }
+    }
+
+    /// The wrapped lines of a body with `highlight` and its inline markup. A line quoted with `>`
+    /// gets a colored bar per nesting level in place of the markers and dimmed text,
+    /// `~~strikethrough~~` is crossed out, `^superscript` or `^(superscript)` is italic after a
+    /// muted `^` and `code` takes `code_style`. `>!spoilers!<` are blacked out unless
+    /// `reveal_spoilers`
+    pub fn body_lines(
+        &self,
+        lines: Vec<String>,
+        query: Option<&str>,
+        reveal_spoilers: bool,
+    ) -> Vec<Line<'static>> {
+        let (bars, contents): (Vec<_>, Vec<_>) = lines.iter().map(|v| self.quote_bars(v)).unzip();
+        inline_markup(&contents.join("\n"), reveal_spoilers, self.muted())
+            .into_iter()
+            .zip(bars)
+            .map(|(runs, bars)| {
+                let quoted = !bars.is_empty();
+                let spans = runs.into_iter().flat_map(|(text, style)| {
+                    let style = if quoted {
+                        style.add_modifier(Modifier::DIM)
+                    } else {
+                        style
+                    };
+                    highlight(text, query)
+                        .into_iter()
+                        .map(move |v| v.patch_style(style))
+                });
+                Line::from(bars.into_iter().chain(spans).collect::<Vec<_>>())
+            })
+            .collect()
+    }
+
+    /// The bars for the `>` markers starting `line` and the text after them
+    fn quote_bars<'a>(&self, line: &'a str) -> (Vec<Span<'static>>, &'a str) {
+        let mut bars = Vec::new();
+        let mut rest = line;
+        while let Some(quoted) = text::strip_quote_marker(rest) {
+            // The bar takes the width of the marker and its spaces so wrapping stays the same
+            let width = rest.len() - quoted.len();
+            let color = if self.high_contrast() {
+                Color::White
+            } else {
+                QUOTE_COLORS[bars.len() % QUOTE_COLORS.len()]
+            };
+            bars.push(format!("▎{}", " ".repeat(width - 1)).fg(color));
+            rest = quoted;
+        }
+        (bars, rest)
+    }
+}
+
+/// Split `text` into spans with occurrences of the lowercased `query` highlighted
+pub fn highlight(text: String, query: Option<&str>) -> Vec<Span<'static>> {
+    let Some(query) = query.filter(|v| !v.is_empty()) else {
+        return vec![Span::raw(text)];
+    };
+    let lowercase = text.to_lowercase();
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for (start, _) in lowercase.match_indices(query) {
+        let end = start + query.len();
+        // Lowercasing can shift byte offsets for some scripts, fall back to no highlight
+        let (Some(before), Some(matched)) = (text.get(last..start), text.get(start..end)) else {
+            return vec![Span::raw(text)];
+        };
+        spans.push(Span::raw(before.to_string()));
+        spans.push(matched.to_string().fg(Color::Black).bg(Color::Yellow));
+        last = end;
+    }
+    match text.get(last..) {
+        Some(rest) => spans.push(Span::raw(rest.to_string())),
+        None => return vec![Span::raw(text)],
+    }
+    spans
+}
+
+/// Bar colors of quotes, by nesting depth
+const QUOTE_COLORS: [Color; 4] = [Color::Blue, Color::Magenta, Color::Cyan, Color::Green];
+
+/// Style of `inline code`, and of code blocks
+pub fn code_style() -> Style {
+    Style::new().fg(Color::LightYellow).bg(Color::Black)
+}
+
+/// Inline markup open at a point of a body
+#[derive(Default)]
+struct Markup {
+    strikethrough: bool,
+    spoiler: bool,
+    /// Whether the superscript ends at a `)` rather than at a space
+    superscript: Option<bool>,
+    /// Backticks the code span was opened with
+    code: Option<usize>,
+}
+
+impl Markup {
+    fn style(&self, reveal_spoilers: bool, muted: Color) -> Style {
+        let mut style = match (self.spoiler, reveal_spoilers) {
+            (true, true) => Style::new().underlined(),
+            (true, false) => Style::new().fg(muted),
+            (false, _) => Style::new(),
+        };
+        if self.strikethrough {
+            style = style.crossed_out();
+        }
+        if self.superscript.is_some() {
+            style = style.italic();
+        }
+        if self.code.is_some() {
+            style = style.patch(code_style());
+        }
+        style
+    }
+}
+
+/// Split `text` into lines of styled runs for its inline markup. Markers are only taken when
+/// they are closed in the same paragraph, a markup can go on across wrapped lines. Hidden spoilers
+/// and the `^` of superscripts take `muted`
+fn inline_markup(text: &str, reveal_spoilers: bool, muted: Color) -> Vec<Vec<(String, Style)>> {
+    let mut lines = vec![Vec::new()];
+    let mut run = String::new();
+    let mut markup = Markup::default();
+    let closed = |rest: &str, marker: &str| {
+        let paragraph = rest.split("\n\n").next().unwrap_or_default();
+        paragraph.contains(marker)
+    };
+    let backticks = |from: usize| text[from..].chars().take_while(|v| *v == '`').count();
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|(_, v)| *v);
+        let current = markup.style(reveal_spoilers, muted);
+        let hidden = markup.spoiler && !reveal_spoilers;
+        match (c, next) {
+            ('\n', _) => {
+                push_run(&mut lines, &mut run, current);
+                lines.push(Vec::new());
+                if markup.superscript == Some(false) {
+                    markup.superscript = None;
+                }
+            }
+            ('`', _) if markup.code.is_some_and(|v| v == backticks(i)) => {
+                push_run(&mut lines, &mut run, current);
+                for _ in 1..markup.code.take().unwrap_or(1) {
+                    chars.next();
+                }
+            }
+            ('!', Some('<')) if markup.spoiler && markup.code.is_none() => {
+                push_run(&mut lines, &mut run, current);
+                markup.spoiler = false;
+                chars.next();
+            }
+            (c, _) if hidden => {
+                // Blocks as wide as the text so the wrapping is the same once revealed
+                run.push_str(&"█".repeat(text::width(&c.to_string()) as usize));
+            }
+            (c, _) if markup.code.is_some() => run.push(c),
+            ('\\', Some(v @ ('~' | '^' | ')' | '`'))) => {
+                run.push(v);
+                chars.next();
+            }
+            (c, _) if c.is_whitespace() && markup.superscript == Some(false) => {
+                push_run(&mut lines, &mut run, current);
+                markup.superscript = None;
+                run.push(c);
+            }
+            (')', _) if markup.superscript == Some(true) => {
+                push_run(&mut lines, &mut run, current);
+                markup.superscript = None;
+            }
+            ('`', _) if closed(&text[i + backticks(i)..], &"`".repeat(backticks(i))) => {
+                push_run(&mut lines, &mut run, current);
+                let count = backticks(i);
+                for _ in 1..count {
+                    chars.next();
+                }
+                markup.code = Some(count);
+            }
+            ('>', Some('!')) if !markup.spoiler && closed(&text[i + 2..], "!<") => {
+                push_run(&mut lines, &mut run, current);
+                markup.spoiler = true;
+                chars.next();
+            }
+            ('~', Some('~')) if markup.strikethrough || closed(&text[i + 2..], "~~") => {
+                push_run(&mut lines, &mut run, current);
+                markup.strikethrough = !markup.strikethrough;
+                chars.next();
+            }
+            ('^', Some(v)) if markup.superscript.is_none() && !v.is_whitespace() => {
+                let parenthesized = v == '(';
+                if parenthesized && !closed(&text[i + 2..], ")") {
+                    run.push(c);
+                    continue;
+                }
+                push_run(&mut lines, &mut run, current);
+                push_run(&mut lines, &mut "^".to_string(), Style::new().fg(muted));
+                markup.superscript = Some(parenthesized);
+                if parenthesized {
+                    chars.next();
+                }
+            }
+            _ => run.push(c),
+        }
+    }
+    push_run(&mut lines, &mut run, markup.style(reveal_spoilers, muted));
+    lines
+}
+
+/// Move `run` to the end of the last line of `lines` if there is any text in it
+fn push_run(lines: &mut [Vec<(String, Style)>], run: &mut String, style: Style) {
+    if !run.is_empty()
+        && let Some(line) = lines.last_mut()
+    {
+        line.push((std::mem::take(run), style));
+    }
+}
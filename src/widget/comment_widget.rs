@@ -3,37 +3,112 @@ use chrono_humanize::HumanTime;
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Stylize},
-    text::Line,
+    symbols::border,
+    text::{Line, Span},
     widgets::{Block, BorderType, Borders, Paragraph, Widget},
 };
 
-use crate::model::comment::Comment;
+use crate::{
+    format,
+    model::comment::Comment,
+    state_store::UserTag,
+    text::{self, WrapOptions},
+    theme::{self, Theme},
+};
 
 pub struct CommentWidget {
     depth: u16,
     body_texts: Vec<String>,
     is_selected: bool,
+    is_new: bool,
     author: String,
+    user_tag: Option<UserTag>,
+    search: Option<String>,
     score: i64,
+    score_hidden: bool,
+    compact_scores: bool,
     created: DateTime<Utc>,
+    edited: Option<DateTime<Utc>>,
+    /// What `created` and `edited` are shown relative to
+    now: DateTime<Utc>,
+    locked: bool,
+    approved: bool,
+    removed: bool,
+    reveal_spoilers: bool,
+    theme: Theme,
 }
 
 impl CommentWidget {
-    pub fn new(depth: u16, comment: Comment, is_selected: bool, container_width: u16) -> Self {
-        let width = container_width - depth * 2;
-        let text_wrap = textwrap::wrap(&comment.body, textwrap::Options::new(width as usize));
+    /// `body_texts` comes from `wrap_body`, wrapping is left to the caller so it can be cached
+    pub fn new(
+        depth: u16,
+        comment: &Comment,
+        body_texts: Vec<String>,
+        user_tag: Option<UserTag>,
+        search: Option<&str>,
+        is_selected: bool,
+        is_new: bool,
+    ) -> Self {
         Self {
-            depth: depth,
-            body_texts: text_wrap.into_iter().map(|v| v.into_owned()).collect(),
-            is_selected: is_selected,
+            depth,
+            body_texts,
+            is_selected,
+            is_new,
             author: comment.author.clone(),
+            user_tag,
+            search: search.map(str::to_lowercase),
             score: comment.score,
+            score_hidden: comment.score_hidden,
+            compact_scores: true,
             created: comment.created_at,
+            edited: comment.edited_at,
+            now: Utc::now(),
+            locked: comment.locked,
+            approved: comment.approved,
+            removed: comment.removed,
+            reveal_spoilers: false,
+            theme: Theme::default(),
+        }
+    }
+
+    /// Show the content of the comment's spoilers instead of blacking it out
+    pub fn reveal_spoilers(self, reveal_spoilers: bool) -> Self {
+        Self {
+            reveal_spoilers,
+            ..self
         }
     }
 
-    pub fn height(&self) -> usize {
-        self.body_texts.len() + 2
+    pub fn theme(self, theme: Theme) -> Self {
+        Self { theme, ..self }
+    }
+
+    pub fn now(self, now: DateTime<Utc>) -> Self {
+        Self { now, ..self }
+    }
+
+    /// Show scores in full rather than as `12.3k`
+    pub fn compact_scores(self, compact_scores: bool) -> Self {
+        Self {
+            compact_scores,
+            ..self
+        }
+    }
+
+    pub fn wrap_body(
+        depth: u16,
+        body: &str,
+        container_width: u16,
+        options: WrapOptions,
+    ) -> Vec<String> {
+        // - 1 for the left border
+        let width = container_width.saturating_sub(depth * 2 + 1);
+        text::wrap(body, width, options)
+    }
+
+    /// Height of a comment whose body wraps into `body_lines` lines, with its title and border
+    pub fn height_for(body_lines: usize) -> usize {
+        body_lines + 2
     }
 }
 
@@ -45,21 +120,92 @@ impl Widget for CommentWidget {
         let [_, area] =
             Layout::horizontal([Constraint::Length(self.depth * 2), Constraint::Fill(1)])
                 .areas(area);
-        let lines: Vec<Line> = self.body_texts.into_iter().map(|t| Line::from(t)).collect();
-        let mut item = Paragraph::new(lines).block(
-            Block::new()
-                .borders(Borders::LEFT | Borders::BOTTOM)
-                .border_type(BorderType::Rounded)
-                // .title(self.author.bold())
-                .title(Line::from(vec![
-                    self.author.bold(),
-                    format!(" • {}", HumanTime::from(self.created - Utc::now())).italic(),
-                ]))
-                .title_bottom(format!("👍🏻{}", self.score)),
-        );
+        let search = self.search.as_deref();
+        let lines = self
+            .theme
+            .body_lines(self.body_texts, search, self.reveal_spoilers);
+        let mut title: Vec<Span> = theme::highlight(self.author, search)
+            .into_iter()
+            .map(|v| {
+                if self.is_new {
+                    v.bold().fg(Color::Cyan)
+                } else {
+                    v.bold()
+                }
+            })
+            .collect();
+        if let Some(user_tag) = &self.user_tag {
+            title.push(" ".into());
+            title.push(user_tag.span());
+        }
+        title.push(format!(" • {}", HumanTime::from(self.created - self.now)).italic());
+        if let Some(edited) = self.edited {
+            title.push(format!("* (edited {})", HumanTime::from(edited - self.now)).italic());
+        }
+        if self.is_new {
+            title.push(" • new".fg(Color::Cyan));
+        }
+        if self.approved {
+            title.push(" • ✔ approved".fg(Color::Green));
+        }
+        if self.removed {
+            title.push(" • ✖ removed".fg(Color::Red));
+        }
+        if self.locked {
+            title.push(" • 🔒 locked".fg(Color::Yellow));
+        }
+        let score = if self.score_hidden {
+            "•".to_string()
+        } else {
+            format::score(self.score, self.compact_scores)
+        };
+        let mut block = Block::new()
+            .borders(Borders::LEFT | Borders::BOTTOM)
+            .border_type(BorderType::Rounded)
+            // .title(self.author.bold())
+            .title(Line::from(title))
+            .title_bottom(format!("👍🏻{}", score));
         if self.is_selected {
-            item = item.fg(Color::Green);
+            block = block.border_set(self.theme.selected_border_set(border::ROUNDED));
+        }
+        let mut item = Paragraph::new(lines).block(block);
+        if self.is_selected {
+            item = item.style(self.theme.selected_comment_style());
         }
         item.render(area, buf);
     }
 }
+
+/// Placeholder for replies hidden by the comment depth limit, or by collapsing their parent
+pub struct ContinueWidget {
+    depth: u16,
+    count: usize,
+    collapsed: bool,
+}
+
+impl ContinueWidget {
+    pub const HEIGHT: u16 = 1;
+
+    pub fn new(depth: u16, count: usize, collapsed: bool) -> Self {
+        Self {
+            depth,
+            count,
+            collapsed,
+        }
+    }
+}
+
+impl Widget for ContinueWidget {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer) {
+        let [_, area] =
+            Layout::horizontal([Constraint::Length(self.depth * 2), Constraint::Fill(1)])
+                .areas(area);
+        let replies = if self.count == 1 { "reply" } else { "replies" };
+        let text = if self.collapsed {
+            format!("[+] {} {} collapsed [Enter]", self.count, replies)
+        } else {
+            format!("[+] continue ({} more {}) [Enter]", self.count, replies)
+        };
+        Line::from(text).fg(Color::Blue).render(area, buf);
+    }
+}
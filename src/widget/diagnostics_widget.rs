@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Widget},
+};
+
+use crate::diagnostics;
+
+/// Overlay in the top right corner with what the app is busy with
+pub struct DiagnosticsWidget {
+    frame_time: Duration,
+    alive_tasks: usize,
+}
+
+impl DiagnosticsWidget {
+    const WIDTH: u16 = 34;
+
+    pub fn new(frame_time: Duration) -> Self {
+        Self {
+            frame_time,
+            alive_tasks: tokio::runtime::Handle::current()
+                .metrics()
+                .num_alive_tasks(),
+        }
+    }
+}
+
+impl Widget for DiagnosticsWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let diagnostics = diagnostics::counters();
+        let hit_rate = diagnostics
+            .protocol_hit_rate
+            .map(|v| format!("{:.0}%", v * 100.0))
+            .unwrap_or_else(|| "-".to_string());
+        let lines = vec![
+            Line::from(format!(
+                "frame       {:.1} ms",
+                self.frame_time.as_secs_f64() * 1000.0
+            )),
+            Line::from(format!("tasks       {}", self.alive_tasks)),
+            Line::from(format!(
+                "http        {} in flight",
+                diagnostics.http_in_flight
+            )),
+            Line::from(format!("image cache {} hits", hit_rate)),
+            Line::from(format!(
+                "images      {} • {:.1} MB",
+                diagnostics.protocols,
+                diagnostics.protocol_bytes as f64 / 1_000_000.0
+            )),
+        ];
+
+        let [area] = Layout::horizontal([Constraint::Length(Self::WIDTH)])
+            .flex(Flex::End)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)]).areas(area);
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("diagnostics".italic()),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,82 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph, Widget},
+};
+
+use crate::{model::embed::Embed, text, theme::Theme};
+
+/// Box around the preview of a link post saying where the link goes, the preview is drawn in
+/// `image_area` by the caller
+pub struct LinkCardWidget<'a> {
+    url: &'a str,
+    embed: Option<&'a Embed>,
+    theme: Theme,
+}
+
+impl<'a> LinkCardWidget<'a> {
+    pub const EXTRA_WIDTH: u16 = 2;
+
+    pub fn new(url: &'a str, embed: Option<&'a Embed>, theme: Theme) -> Self {
+        Self { url, embed, theme }
+    }
+
+    /// Border and the lines above the preview
+    pub fn extra_height(&self) -> u16 {
+        2 + self.header_lines().len() as u16
+    }
+
+    /// Where the preview goes inside the card drawn in `area`
+    pub fn image_area(&self, area: Rect) -> Rect {
+        let inner = Block::bordered().inner(area);
+        let header_height = self.header_lines().len() as u16;
+        Rect {
+            y: inner.y + header_height,
+            height: inner.height.saturating_sub(header_height),
+            ..inner
+        }
+    }
+
+    /// The embed's title and byline if the site described it, then the url
+    fn header_lines(&self) -> Vec<(String, Style)> {
+        let mut lines = Vec::new();
+        if let Some(embed) = self.embed {
+            lines.push((embed.title.clone(), Style::new().bold()));
+            if let Some(byline) = embed.byline() {
+                lines.push((byline, Style::new().fg(self.theme.muted())));
+            }
+        }
+        lines.push((
+            self.url.to_string(),
+            Style::new().underlined().fg(self.theme.muted()),
+        ));
+        lines
+    }
+}
+
+impl Widget for LinkCardWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let keys = if self.embed.is_some() {
+            "[v] play • [o] share"
+        } else {
+            "[o] share"
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(format!("🔗 {}", text::domain(self.url)).italic())
+            .title_bottom(Line::from(keys).right_aligned());
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let lines = self.header_lines().into_iter().map(|(line, style)| {
+            let line = if text::width(&line) > inner.width {
+                text::ellipsize(&line, inner.width)
+            } else {
+                line
+            };
+            Line::styled(line, style)
+        });
+        Paragraph::new(lines.collect::<Vec<_>>()).render(inner, buf);
+    }
+}
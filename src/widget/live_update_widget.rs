@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use ratatui::{
+    style::{Modifier, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Paragraph, Widget},
+};
+
+use crate::{
+    model::live_thread::LiveUpdate,
+    text::{self, WrapOptions},
+};
+
+pub struct LiveUpdateWidget {
+    body_texts: Vec<String>,
+    author: String,
+    created: DateTime<Utc>,
+    /// What `created` is shown relative to
+    now: DateTime<Utc>,
+    stricken: bool,
+}
+
+impl LiveUpdateWidget {
+    pub fn new(
+        update: &LiveUpdate,
+        container_width: u16,
+        now: DateTime<Utc>,
+        wrap_options: WrapOptions,
+    ) -> Self {
+        let width = container_width.saturating_sub(1);
+        Self {
+            body_texts: text::wrap(&update.body, width, wrap_options),
+            author: update.author.clone(),
+            created: update.created_at,
+            now,
+            stricken: update.stricken,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.body_texts.len() + 2
+    }
+}
+
+impl Widget for LiveUpdateWidget {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let lines: Vec<Line> = self.body_texts.into_iter().map(Line::from).collect();
+        let mut item = Paragraph::new(lines).block(
+            Block::new()
+                .borders(Borders::LEFT | Borders::BOTTOM)
+                .border_type(BorderType::Rounded)
+                .title(Line::from(vec![
+                    format!("u/{}", self.author).bold(),
+                    format!(" • {}", HumanTime::from(self.created - self.now)).italic(),
+                ])),
+        );
+        if self.stricken {
+            item = item.add_modifier(Modifier::CROSSED_OUT).dim();
+        }
+        item.render(area, buf);
+    }
+}
@@ -0,0 +1,190 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Paragraph, StatefulWidget, Widget},
+};
+use std::sync::Arc;
+
+use image::DynamicImage;
+use ratatui_image::{Resize, StatefulImage, picker::Picker, protocol::StatefulProtocol};
+
+use crate::{config::Config, diagnostics, model::image::ImageRef, theme::Theme};
+
+/// How images are fitted and colored, from the config
+#[derive(Clone)]
+pub struct ImageOptions {
+    pub resize: Resize,
+    /// Leave sixel images to the encoder's dithering instead of mapping them to a palette
+    pub dither: bool,
+}
+
+impl ImageOptions {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            resize: config.image_resize(),
+            dither: config.image_dithering.unwrap_or(true),
+        }
+    }
+}
+
+/// A decoded image, its protocol (holding the encoded buffers) is only kept while it's drawn
+pub struct LoadedImage {
+    image: ImageRef,
+    source: DynamicImage,
+    picker: Arc<Picker>,
+    resize: Resize,
+    protocol: Option<StatefulProtocol>,
+    /// Last `size_for` area and result, so a released image can be laid out without rebuilding
+    size: Option<(Rect, Rect)>,
+    /// Clockwise quarter turns `source` was rotated by
+    quarter_turns: u8,
+}
+
+impl LoadedImage {
+    pub fn new(image: ImageRef, source: DynamicImage, picker: Arc<Picker>, resize: Resize) -> Self {
+        Self {
+            image,
+            source,
+            picker,
+            resize,
+            protocol: None,
+            size: None,
+            quarter_turns: 0,
+        }
+    }
+
+    fn protocol(&mut self) -> &mut StatefulProtocol {
+        diagnostics::protocol_lookup(self.protocol.is_some());
+        self.protocol.get_or_insert_with(|| {
+            diagnostics::protocol_created(self.source.as_bytes().len());
+            self.picker.new_resize_protocol(self.source.clone())
+        })
+    }
+
+    fn release(&mut self) {
+        if self.protocol.take().is_some() {
+            diagnostics::protocol_released(self.source.as_bytes().len());
+        }
+    }
+
+    /// Turn the image by `quarter_turns` more clockwise, its protocol and size are rebuilt
+    fn rotate(&mut self, quarter_turns: u8) {
+        self.source = match quarter_turns % 4 {
+            1 => self.source.rotate90(),
+            2 => self.source.rotate180(),
+            3 => self.source.rotate270(),
+            _ => return,
+        };
+        self.quarter_turns = (self.quarter_turns + quarter_turns) % 4;
+        self.release();
+        self.size = None;
+    }
+
+    fn size_for(&mut self, area: Rect) -> Rect {
+        if let Some((cached_area, size)) = self.size
+            && cached_area == area
+        {
+            return size;
+        }
+        let resize = self.resize.clone();
+        let size = self.protocol().size_for(resize, area);
+        self.size = Some((area, size));
+        size
+    }
+}
+
+impl Drop for LoadedImage {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// An image ready to be drawn, or a placeholder when it couldn't be loaded or rendered
+pub enum Media {
+    Image(Box<LoadedImage>),
+    Placeholder { image: ImageRef, reason: String },
+}
+
+impl Media {
+    const PLACEHOLDER_WIDTH: u16 = 40;
+
+    pub fn image(&self) -> &ImageRef {
+        match self {
+            Media::Image(loaded) => &loaded.image,
+            Media::Placeholder { image, .. } => image,
+        }
+    }
+
+    /// Drop the encoded buffers of an image that isn't on screen, it's rebuilt when drawn again
+    pub fn release(&mut self) {
+        if let Media::Image(loaded) = self {
+            loaded.release();
+        }
+    }
+
+    /// Turn the image a quarter clockwise, placeholders stay as they are
+    pub fn rotate(&mut self) {
+        if let Media::Image(loaded) = self {
+            loaded.rotate(1);
+        }
+    }
+
+    /// Undo the rotations of the image
+    pub fn reset_rotation(&mut self) {
+        if let Media::Image(loaded) = self {
+            loaded.rotate(4 - loaded.quarter_turns);
+        }
+    }
+
+    pub fn size_for(&mut self, area: Rect) -> Rect {
+        match self {
+            Media::Image(loaded) => loaded.size_for(area),
+            Media::Placeholder { image, .. } => {
+                // Size, optional caption and reason, inside the border
+                let height = 4 + image.caption.is_some() as u16;
+                Rect::new(0, 0, area.width.min(Self::PLACEHOLDER_WIDTH), height)
+            }
+        }
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer, theme: Theme) {
+        match self {
+            Media::Image(loaded) => {
+                let resize = loaded.resize.clone();
+                let protocol = loaded.protocol();
+                StatefulImage::new()
+                    .resize(resize)
+                    .render(area, buf, protocol);
+                // Encoding failures are only known after rendering, show the placeholder next draw
+                if let Some(Err(err)) = protocol.last_encoding_result() {
+                    *self = Media::Placeholder {
+                        image: loaded.image.clone(),
+                        reason: err.to_string(),
+                    };
+                }
+            }
+            Media::Placeholder { image, reason } => {
+                let block = Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("🖼 image".italic())
+                    .title_bottom("[O] open externally");
+                let size = if image.width == 0 {
+                    "size unknown".to_string()
+                } else {
+                    format!("{}×{}", image.width, image.height)
+                };
+                let mut lines = vec![Line::from(size).bold()];
+                if let Some(caption) = &image.caption {
+                    lines.push(Line::from(caption.clone()).italic());
+                }
+                lines.push(Line::from(reason.clone()).fg(theme.muted()));
+                Paragraph::new(lines)
+                    .centered()
+                    .block(block)
+                    .render(area, buf);
+            }
+        }
+    }
+}
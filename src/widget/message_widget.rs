@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use ratatui::{
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Paragraph, Widget},
+};
+
+use crate::{
+    model::modmail::Message,
+    text::{self, WrapOptions},
+};
+
+pub struct MessageWidget {
+    body_texts: Vec<String>,
+    author: String,
+    is_mod: bool,
+    created: DateTime<Utc>,
+    /// What `created` is shown relative to
+    now: DateTime<Utc>,
+}
+
+impl MessageWidget {
+    pub fn new(
+        message: &Message,
+        container_width: u16,
+        now: DateTime<Utc>,
+        wrap_options: WrapOptions,
+    ) -> Self {
+        let width = container_width.saturating_sub(1);
+        Self {
+            body_texts: text::wrap(&message.body, width, wrap_options),
+            author: message.author.clone(),
+            is_mod: message.is_mod,
+            created: message.created_at,
+            now,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.body_texts.len() + 2
+    }
+}
+
+impl Widget for MessageWidget {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let mut author = format!("u/{}", self.author).bold();
+        if self.is_mod {
+            author = author.fg(Color::Green);
+        }
+        let lines: Vec<Line> = self.body_texts.into_iter().map(Line::from).collect();
+        Paragraph::new(lines)
+            .block(
+                Block::new()
+                    .borders(Borders::LEFT | Borders::BOTTOM)
+                    .border_type(BorderType::Rounded)
+                    .title(Line::from(vec![
+                        author,
+                        format!(" • {}", HumanTime::from(self.created - self.now)).italic(),
+                    ])),
+            )
+            .render(area, buf);
+    }
+}
@@ -1 +1,10 @@
 pub mod comment_widget;
+pub mod diagnostics_widget;
+pub mod link_card_widget;
+pub mod live_update_widget;
+pub mod media_widget;
+pub mod message_widget;
+pub mod poll_widget;
+pub mod status_line_widget;
+pub mod sub_header_widget;
+pub mod virtual_scroll;
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use chrono_humanize::HumanTime;
+use ratatui::{
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Paragraph, Widget},
+};
+
+use crate::{
+    model::poll::Poll,
+    text::{self, WrapOptions},
+    theme::Theme,
+};
+
+pub struct PollWidget {
+    lines: Vec<Line<'static>>,
+    footer: String,
+}
+
+impl PollWidget {
+    pub fn new(
+        poll: &Poll,
+        container_width: u16,
+        theme: Theme,
+        now: DateTime<Utc>,
+        wrap_options: WrapOptions,
+    ) -> Self {
+        let width = container_width.saturating_sub(2); // block border
+        let count_width = text::width(&poll.total_vote_count.to_string()) + 8; // " {count} (100%)"
+        let bar_width = width.saturating_sub(count_width) as usize;
+
+        let mut lines = Vec::new();
+        for option in &poll.options {
+            lines.extend(
+                text::wrap(&option.text, width, wrap_options)
+                    .into_iter()
+                    .map(|v| Line::from(v).bold()),
+            );
+            if let Some(vote_count) = option.vote_count {
+                let ratio = if poll.total_vote_count > 0 {
+                    vote_count as f64 / poll.total_vote_count as f64
+                } else {
+                    0.0
+                };
+                let filled = ((ratio * bar_width as f64).round() as usize).min(bar_width);
+                lines.push(Line::from(vec![
+                    "█".repeat(filled).fg(Color::Cyan),
+                    "░".repeat(bar_width - filled).fg(theme.muted()),
+                    format!(" {} ({:.0}%)", vote_count, ratio * 100.0).into(),
+                ]));
+            }
+        }
+
+        let mut footer = format!("{} votes", poll.total_vote_count);
+        if let Some(voting_end_at) = poll.voting_end_at {
+            let ends = HumanTime::from(voting_end_at - now);
+            if voting_end_at > now {
+                footer.push_str(&format!(" • voting ends {}", ends));
+            } else {
+                footer.push_str(&format!(" • voting ended {}", ends));
+            }
+        }
+
+        Self { lines, footer }
+    }
+
+    pub fn height(&self) -> usize {
+        self.lines.len() + 2
+    }
+}
+
+impl Widget for PollWidget {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        Paragraph::new(self.lines)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("Poll")
+                    .title_bottom(self.footer.italic()),
+            )
+            .render(area, buf);
+    }
+}
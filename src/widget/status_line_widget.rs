@@ -0,0 +1,75 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::Widget,
+};
+
+use crate::{component::StatusInfo, format::Clock};
+
+/// Splits the format, what's after it is aligned to the right like vim's `%=`
+const ALIGN_RIGHT: &str = "{=}";
+
+/// Bottom row showing the `status_line` format with its placeholders filled in from the screen:
+/// `{sub}`, `{sort}`, `{position}`, `{unread}` and `{clock}`
+pub struct StatusLineWidget<'a> {
+    format: &'a str,
+    info: StatusInfo,
+    clock: Clock,
+}
+
+impl<'a> StatusLineWidget<'a> {
+    pub fn new(format: &'a str, info: StatusInfo, clock: Clock) -> Self {
+        Self {
+            format,
+            info,
+            clock,
+        }
+    }
+
+    /// Whether the format shows the time, so the line needs redrawing as it changes
+    pub fn shows_clock(format: &str) -> bool {
+        format.contains("{clock}")
+    }
+
+    fn expand(&self, format: &str) -> String {
+        let info = &self.info;
+        let position = info
+            .position
+            .map(|(selected, count)| format!("{}/{}", selected, count));
+        format
+            .replace(
+                "{sub}",
+                &info
+                    .sub
+                    .as_deref()
+                    .map(|v| format!("r/{}", v))
+                    .unwrap_or_default(),
+            )
+            .replace("{sort}", info.sort.as_deref().unwrap_or_default())
+            .replace("{position}", &position.unwrap_or_default())
+            .replace(
+                "{unread}",
+                &info.unread.map(|v| v.to_string()).unwrap_or_default(),
+            )
+            .replace(
+                "{clock}",
+                &self.clock.local_now().format("%H:%M").to_string(),
+            )
+    }
+}
+
+impl Widget for StatusLineWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (left, right) = self
+            .format
+            .split_once(ALIGN_RIGHT)
+            .unwrap_or((self.format, ""));
+        let left = self.expand(left);
+        let right = self.expand(right);
+        buf.set_style(area, Style::new().reversed());
+        Line::from(left).render(area, buf);
+        Line::from(right).right_aligned().render(area, buf);
+    }
+}
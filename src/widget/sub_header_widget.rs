@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::{format, model::subreddit::SubredditAbout, text, theme::Theme};
+
+/// Strip above the post list with the sub's title, size and description, the icon is drawn in
+/// `icon_area` by the caller
+pub struct SubHeaderWidget<'a> {
+    about: &'a SubredditAbout,
+    has_icon: bool,
+    compact_scores: bool,
+    theme: Theme,
+}
+
+impl<'a> SubHeaderWidget<'a> {
+    /// Lines of text, and the icon's height
+    pub const HEIGHT: u16 = 3;
+    const ICON_WIDTH: u16 = 6;
+
+    pub fn new(
+        about: &'a SubredditAbout,
+        has_icon: bool,
+        compact_scores: bool,
+        theme: Theme,
+    ) -> Self {
+        Self {
+            about,
+            has_icon,
+            compact_scores,
+            theme,
+        }
+    }
+
+    fn areas(&self, area: Rect) -> [Rect; 2] {
+        let icon_width = if self.has_icon {
+            Self::ICON_WIDTH + 1
+        } else {
+            0
+        };
+        Layout::horizontal([Constraint::Length(icon_width), Constraint::Fill(1)]).areas(area)
+    }
+
+    pub fn icon_area(&self, area: Rect) -> Rect {
+        let [icon_area, _] = self.areas(area);
+        Rect {
+            width: icon_area.width.saturating_sub(1),
+            ..icon_area
+        }
+    }
+}
+
+impl Widget for SubHeaderWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [_, text_area] = self.areas(area);
+        let about = self.about;
+        let color = about.color.as_deref().and_then(|v| Color::from_str(v).ok());
+        let title_style = color.map_or(Style::new(), |v| Style::new().fg(v)).bold();
+
+        let mut counts = Vec::new();
+        if let Some(subscribers) = about.subscribers {
+            counts.push(format!(
+                "{} members",
+                format::score(subscribers as i64, self.compact_scores)
+            ));
+        }
+        if let Some(active_users) = about.active_users {
+            counts.push(format!(
+                "{} online",
+                format::score(active_users as i64, self.compact_scores)
+            ));
+        }
+        let description = about.description.lines().next().unwrap_or_default();
+        let lines = [
+            Line::styled(about.title.clone(), title_style),
+            Line::from(counts.join(" • ")).italic(),
+            Line::from(description.to_string()).fg(self.theme.muted()),
+        ]
+        .map(|line| {
+            if line.width() > text_area.width as usize {
+                let text: String = line.spans.iter().map(|v| v.content.as_ref()).collect();
+                Line::styled(text::ellipsize(&text, text_area.width), line.style)
+            } else {
+                line
+            }
+        });
+        Paragraph::new(lines.to_vec()).render(text_area, buf);
+    }
+}
@@ -0,0 +1,165 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget},
+};
+
+/// Scroll position of a `VirtualScroll`, clamped to the content once it has been drawn
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualScrollState {
+    offset: u16,
+    content_height: u16,
+    page_height: u16,
+    /// Lines moved by `scroll_down`/`scroll_up`, the config's `scroll_step`
+    step: u16,
+}
+
+impl VirtualScrollState {
+    pub fn new(step: u16) -> Self {
+        Self {
+            offset: 0,
+            content_height: 0,
+            page_height: 0,
+            step: step.max(1),
+        }
+    }
+
+    pub fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: u16) {
+        self.offset = offset;
+        self.clamp();
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.set_offset(self.offset.saturating_add(self.step));
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.set_offset(self.offset.saturating_sub(self.step));
+    }
+
+    pub fn scroll_half_page_down(&mut self) {
+        self.set_offset(self.offset.saturating_add((self.page_height / 2).max(1)));
+    }
+
+    pub fn scroll_half_page_up(&mut self) {
+        self.set_offset(self.offset.saturating_sub((self.page_height / 2).max(1)));
+    }
+
+    /// Keeps one line of the previous page in view
+    pub fn scroll_page_down(&mut self) {
+        let page = self.page_height.saturating_sub(1).max(1);
+        self.set_offset(self.offset.saturating_add(page));
+    }
+
+    pub fn scroll_page_up(&mut self) {
+        let page = self.page_height.saturating_sub(1).max(1);
+        self.set_offset(self.offset.saturating_sub(page));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        // Clamped to the last page here, or by the next draw when nothing was drawn yet
+        self.set_offset(u16::MAX);
+    }
+
+    fn clamp(&mut self) {
+        // Unknown before the first draw, the draw clamps it then
+        if self.page_height == 0 {
+            return;
+        }
+        self.offset = self
+            .offset
+            .min(self.content_height.saturating_sub(self.page_height));
+    }
+}
+
+/// A scrollable column that only draws what's in view. The caller lays items out in content
+/// coordinates, (0, 0) being the top left of the content, and each visible item is rendered into a
+/// buffer of its own size, so nothing as big as the whole content is ever allocated
+pub struct VirtualScroll<'a> {
+    viewport: Rect,
+    offset: u16,
+    buf: &'a mut Buffer,
+}
+
+impl<'a> VirtualScroll<'a> {
+    /// The last column of `area` is kept for the scrollbar when `show_scrollbar` is set
+    pub fn new(
+        area: Rect,
+        content_height: u16,
+        show_scrollbar: bool,
+        state: &mut VirtualScrollState,
+        buf: &'a mut Buffer,
+    ) -> Self {
+        state.content_height = content_height;
+        state.page_height = area.height;
+        state.clamp();
+
+        let mut viewport = area;
+        if show_scrollbar {
+            viewport.width = viewport.width.saturating_sub(1);
+            let overflow = content_height.saturating_sub(area.height);
+            if overflow > 0 {
+                let mut scrollbar_state =
+                    ScrollbarState::new(overflow as usize).position(state.offset as usize);
+                Scrollbar::new(ScrollbarOrientation::VerticalRight).render(
+                    area,
+                    buf,
+                    &mut scrollbar_state,
+                );
+            }
+        }
+        Self {
+            viewport,
+            offset: state.offset,
+            buf,
+        }
+    }
+
+    pub fn is_visible(&self, area: Rect) -> bool {
+        area.bottom() > self.offset && area.top() < self.offset + self.viewport.height
+    }
+
+    /// Where the top left of the part of `area` in view is on screen, `None` when out of view
+    pub fn screen_position(&self, area: Rect) -> Option<Position> {
+        self.is_visible(area).then(|| {
+            Position::new(
+                self.viewport.x + area.x,
+                self.viewport.y + area.top().max(self.offset) - self.offset,
+            )
+        })
+    }
+
+    pub fn render_widget<W: Widget>(&mut self, widget: W, area: Rect) {
+        self.render_with(area, |area, buf| widget.render(area, buf));
+    }
+
+    /// Render with `render` into a buffer covering `area`, then copy the rows in view
+    pub fn render_with(&mut self, area: Rect, render: impl FnOnce(Rect, &mut Buffer)) {
+        if area.is_empty() || !self.is_visible(area) {
+            return;
+        }
+        let mut item_buf = Buffer::empty(area);
+        render(area, &mut item_buf);
+
+        let top = area.top().max(self.offset);
+        let bottom = area.bottom().min(self.offset + self.viewport.height);
+        let right = area.right().min(self.viewport.width);
+        for y in top..bottom {
+            for x in area.left()..right {
+                let position =
+                    Position::new(self.viewport.x + x, self.viewport.y + y - self.offset);
+                if let Some(cell) = self.buf.cell_mut(position) {
+                    *cell = item_buf[(x, y)].clone();
+                }
+            }
+        }
+    }
+}